@@ -0,0 +1,22 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// Deflates the bundled Unicode data, which is mostly repetitive names and
+// would otherwise take up more of the binary than the code
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    for name in ["unicode-names.txt", "cldr-annotations-en.xml"] {
+        let source = Path::new("assets").join(name);
+        println!("cargo:rerun-if-changed={}", source.display());
+
+        let data = fs::read(&source).unwrap_or_else(|e| panic!("{}: {}", source.display(), e));
+        let compressed = miniz_oxide::deflate::compress_to_vec(&data, 10);
+        fs::write(
+            Path::new(&out_dir).join(format!("{}.deflate", name)),
+            compressed,
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,381 @@
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::sqlite::sqlite_json_copy;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+// Loaded once per launch; copying places.sqlite on every keystroke is too slow
+static BOOKMARKS: OnceLock<Vec<Bookmark>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub title: String,
+    pub url: String,
+    pub folder: String, // Slash-separated folder path, e.g. "Bookmarks bar/Dev"
+}
+
+/// Host of a URL without `www.`, for the hint line
+pub fn domain(url: &str) -> String {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.rsplit_once('@').map(|(_, h)| h).unwrap_or(host);
+    // Keep IPv6 literals intact, drop a port otherwise
+    let host = if host.starts_with('[') {
+        host.split_inclusive(']').next().unwrap_or(host)
+    } else {
+        host.split(':').next().unwrap_or(host)
+    };
+    host.strip_prefix("www.").unwrap_or(host).to_lowercase()
+}
+
+fn collect_chromium(node: &serde_json::Value, folder: &str, bookmarks: &mut Vec<Bookmark>) {
+    let name = node
+        .get("name")
+        .and_then(|n| n.as_str())
+        .unwrap_or_default();
+    match node.get("type").and_then(|t| t.as_str()) {
+        Some("url") => {
+            if let Some(url) = node.get("url").and_then(|u| u.as_str()) {
+                bookmarks.push(Bookmark {
+                    title: if name.is_empty() { url } else { name }.to_string(),
+                    url: url.to_string(),
+                    folder: folder.to_string(),
+                });
+            }
+        }
+        Some("folder") => {
+            let path = if folder.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}/{}", folder, name)
+            };
+            for child in node
+                .get("children")
+                .and_then(|c| c.as_array())
+                .into_iter()
+                .flatten()
+            {
+                collect_chromium(child, &path, bookmarks);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parses a Chromium-family `Bookmarks` file
+pub fn parse_chromium_bookmarks(json: &str) -> Vec<Bookmark> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+
+    let mut bookmarks = Vec::new();
+    if let Some(roots) = value.get("roots").and_then(|r| r.as_object()) {
+        for root in roots.values() {
+            collect_chromium(root, "", &mut bookmarks);
+        }
+    }
+    bookmarks
+}
+
+/// The query run against `places.sqlite`: folders (type 2), to build each
+/// bookmark's folder path from, and bookmarks (type 1) except smart folders,
+/// which use `place:` URLs
+pub const FIREFOX_QUERY: &str = "SELECT b.id AS id, b.parent AS parent, b.type AS type, \
+     b.guid AS guid, b.title AS title, p.url AS url \
+     FROM moz_bookmarks b \
+     LEFT JOIN moz_places p ON b.fk = p.id \
+     WHERE b.type = 2 OR (b.type = 1 AND p.url NOT LIKE 'place:%')";
+
+// Firefox's built-in folders are titled "toolbar", "menu", ... in the
+// database; these are the names its UI shows
+const FIREFOX_ROOTS: &[(&str, &str)] = &[
+    ("toolbar_____", "Bookmarks Toolbar"),
+    ("menu________", "Bookmarks Menu"),
+    ("unfiled_____", "Other Bookmarks"),
+    ("mobile______", "Mobile Bookmarks"),
+];
+
+const FIREFOX_ROOT_GUID: &str = "root________";
+// Tags are folders of bookmark copies under this one, not bookmarks
+const FIREFOX_TAGS_GUID: &str = "tags________";
+
+/// Parses `sqlite3 -json` output of [`FIREFOX_QUERY`]
+pub fn parse_firefox_bookmarks(json: &str) -> Vec<Bookmark> {
+    let Ok(rows) = serde_json::from_str::<Vec<serde_json::Value>>(json) else {
+        return Vec::new();
+    };
+    let text = |row: &serde_json::Value, key: &str| {
+        row.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let number = |row: &serde_json::Value, key: &str| row.get(key).and_then(|v| v.as_i64());
+
+    let tags = rows
+        .iter()
+        .find(|row| text(row, "guid") == FIREFOX_TAGS_GUID)
+        .and_then(|row| number(row, "id"));
+    // Folder id -> parent id and name
+    let folders: HashMap<i64, (Option<i64>, String)> = rows
+        .iter()
+        .filter(|row| number(row, "type") == Some(2))
+        .filter_map(|row| {
+            let guid = text(row, "guid");
+            if guid == FIREFOX_ROOT_GUID {
+                return None;
+            }
+            let name = FIREFOX_ROOTS
+                .iter()
+                .find(|(root, _)| *root == guid)
+                .map_or_else(|| text(row, "title"), |(_, name)| name.to_string());
+            Some((number(row, "id")?, (number(row, "parent"), name)))
+        })
+        .collect();
+    // None for the copies kept under a tag
+    let folder_path = |mut id: Option<i64>| {
+        let mut names = Vec::new();
+        // Bounded in case the parents form a loop
+        while let Some((parent, name)) = id.and_then(|id| folders.get(&id)) {
+            if id == tags {
+                return None;
+            }
+            if names.len() == folders.len() {
+                break;
+            }
+            names.push(name.as_str());
+            id = *parent;
+        }
+        names.reverse();
+        Some(names.join("/"))
+    };
+
+    rows.iter()
+        .filter(|row| number(row, "type") == Some(1))
+        .filter_map(|row| {
+            let url = text(row, "url");
+            if url.is_empty() {
+                return None;
+            }
+            let folder = folder_path(number(row, "parent"))?;
+            let title = text(row, "title");
+            Some(Bookmark {
+                title: if title.is_empty() { url.clone() } else { title },
+                url,
+                folder,
+            })
+        })
+        .collect()
+}
+
+/// Every `Bookmarks` file below the Chromium-family browser directories
+fn chromium_bookmark_files() -> Vec<PathBuf> {
+    let Some(config_dir) = dirs::config_dir() else {
+        return Vec::new();
+    };
+    let browsers = [
+        // macOS
+        "Google/Chrome",
+        "Google/Chrome Beta",
+        "Chromium",
+        "BraveSoftware/Brave-Browser",
+        "Microsoft Edge",
+        "Vivaldi",
+        "Arc/User Data",
+        // Linux
+        "google-chrome",
+        "chromium",
+        "microsoft-edge",
+        "vivaldi",
+    ];
+
+    let mut files = Vec::new();
+    for browser in browsers {
+        let Ok(profiles) = fs::read_dir(config_dir.join(browser)) else {
+            continue;
+        };
+        // Profiles are "Default", "Profile 1", ...
+        for profile in profiles.flatten() {
+            let file = profile.path().join("Bookmarks");
+            if file.is_file() {
+                files.push(file);
+            }
+        }
+    }
+    files
+}
+
+fn firefox_places_files() -> Vec<PathBuf> {
+    let roots = [
+        dirs::config_dir().map(|d| d.join("Firefox").join("Profiles")),
+        dirs::home_dir().map(|d| d.join(".mozilla").join("firefox")),
+    ];
+    roots
+        .into_iter()
+        .flatten()
+        .filter_map(|root| fs::read_dir(root).ok())
+        .flat_map(|profiles| profiles.flatten().map(|p| p.path().join("places.sqlite")))
+        .filter(|path: &PathBuf| path.is_file())
+        .collect()
+}
+
+fn load_firefox(places: &Path) -> Vec<Bookmark> {
+    sqlite_json_copy(places, FIREFOX_QUERY)
+        .map(|json| parse_firefox_bookmarks(&json))
+        .unwrap_or_default()
+}
+
+pub fn load_bookmarks() -> Vec<Bookmark> {
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+
+    for file in chromium_bookmark_files() {
+        if let Ok(json) = fs::read_to_string(&file) {
+            bookmarks.extend(parse_chromium_bookmarks(&json));
+        }
+    }
+    for places in firefox_places_files() {
+        bookmarks.extend(load_firefox(&places));
+    }
+
+    // The same page bookmarked in several browsers shows once
+    let mut seen = std::collections::HashSet::new();
+    bookmarks.retain(|b| seen.insert(b.url.clone()));
+    bookmarks
+}
+
+fn bookmark_to_result(bookmark: &Bookmark) -> SearchResult {
+    SearchResult::new(
+        bookmark.title.clone(),
+        bookmark.url.clone(),
+        SearchMode::Bookmarks,
+    )
+    .with_hint(&domain(&bookmark.url))
+    .with_alt_action("Copy URL", ResultAction::Copy(bookmark.url.clone()))
+}
+
+pub fn search_bookmarks(query: &str) -> Vec<SearchResult> {
+    let bookmarks = BOOKMARKS.get_or_init(load_bookmarks);
+
+    if query.is_empty() {
+        return bookmarks.iter().take(15).map(bookmark_to_result).collect();
+    }
+
+    let matcher = get_matcher();
+    let mut scored: Vec<_> = bookmarks
+        .iter()
+        .filter_map(|bookmark| {
+            // Titles matter most, then the folder, then anywhere in the URL
+            let title = matcher.fuzzy_match(&bookmark.title, query).map(|s| s * 3);
+            let folder = matcher.fuzzy_match(&bookmark.folder, query).map(|s| s * 2);
+            let url = matcher.fuzzy_match(&bookmark.url, query);
+            title.max(folder).max(url).map(|score| (bookmark, score))
+        })
+        .collect();
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(bookmark, _)| bookmark_to_result(bookmark))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(title: &str, url: &str, folder: &str) -> Bookmark {
+        Bookmark {
+            title: title.to_string(),
+            url: url.to_string(),
+            folder: folder.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_chromium_bookmarks() {
+        let json = include_str!("../tests/fixtures/bookmarks/chromium-Bookmarks.json");
+        assert_eq!(
+            parse_chromium_bookmarks(json),
+            [
+                bookmark(
+                    "The Rust Programming Language",
+                    "https://www.rust-lang.org/",
+                    "Bookmarks bar/Dev/Rust"
+                ),
+                bookmark(
+                    "serde - Rust",
+                    "https://docs.rs/serde/latest/serde/",
+                    "Bookmarks bar/Dev/Rust"
+                ),
+                // Untitled bookmarks show their URL
+                bookmark(
+                    "https://User:pw@Example.COM:8443/path?q=1#top",
+                    "https://User:pw@Example.COM:8443/path?q=1#top",
+                    "Other bookmarks"
+                ),
+            ]
+        );
+        assert!(parse_chromium_bookmarks("{not json").is_empty());
+    }
+
+    #[test]
+    fn parses_firefox_bookmarks_with_folder_paths() {
+        let json = include_str!("../tests/fixtures/bookmarks/firefox-places.json");
+        assert_eq!(
+            parse_firefox_bookmarks(json),
+            [
+                bookmark(
+                    "The Rust Programming Language",
+                    "https://www.rust-lang.org/",
+                    "Bookmarks Toolbar/Dev/Rust"
+                ),
+                bookmark(
+                    "serde - Rust",
+                    "https://docs.rs/serde/latest/serde/",
+                    "Bookmarks Toolbar/Dev/Rust"
+                ),
+                bookmark(
+                    "Hacker News",
+                    "https://news.ycombinator.com/",
+                    "Other Bookmarks"
+                ),
+                bookmark(
+                    "http://[::1]:8080/admin",
+                    "http://[::1]:8080/admin",
+                    "Bookmarks Menu"
+                ),
+            ]
+        );
+        assert!(parse_firefox_bookmarks("").is_empty());
+    }
+
+    #[test]
+    fn firefox_folder_loops_end() {
+        let json = r#"[{"id":2,"parent":3,"type":2,"guid":"a","title":"A","url":null},
+            {"id":3,"parent":2,"type":2,"guid":"b","title":"B","url":null},
+            {"id":4,"parent":2,"type":1,"guid":"c","title":"x","url":"https://x.org/"}]"#;
+        assert_eq!(parse_firefox_bookmarks(json)[0].folder, "B/A");
+    }
+
+    #[test]
+    fn domains_drop_www_ports_and_credentials() {
+        assert_eq!(domain("https://www.rust-lang.org/learn"), "rust-lang.org");
+        assert_eq!(
+            domain("https://User:pw@Example.COM:8443/path?q=1#top"),
+            "example.com"
+        );
+        assert_eq!(domain("http://[::1]:8080/admin"), "[::1]");
+        assert_eq!(domain("example.org?q=a/b"), "example.org");
+        assert_eq!(domain("file:///home/ada/notes.html"), "");
+    }
+}
@@ -0,0 +1,604 @@
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::units::convert;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(usize, usize), // byte range into the source string
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    Bang,
+    LParen,
+    RParen,
+    Comma,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputBase {
+    Dec,
+    Hex,
+    Bin,
+    Oct,
+}
+
+impl OutputBase {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "dec" | "decimal" => Some(OutputBase::Dec),
+            "hex" | "hexadecimal" => Some(OutputBase::Hex),
+            "bin" | "binary" => Some(OutputBase::Bin),
+            "oct" | "octal" => Some(OutputBase::Oct),
+            _ => None,
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        match c {
+            b' ' | b'\t' => i += 1,
+            b'+' => {
+                tokens.push(Token::Plus);
+                i += 1;
+            }
+            b'-' => {
+                tokens.push(Token::Minus);
+                i += 1;
+            }
+            b'*' if bytes.get(i + 1) == Some(&b'*') => {
+                tokens.push(Token::Caret);
+                i += 2;
+            }
+            b'*' => {
+                tokens.push(Token::Star);
+                i += 1;
+            }
+            b'/' => {
+                tokens.push(Token::Slash);
+                i += 1;
+            }
+            b'%' => {
+                tokens.push(Token::Percent);
+                i += 1;
+            }
+            b'^' => {
+                tokens.push(Token::Caret);
+                i += 1;
+            }
+            b'!' => {
+                tokens.push(Token::Bang);
+                i += 1;
+            }
+            b'(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            b')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            b',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            b'0'..=b'9' | b'.' => {
+                let (value, len) = parse_number(&input[i..])?;
+                tokens.push(Token::Number(value));
+                i += len;
+            }
+            _ if c.is_ascii_alphabetic() || c == b'_' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(start, i));
+            }
+            _ => {
+                // Non-ASCII operators and constants
+                let ch = input[i..].chars().next()?;
+                let token = match ch {
+                    '×' | '·' => Token::Star,
+                    '÷' => Token::Slash,
+                    '−' => Token::Minus,
+                    'π' => Token::Number(std::f64::consts::PI),
+                    'τ' => Token::Number(std::f64::consts::TAU),
+                    _ => return None,
+                };
+                tokens.push(token);
+                i += ch.len_utf8();
+            }
+        }
+    }
+
+    Some(tokens)
+}
+
+/// Parses a numeric literal at the start of `s`, returning its value and byte length.
+/// Supports `0x`/`0b`/`0o` prefixes, decimal fractions, exponents and `_` separators.
+fn parse_number(s: &str) -> Option<(f64, usize)> {
+    let bytes = s.as_bytes();
+
+    if bytes.len() > 2 && bytes[0] == b'0' {
+        let radix = match bytes[1] {
+            b'x' | b'X' => Some(16),
+            b'b' | b'B' => Some(2),
+            b'o' | b'O' => Some(8),
+            _ => None,
+        };
+        if let Some(radix) = radix {
+            let len = 2 + s[2..]
+                .bytes()
+                .take_while(|b| b.is_ascii_alphanumeric() || *b == b'_')
+                .count();
+            let digits: String = s[2..len].chars().filter(|c| *c != '_').collect();
+            let value = u64::from_str_radix(&digits, radix).ok()?;
+            return Some((value as f64, len));
+        }
+    }
+
+    let mut len = 0;
+    while len < bytes.len()
+        && (bytes[len].is_ascii_digit() || bytes[len] == b'.' || bytes[len] == b'_')
+    {
+        len += 1;
+    }
+    // Exponent part: 1e3, 2.5E-4
+    if len < bytes.len() && (bytes[len] == b'e' || bytes[len] == b'E') {
+        let mut exp_len = len + 1;
+        if exp_len < bytes.len() && (bytes[exp_len] == b'+' || bytes[exp_len] == b'-') {
+            exp_len += 1;
+        }
+        if exp_len < bytes.len() && bytes[exp_len].is_ascii_digit() {
+            while exp_len < bytes.len() && bytes[exp_len].is_ascii_digit() {
+                exp_len += 1;
+            }
+            len = exp_len;
+        }
+    }
+
+    let literal: String = s[..len].chars().filter(|c| *c != '_').collect();
+    literal.parse::<f64>().ok().map(|value| (value, len))
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    fn ident(&self, start: usize, end: usize) -> String {
+        self.source[start..end].to_lowercase()
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.pos += 1;
+                    value += self.term()?;
+                }
+                Some(Token::Minus) => {
+                    self.pos += 1;
+                    value -= self.term()?;
+                }
+                _ => return Some(value),
+            }
+        }
+    }
+
+    // term := unary (('*' | '/' | '%' | 'mod') unary)*
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.pos += 1;
+                    value *= self.unary()?;
+                }
+                Some(Token::Slash) => {
+                    self.pos += 1;
+                    value /= self.unary()?;
+                }
+                Some(Token::Percent) => {
+                    self.pos += 1;
+                    value %= self.unary()?;
+                }
+                Some(Token::Ident(start, end)) if self.ident(start, end) == "mod" => {
+                    self.pos += 1;
+                    value %= self.unary()?;
+                }
+                _ => return Some(value),
+            }
+        }
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Option<f64> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.pos += 1;
+                Some(-self.unary()?)
+            }
+            Some(Token::Plus) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    // power := postfix ('^' unary)?   (right associative, binds tighter than unary minus)
+    fn power(&mut self) -> Option<f64> {
+        let base = self.postfix()?;
+        if self.peek() == Some(Token::Caret) {
+            self.pos += 1;
+            let exponent = self.unary()?;
+            return Some(base.powf(exponent));
+        }
+        Some(base)
+    }
+
+    // postfix := primary '!'*
+    fn postfix(&mut self) -> Option<f64> {
+        let mut value = self.primary()?;
+        while self.peek() == Some(Token::Bang) {
+            self.pos += 1;
+            value = factorial(value)?;
+        }
+        Some(value)
+    }
+
+    fn primary(&mut self) -> Option<f64> {
+        match self.next()? {
+            Token::Number(value) => Some(value),
+            Token::LParen => {
+                let value = self.expr()?;
+                match self.next() {
+                    Some(Token::RParen) => Some(value),
+                    _ => None,
+                }
+            }
+            Token::Ident(start, end) => {
+                let name = self.ident(start, end);
+                if let Some(value) = constant(&name) {
+                    return Some(value);
+                }
+                let args = if self.peek() == Some(Token::LParen) {
+                    self.pos += 1;
+                    self.arguments()?
+                } else {
+                    // Allow `sqrt 16` without parentheses
+                    vec![self.unary()?]
+                };
+                call_function(&name, &args)
+            }
+            _ => None,
+        }
+    }
+
+    // Parses a comma separated argument list after the opening parenthesis
+    fn arguments(&mut self) -> Option<Vec<f64>> {
+        let mut args = Vec::new();
+        if self.peek() == Some(Token::RParen) {
+            self.pos += 1;
+            return Some(args);
+        }
+        loop {
+            args.push(self.expr()?);
+            match self.next()? {
+                Token::Comma => continue,
+                Token::RParen => return Some(args),
+                _ => return None,
+            }
+        }
+    }
+}
+
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" => Some(std::f64::consts::PI),
+        "e" => Some(std::f64::consts::E),
+        "tau" => Some(std::f64::consts::TAU),
+        "phi" => Some(1.618_033_988_749_895),
+        "inf" => Some(f64::INFINITY),
+        _ => None,
+    }
+}
+
+fn call_function(name: &str, args: &[f64]) -> Option<f64> {
+    let one = |f: fn(f64) -> f64| match args {
+        [x] => Some(f(*x)),
+        _ => None,
+    };
+
+    match name {
+        "sqrt" => one(f64::sqrt),
+        "cbrt" => one(f64::cbrt),
+        "abs" => one(f64::abs),
+        "sin" => one(f64::sin),
+        "cos" => one(f64::cos),
+        "tan" => one(f64::tan),
+        "asin" => one(f64::asin),
+        "acos" => one(f64::acos),
+        "atan" => one(f64::atan),
+        "sinh" => one(f64::sinh),
+        "cosh" => one(f64::cosh),
+        "tanh" => one(f64::tanh),
+        "ln" => one(f64::ln),
+        "log2" => one(f64::log2),
+        "log10" => one(f64::log10),
+        "exp" => one(f64::exp),
+        "floor" => one(f64::floor),
+        "ceil" => one(f64::ceil),
+        "round" => one(f64::round),
+        "trunc" => one(f64::trunc),
+        "deg" => one(f64::to_degrees),
+        "rad" => one(f64::to_radians),
+        "log" => match args {
+            [x] => Some(x.log10()),
+            [x, base] => Some(x.log(*base)),
+            _ => None,
+        },
+        "pow" => match args {
+            [x, y] => Some(x.powf(*y)),
+            _ => None,
+        },
+        "atan2" => match args {
+            [y, x] => Some(y.atan2(*x)),
+            _ => None,
+        },
+        "hypot" => match args {
+            [x, y] => Some(x.hypot(*y)),
+            _ => None,
+        },
+        "min" if !args.is_empty() => args.iter().copied().reduce(f64::min),
+        "max" if !args.is_empty() => args.iter().copied().reduce(f64::max),
+        _ => None,
+    }
+}
+
+fn factorial(n: f64) -> Option<f64> {
+    if n < 0.0 || n.fract() != 0.0 || n > 170.0 {
+        return None;
+    }
+    Some((1..=n as u64).fold(1.0, |acc, k| acc * k as f64))
+}
+
+/// Evaluates an arithmetic expression.
+///
+/// Supports `+ - * / % ^` (and `**`), parentheses, factorial, hex/bin/oct
+/// literals, constants (`pi`, `e`, `tau`, `phi`) and common functions.
+pub fn evaluate(expr: &str) -> Option<f64> {
+    let tokens = tokenize(expr)?;
+    if tokens.is_empty() {
+        return None;
+    }
+
+    let mut parser = Parser {
+        source: expr,
+        tokens,
+        pos: 0,
+    };
+    let value = parser.expr()?;
+
+    // Reject trailing garbage such as "2 3" or unbalanced ")"
+    if parser.pos != parser.tokens.len() || value.is_nan() {
+        return None;
+    }
+    Some(value)
+}
+
+/// Splits a trailing `in hex` / `to bin` style suffix off the query.
+fn split_output_base(query: &str) -> (&str, OutputBase) {
+    let trimmed = query.trim();
+    for keyword in [" in ", " to ", " as "] {
+        if let Some(pos) = trimmed.to_ascii_lowercase().rfind(keyword) {
+            if let Some(base) = OutputBase::from_name(trimmed[pos + keyword.len()..].trim()) {
+                return (&trimmed[..pos], base);
+            }
+        }
+    }
+    (trimmed, OutputBase::Dec)
+}
+
+/// Formats a value for display, trimming float noise such as `0.30000000000000004`.
+pub fn format_number(value: f64) -> String {
+    if value.is_infinite() {
+        return if value > 0.0 {
+            "∞".to_string()
+        } else {
+            "-∞".to_string()
+        };
+    }
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    let magnitude = value.abs();
+    if value.fract() == 0.0 && magnitude < 1e15 {
+        return format!("{}", value as i64);
+    }
+    if !(1e-6..1e15).contains(&magnitude) {
+        let formatted = format!("{:.10e}", value);
+        let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+        let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+        return format!("{}e{}", mantissa, exponent);
+    }
+
+    // Round to 12 significant digits
+    let decimals = (11 - magnitude.log10().floor() as i32).clamp(0, 15) as usize;
+    let formatted = format!("{:.*}", decimals, value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    if trimmed == "-0" {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn format_in_base(value: f64, base: OutputBase) -> Option<String> {
+    if base == OutputBase::Dec {
+        return Some(format_number(value));
+    }
+
+    let rounded = value.round();
+    if (value - rounded).abs() > 1e-9 || rounded.abs() >= u64::MAX as f64 {
+        return None;
+    }
+    let sign = if rounded < 0.0 { "-" } else { "" };
+    let n = rounded.abs() as u64;
+    let digits = match base {
+        OutputBase::Hex => format!("0x{:x}", n),
+        OutputBase::Bin => format!("0b{:b}", n),
+        OutputBase::Oct => format!("0o{:o}", n),
+        OutputBase::Dec => unreachable!(),
+    };
+    Some(format!("{}{}", sign, digits))
+}
+
+/// Evaluates a calculator query (with an optional `in <base>` suffix) and
+/// returns the formatted result.
+pub fn calculate(query: &str) -> Option<String> {
+    let (expr, base) = split_output_base(query);
+    let value = evaluate(expr)?;
+    format_in_base(value, base)
+}
+
+/// Whether an apps-mode query should also be treated as a calculation.
+///
+/// Bare numbers and lone words (e.g. "pi", an app called "e") are left alone;
+/// the query needs at least an operator, function call, base or unit conversion.
+pub fn is_expression(query: &str) -> bool {
+    let (expr, base) = split_output_base(query);
+    let calculation = match tokenize(expr) {
+        Some(tokens) => (tokens.len() > 1 || base != OutputBase::Dec) && calculate(query).is_some(),
+        None => false,
+    };
+    calculation || convert(query).is_some()
+}
+
+pub fn search_calculator(query: &str) -> Vec<SearchResult> {
+    if let Some(result) = calculate(query) {
+        return vec![
+            SearchResult::new(result.clone(), result.clone(), SearchMode::Calc)
+                .with_action(ResultAction::Copy(result)),
+        ];
+    }
+
+    // "5 km in mi": show the unit but only copy the number
+    match convert(query) {
+        Some(conversion) => vec![SearchResult::new(
+            format!("{} {}", conversion.value, conversion.unit),
+            conversion.value.clone(),
+            SearchMode::Calc,
+        )
+        .with_action(ResultAction::Copy(conversion.value))],
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precedence_and_right_associative_power() {
+        assert_eq!(evaluate("2 + 3 * 4"), Some(14.0));
+        assert_eq!(evaluate("(2 + 3) * 4"), Some(20.0));
+        assert_eq!(evaluate("10 - 4 - 3"), Some(3.0));
+        assert_eq!(evaluate("2 ^ 3 ^ 2"), Some(512.0));
+        assert_eq!(evaluate("2 ** 10"), Some(1024.0));
+        assert_eq!(evaluate("7 mod 4"), Some(3.0));
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(evaluate("-3 + 5"), Some(2.0));
+        assert_eq!(evaluate("--3"), Some(3.0));
+        assert_eq!(evaluate("2 * -3"), Some(-6.0));
+        // Power binds tighter than negation, as in maths notation
+        assert_eq!(evaluate("-2 ^ 2"), Some(-4.0));
+        assert_eq!(evaluate("2 ^ -1"), Some(0.5));
+    }
+
+    #[test]
+    fn factorial_limits() {
+        assert_eq!(evaluate("5!"), Some(120.0));
+        assert_eq!(evaluate("0!"), Some(1.0));
+        assert!(evaluate("170!").unwrap().is_finite());
+        assert_eq!(evaluate("171!"), None);
+        assert_eq!(evaluate("2.5!"), None);
+        assert_eq!(evaluate("(-1)!"), None);
+    }
+
+    #[test]
+    fn base_literals_and_output() {
+        assert_eq!(evaluate("0xff"), Some(255.0));
+        assert_eq!(evaluate("0b1010"), Some(10.0));
+        assert_eq!(evaluate("0o17"), Some(15.0));
+        assert_eq!(evaluate("1_000 + 0x1_0"), Some(1016.0));
+        assert_eq!(calculate("255 in hex").as_deref(), Some("0xff"));
+        assert_eq!(calculate("10 to bin").as_deref(), Some("0b1010"));
+        assert_eq!(calculate("-8 as oct").as_deref(), Some("-0o10"));
+        assert_eq!(calculate("0xff in dec").as_deref(), Some("255"));
+        assert_eq!(calculate("1.5 in hex"), None);
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(calculate("1 / 0").as_deref(), Some("∞"));
+        assert_eq!(calculate("-1 / 0").as_deref(), Some("-∞"));
+        assert_eq!(evaluate("0 / 0"), None);
+    }
+
+    #[test]
+    fn formatting_trims_float_noise() {
+        assert_eq!(calculate("0.1 + 0.2").as_deref(), Some("0.3"));
+        assert_eq!(calculate("2 ^ 64").as_deref(), Some("1.8446744074e19"));
+        assert_eq!(calculate("1 / 3").as_deref(), Some("0.333333333333"));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(evaluate("2 3"), None);
+        assert_eq!(evaluate("(1 + 2"), None);
+        assert_eq!(evaluate("1 + 2)"), None);
+        assert_eq!(evaluate(""), None);
+    }
+
+    #[test]
+    fn is_expression_leaves_app_names_alone() {
+        assert!(is_expression("2 + 2"));
+        assert!(is_expression("sqrt(16)"));
+        assert!(is_expression("255 in hex"));
+        assert!(!is_expression("42"));
+        assert!(!is_expression("pi"));
+        assert!(!is_expression("e"));
+        assert!(!is_expression("1Password"));
+        assert!(!is_expression("Office 365"));
+        assert!(!is_expression("7zip"));
+        assert!(!is_expression("Safari"));
+    }
+}
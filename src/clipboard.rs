@@ -0,0 +1,423 @@
+use std::io::{self, Read, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Plain-text access to a system clipboard
+pub trait Clipboard {
+    fn get_text(&self) -> Option<String>;
+    fn set_text(&self, text: &str) -> io::Result<()>;
+
+    /// Sets text that clipboard managers should not record, where the
+    /// platform has a way to say so
+    fn set_secret(&self, text: &str) -> io::Result<()> {
+        self.set_text(text)
+    }
+
+    /// Type identifiers advertised for the current contents (UTIs or MIME types)
+    fn content_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// A counter that moves with every copy, where the platform keeps one
+    fn change_count(&self) -> Option<isize> {
+        None
+    }
+
+    fn clear(&self) -> io::Result<()> {
+        self.set_text("")
+    }
+}
+
+/// The macOS general pasteboard
+#[cfg(target_os = "macos")]
+pub struct PasteboardClipboard;
+
+#[cfg(target_os = "macos")]
+impl PasteboardClipboard {
+    const PLAIN_TEXT: &'static str = "public.utf8-plain-text";
+    // See nspasteboard.org; our own clipd skips it as well
+    const CONCEALED: &'static str = "org.nspasteboard.ConcealedType";
+}
+
+#[cfg(target_os = "macos")]
+impl Clipboard for PasteboardClipboard {
+    fn get_text(&self) -> Option<String> {
+        use cocoa::base::{id, nil};
+        use cocoa::foundation::NSString;
+        use objc::{class, msg_send, sel, sel_impl};
+
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            let type_string = NSString::alloc(nil).init_str(Self::PLAIN_TEXT);
+            let text: id = msg_send![pasteboard, stringForType: type_string];
+            if text == nil {
+                return None;
+            }
+            let cstr: *const i8 = msg_send![text, UTF8String];
+            if cstr.is_null() {
+                return None;
+            }
+            Some(std::ffi::CStr::from_ptr(cstr).to_string_lossy().to_string())
+        }
+    }
+
+    fn set_text(&self, text: &str) -> io::Result<()> {
+        use cocoa::base::{id, nil};
+        use cocoa::foundation::NSString;
+        use objc::{class, msg_send, sel, sel_impl};
+
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            let _: isize = msg_send![pasteboard, clearContents];
+            let text_string = NSString::alloc(nil).init_str(text);
+            let type_string = NSString::alloc(nil).init_str(Self::PLAIN_TEXT);
+            let ok: bool = msg_send![pasteboard, setString: text_string forType: type_string];
+            if ok {
+                Ok(())
+            } else {
+                Err(io::Error::other("pasteboard rejected the text"))
+            }
+        }
+    }
+
+    fn set_secret(&self, text: &str) -> io::Result<()> {
+        use cocoa::base::{id, nil};
+        use cocoa::foundation::{NSArray, NSString};
+        use objc::{class, msg_send, sel, sel_impl};
+
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            let text_type = NSString::alloc(nil).init_str(Self::PLAIN_TEXT);
+            let concealed_type = NSString::alloc(nil).init_str(Self::CONCEALED);
+            let types = NSArray::arrayWithObjects(nil, &[text_type, concealed_type]);
+            let _: isize = msg_send![pasteboard, declareTypes: types owner: nil];
+
+            let text_string = NSString::alloc(nil).init_str(text);
+            let marker = NSString::alloc(nil).init_str("");
+            let ok: bool = msg_send![pasteboard, setString: text_string forType: text_type];
+            let _: bool = msg_send![pasteboard, setString: marker forType: concealed_type];
+            if ok {
+                Ok(())
+            } else {
+                Err(io::Error::other("pasteboard rejected the text"))
+            }
+        }
+    }
+
+    fn content_types(&self) -> Vec<String> {
+        use cocoa::base::{id, nil};
+        use cocoa::foundation::NSArray;
+        use objc::{class, msg_send, sel, sel_impl};
+
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            let types: id = msg_send![pasteboard, types];
+            if types == nil {
+                return Vec::new();
+            }
+            (0..types.count())
+                .filter_map(|i| {
+                    let item = types.objectAtIndex(i);
+                    let cstr: *const i8 = msg_send![item, UTF8String];
+                    (!cstr.is_null())
+                        .then(|| std::ffi::CStr::from_ptr(cstr).to_string_lossy().to_string())
+                })
+                .collect()
+        }
+    }
+
+    fn change_count(&self) -> Option<isize> {
+        use cocoa::base::id;
+        use objc::{class, msg_send, sel, sel_impl};
+
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            let count: isize = msg_send![pasteboard, changeCount];
+            Some(count)
+        }
+    }
+
+    fn clear(&self) -> io::Result<()> {
+        use cocoa::base::id;
+        use objc::{class, msg_send, sel, sel_impl};
+
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            let _: isize = msg_send![pasteboard, clearContents];
+        }
+        Ok(())
+    }
+}
+
+/// Wayland clipboard through `wl-copy` / `wl-paste`
+#[cfg(not(target_os = "macos"))]
+pub struct WaylandClipboard;
+
+#[cfg(not(target_os = "macos"))]
+impl Clipboard for WaylandClipboard {
+    fn get_text(&self) -> Option<String> {
+        read_command("wl-paste", &["--no-newline"])
+    }
+
+    fn set_text(&self, text: &str) -> io::Result<()> {
+        write_command("wl-copy", &[], text)
+    }
+
+    fn content_types(&self) -> Vec<String> {
+        read_lines("wl-paste", &["--list-types"])
+    }
+
+    fn clear(&self) -> io::Result<()> {
+        write_command("wl-copy", &["--clear"], "")
+    }
+}
+
+/// X11 clipboard selection through `xclip` or `xsel`
+#[cfg(not(target_os = "macos"))]
+pub struct X11Clipboard {
+    use_xsel: bool,
+}
+
+#[cfg(not(target_os = "macos"))]
+impl Clipboard for X11Clipboard {
+    fn get_text(&self) -> Option<String> {
+        if self.use_xsel {
+            read_command("xsel", &["--clipboard", "--output"])
+        } else {
+            read_command("xclip", &["-selection", "clipboard", "-out"])
+        }
+    }
+
+    fn set_text(&self, text: &str) -> io::Result<()> {
+        if self.use_xsel {
+            write_command("xsel", &["--clipboard", "--input"], text)
+        } else {
+            write_command("xclip", &["-selection", "clipboard", "-in"], text)
+        }
+    }
+
+    fn content_types(&self) -> Vec<String> {
+        // xsel cannot list targets
+        if self.use_xsel {
+            Vec::new()
+        } else {
+            read_lines(
+                "xclip",
+                &["-selection", "clipboard", "-t", "TARGETS", "-out"],
+            )
+        }
+    }
+}
+
+/// Process-local clipboard for tests
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Default)]
+pub struct MemoryClipboard {
+    contents: Mutex<Option<String>>,
+    types: Mutex<Vec<String>>,
+    changes: Mutex<isize>,
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+impl MemoryClipboard {
+    pub fn set_content_types(&self, types: &[&str]) {
+        *self.types.lock().unwrap() = types.iter().map(|t| t.to_string()).collect();
+    }
+}
+
+impl Clipboard for MemoryClipboard {
+    fn get_text(&self) -> Option<String> {
+        self.contents.lock().unwrap().clone()
+    }
+
+    fn set_text(&self, text: &str) -> io::Result<()> {
+        *self.contents.lock().unwrap() = Some(text.to_string());
+        *self.changes.lock().unwrap() += 1;
+        Ok(())
+    }
+
+    fn content_types(&self) -> Vec<String> {
+        self.types.lock().unwrap().clone()
+    }
+
+    fn change_count(&self) -> Option<isize> {
+        Some(*self.changes.lock().unwrap())
+    }
+
+    fn clear(&self) -> io::Result<()> {
+        *self.contents.lock().unwrap() = None;
+        *self.changes.lock().unwrap() += 1;
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_command(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_lines(program: &str, args: &[&str]) -> Vec<String> {
+    read_command(program, args)
+        .map(|out| out.lines().map(|l| l.trim().to_string()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn write_command(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    // The tools fork to keep serving the selection, so this returns promptly
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} exited with {}",
+            program, status
+        )))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn command_exists(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Picks the clipboard backend for the current session, failing when none of
+/// the clipboard tools is installed
+pub fn system_clipboard() -> io::Result<Box<dyn Clipboard>> {
+    #[cfg(target_os = "macos")]
+    {
+        Ok(Box::new(PasteboardClipboard))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let has_env = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+
+        if has_env("WAYLAND_DISPLAY") && command_exists("wl-copy") {
+            return Ok(Box::new(WaylandClipboard));
+        }
+        if has_env("DISPLAY") {
+            if command_exists("xclip") {
+                return Ok(Box::new(X11Clipboard { use_xsel: false }));
+            }
+            if command_exists("xsel") {
+                return Ok(Box::new(X11Clipboard { use_xsel: true }));
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no clipboard tool found, install wl-clipboard, xclip or xsel",
+        ))
+    }
+}
+
+/// Copies a secret and leaves a detached `rufi clip-clear` behind to clear
+/// it after `seconds`, since the launcher exits right after copying
+pub fn copy_secret(text: &str, seconds: u64) -> io::Result<()> {
+    let clipboard = system_clipboard()?;
+    clipboard.set_secret(text)?;
+
+    let mut command = Command::new(std::env::current_exe()?);
+    command.arg("clip-clear").arg(seconds.to_string());
+    if let Some(count) = clipboard.change_count() {
+        command.arg(count.to_string());
+    }
+    // Its own process group, so closing the launcher's terminal spares it.
+    // The secret goes through stdin, where `ps` cannot show it.
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Whether the clipboard still holds what was copied: by the change count
+/// where there is one, else by comparing the text
+fn still_holds(clipboard: &dyn Clipboard, secret: &str, change_count: Option<isize>) -> bool {
+    match (change_count, clipboard.change_count()) {
+        (Some(copied), Some(now)) => copied == now,
+        _ => clipboard.get_text().as_deref() == Some(secret),
+    }
+}
+
+/// Entry point for `rufi clip-clear <seconds> [change count]`, with the
+/// secret on stdin. Clears the clipboard unless something else was copied.
+pub fn run_clip_clear(args: &[String]) {
+    let seconds = args.first().and_then(|s| s.parse().ok()).unwrap_or(45);
+    let change_count = args.get(1).and_then(|c| c.parse().ok());
+    let mut secret = String::new();
+    if io::stdin().read_to_string(&mut secret).is_err() {
+        return;
+    }
+
+    std::thread::sleep(Duration::from_secs(seconds));
+    let Ok(clipboard) = system_clipboard() else {
+        return;
+    };
+    if still_holds(clipboard.as_ref(), &secret, change_count) {
+        let _ = clipboard.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_clipboard_round_trips() {
+        let clipboard = MemoryClipboard::default();
+        assert_eq!(clipboard.get_text(), None);
+        clipboard.set_text("hello").unwrap();
+        assert_eq!(clipboard.get_text().as_deref(), Some("hello"));
+        // Without a concealed type, secrets are plain text
+        clipboard.set_secret("hunter2").unwrap();
+        assert_eq!(clipboard.get_text().as_deref(), Some("hunter2"));
+        clipboard.set_content_types(&["text/plain"]);
+        assert_eq!(clipboard.content_types(), ["text/plain"]);
+    }
+
+    #[test]
+    fn clears_only_an_unchanged_clipboard() {
+        let clipboard = MemoryClipboard::default();
+        clipboard.set_secret("hunter2").unwrap();
+        let copied = clipboard.change_count();
+        assert!(still_holds(&clipboard, "hunter2", copied));
+        // Copying the same text again is still a newer copy
+        clipboard.set_text("hunter2").unwrap();
+        assert!(!still_holds(&clipboard, "hunter2", copied));
+        // Without a counter, only the text tells
+        assert!(still_holds(&clipboard, "hunter2", None));
+        clipboard.set_text("other").unwrap();
+        assert!(!still_holds(&clipboard, "hunter2", None));
+
+        clipboard.clear().unwrap();
+        assert_eq!(clipboard.get_text(), None);
+    }
+}
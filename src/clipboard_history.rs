@@ -0,0 +1,363 @@
+use crate::clipboard::{system_clipboard, Clipboard};
+use crate::config::ClipboardConfig;
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipEntry {
+    pub text: String,
+    pub pinned: bool,
+    pub copied_at: u64, // Unix seconds of the most recent copy
+}
+
+/// Recorded clipboard texts, most recent first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClipboardHistory {
+    pub entries: Vec<ClipEntry>,
+}
+
+impl ClipboardHistory {
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    /// Records a copy, moving duplicates to the front. Returns false when the
+    /// text was rejected (blank or over the size limit).
+    pub fn add(&mut self, text: &str, now: u64, config: &ClipboardConfig) -> bool {
+        if text.trim().is_empty() || text.len() > config.max_entry_bytes {
+            return false;
+        }
+
+        let pinned = match self.entries.iter().position(|e| e.text == text) {
+            Some(index) => self.entries.remove(index).pinned,
+            None => false,
+        };
+        self.entries.insert(
+            0,
+            ClipEntry {
+                text: text.to_string(),
+                pinned,
+                copied_at: now,
+            },
+        );
+
+        // Pinned entries never count towards the cap
+        let mut unpinned = 0;
+        self.entries.retain(|e| {
+            if e.pinned {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= config.max_entries
+        });
+        true
+    }
+
+    pub fn toggle_pin(&mut self, text: &str) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.text == text) {
+            entry.pinned = !entry.pinned;
+        }
+    }
+
+    pub fn remove(&mut self, text: &str) {
+        self.entries.retain(|e| e.text != text);
+    }
+
+    /// Drops everything except pinned entries
+    pub fn clear(&mut self) {
+        self.entries.retain(|e| e.pinned);
+    }
+}
+
+pub fn history_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap()
+        .join("rofi-mac")
+        .join("clipboard_history.json")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Whether the clipboard holds content a password manager asked us not to record
+pub fn is_ignored(content_types: &[String], config: &ClipboardConfig) -> bool {
+    content_types
+        .iter()
+        .any(|t| config.ignore_types.iter().any(|ignored| ignored == t))
+}
+
+/// Checks the clipboard once and returns text that should be recorded.
+/// `last_seen` tracks the previous poll so unchanged contents are not re-added.
+pub fn poll_once(
+    clipboard: &dyn Clipboard,
+    last_seen: &mut Option<String>,
+    config: &ClipboardConfig,
+) -> Option<String> {
+    let text = clipboard.get_text()?;
+    if last_seen.as_deref() == Some(text.as_str()) {
+        return None;
+    }
+    *last_seen = Some(text.clone());
+
+    if is_ignored(&clipboard.content_types(), config) {
+        return None;
+    }
+    Some(text)
+}
+
+/// Entry point for `rufi clipd [clear]`
+pub fn run_clipd(args: &[String], config: &ClipboardConfig) {
+    let path = history_path();
+
+    match args.first().map(String::as_str) {
+        None => {}
+        Some("clear") => {
+            let mut history = ClipboardHistory::load(&path);
+            history.clear();
+            history.save(&path);
+            return;
+        }
+        Some(other) => {
+            eprintln!("Error: unknown clipd command '{}'", other);
+            std::process::exit(1);
+        }
+    }
+
+    let clipboard = system_clipboard().unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let mut last_seen = None;
+    loop {
+        // Pasteboard strings are autoreleased; without a run loop they pile up
+        #[cfg(target_os = "macos")]
+        let pool = unsafe { cocoa::foundation::NSAutoreleasePool::new(cocoa::base::nil) };
+
+        if let Some(text) = poll_once(clipboard.as_ref(), &mut last_seen, config) {
+            // Load only when recording, so pins and deletions made from the UI are kept
+            let mut history = ClipboardHistory::load(&path);
+            if history.add(&text, now_secs(), config) {
+                history.save(&path);
+            }
+        }
+
+        #[cfg(target_os = "macos")]
+        unsafe {
+            use objc::{msg_send, sel, sel_impl};
+            let _: () = msg_send![pool, drain];
+        }
+        std::thread::sleep(Duration::from_millis(config.poll_interval_ms));
+    }
+}
+
+/// Single-line preview of a possibly multi-line entry
+fn preview(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    if line.chars().count() > 60 {
+        format!("{}…", line.chars().take(59).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+fn entry_to_result(entry: &ClipEntry) -> SearchResult {
+    let name = if entry.pinned {
+        format!("📌 {}", preview(&entry.text))
+    } else {
+        preview(&entry.text)
+    };
+    let pin_label = if entry.pinned { "Unpin" } else { "Pin" };
+
+    SearchResult::new(name, entry.text.clone(), SearchMode::Clipboard)
+        .with_action(ResultAction::Copy(entry.text.clone()))
+        .with_alt_action("Paste", ResultAction::Paste(entry.text.clone()))
+        .with_alt_action(pin_label, ResultAction::TogglePin(entry.text.clone()))
+        .with_alt_action("Delete", ResultAction::DeleteClip(entry.text.clone()))
+        .with_alt_action("Clear history", ResultAction::ClearClipHistory)
+}
+
+pub fn search_clipboard(query: &str) -> Vec<SearchResult> {
+    let history = ClipboardHistory::load(&history_path());
+
+    // Pinned entries first, then most recent
+    let mut entries: Vec<&ClipEntry> = history.entries.iter().collect();
+    entries.sort_by_key(|e| !e.pinned);
+
+    if query.is_empty() {
+        return entries.into_iter().take(15).map(entry_to_result).collect();
+    }
+
+    let matcher = get_matcher();
+    let mut scored: Vec<_> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            matcher
+                .fuzzy_match(&entry.text, query)
+                .map(|score| (entry, score))
+        })
+        .collect();
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(entry, _)| entry_to_result(entry))
+        .collect()
+}
+
+/// Applies a history edit made from the launcher
+pub fn apply_action(action: &ResultAction) {
+    let path = history_path();
+    let mut history = ClipboardHistory::load(&path);
+    match action {
+        ResultAction::TogglePin(text) => history.toggle_pin(text),
+        ResultAction::DeleteClip(text) => history.remove(text),
+        ResultAction::ClearClipHistory => history.clear(),
+        _ => return,
+    }
+    history.save(&path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clipboard::MemoryClipboard;
+
+    fn config(max_entries: usize) -> ClipboardConfig {
+        ClipboardConfig {
+            max_entries,
+            max_entry_bytes: 16,
+            poll_interval_ms: 500,
+            ignore_types: vec!["org.nspasteboard.ConcealedType".to_string()],
+        }
+    }
+
+    fn texts(history: &ClipboardHistory) -> Vec<&str> {
+        history.entries.iter().map(|e| e.text.as_str()).collect()
+    }
+
+    /// Polls the clipboard and records what it returns, like `clipd` does
+    fn record(
+        clipboard: &MemoryClipboard,
+        history: &mut ClipboardHistory,
+        last_seen: &mut Option<String>,
+        config: &ClipboardConfig,
+    ) -> bool {
+        poll_once(clipboard, last_seen, config).is_some_and(|text| history.add(&text, 1, config))
+    }
+
+    #[test]
+    fn add_rejects_blank_and_oversized_text() {
+        let mut history = ClipboardHistory::default();
+        assert!(history.add("hello", 1, &config(10)));
+        assert!(!history.add("   \n", 2, &config(10)));
+        assert!(!history.add("seventeen bytes!!", 3, &config(10)));
+        assert_eq!(texts(&history), ["hello"]);
+    }
+
+    #[test]
+    fn duplicates_move_to_the_front() {
+        let mut history = ClipboardHistory::default();
+        let config = config(10);
+        history.add("a", 1, &config);
+        history.add("b", 2, &config);
+        history.toggle_pin("a");
+        history.add("a", 3, &config);
+        assert_eq!(texts(&history), ["a", "b"]);
+        assert!(history.entries[0].pinned);
+        assert_eq!(history.entries[0].copied_at, 3);
+    }
+
+    #[test]
+    fn cap_drops_oldest_unpinned_entries() {
+        let mut history = ClipboardHistory::default();
+        let config = config(2);
+        history.add("old", 1, &config);
+        history.toggle_pin("old");
+        for (i, text) in ["a", "b", "c"].iter().enumerate() {
+            history.add(text, i as u64 + 2, &config);
+        }
+        // Pinned entries do not count towards the cap
+        assert_eq!(texts(&history), ["c", "b", "old"]);
+
+        history.clear();
+        assert_eq!(texts(&history), ["old"]);
+        history.remove("old");
+        assert!(history.entries.is_empty());
+    }
+
+    #[test]
+    fn polling_records_changes_once() {
+        let clipboard = MemoryClipboard::default();
+        let mut history = ClipboardHistory::default();
+        let mut last_seen = None;
+        let config = config(10);
+
+        assert!(!record(&clipboard, &mut history, &mut last_seen, &config));
+        clipboard.set_text("first").unwrap();
+        assert!(record(&clipboard, &mut history, &mut last_seen, &config));
+        assert!(!record(&clipboard, &mut history, &mut last_seen, &config));
+        clipboard.set_text("second").unwrap();
+        assert!(record(&clipboard, &mut history, &mut last_seen, &config));
+        assert_eq!(texts(&history), ["second", "first"]);
+    }
+
+    #[test]
+    fn ignored_types_are_never_recorded() {
+        let clipboard = MemoryClipboard::default();
+        let mut history = ClipboardHistory::default();
+        let mut last_seen = None;
+        let config = config(10);
+
+        clipboard.set_text("hunter2").unwrap();
+        clipboard.set_content_types(&["public.utf8-plain-text", "org.nspasteboard.ConcealedType"]);
+        assert!(!record(&clipboard, &mut history, &mut last_seen, &config));
+        // Still skipped once the marker is gone, since the text has not changed
+        clipboard.set_content_types(&["public.utf8-plain-text"]);
+        assert!(!record(&clipboard, &mut history, &mut last_seen, &config));
+        assert!(history.entries.is_empty());
+    }
+
+    #[test]
+    fn history_round_trips_through_a_file() {
+        let path = std::env::temp_dir()
+            .join(format!("rufi-clipboard-{}", std::process::id()))
+            .join("history.json");
+        let mut history = ClipboardHistory::default();
+        history.add("saved", 7, &config(10));
+        history.toggle_pin("saved");
+        history.save(&path);
+
+        assert_eq!(ClipboardHistory::load(&path), history);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(ClipboardHistory::load(&path), ClipboardHistory::default());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::app_search::Application;
+use crate::calculator::is_expression;
+use crate::config::{CombiConfig, Config};
+use crate::modes::enabled_modes;
+use crate::providers::{self, app_results};
+use crate::search_mode::{SearchMode, SearchResult};
+use crate::web_search::{search_web, web_fallback};
+use std::thread;
+
+// Six rows of the grid
+const MAX_RESULTS: usize = 30;
+
+/// The configured modes in order, skipping unknown names, repeats, modes
+/// that are not enabled and combi itself
+pub fn combi_modes(config: &Config) -> Vec<SearchMode> {
+    let enabled = enabled_modes(config);
+    let mut modes = Vec::new();
+    for mode in config
+        .combi
+        .modes
+        .iter()
+        .filter_map(|name| SearchMode::from_name(name))
+    {
+        if mode != SearchMode::Combi && enabled.contains(&mode) && !modes.contains(&mode) {
+            modes.push(mode);
+        }
+    }
+    modes
+}
+
+fn weight(config: &CombiConfig, mode: SearchMode) -> f64 {
+    config
+        .weights
+        .iter()
+        .find(|(name, _)| SearchMode::from_name(name) == Some(mode))
+        .map(|(_, weight)| *weight)
+        .unwrap_or(1.0)
+}
+
+/// What one mode adds to the combined list. The calculation, dates and URLs
+/// that apps mode mixes in are modes of their own here, and nothing falls
+/// back to a web search.
+fn source_results(
+    mode: SearchMode,
+    query: &str,
+    config: &Config,
+    apps: &[Application],
+) -> Vec<SearchResult> {
+    match mode {
+        SearchMode::Apps => app_results(apps, query),
+        SearchMode::Calc if !is_expression(query) => Vec::new(),
+        SearchMode::Web => search_web(query, &config.web),
+        _ => providers::search(mode, query, config, apps),
+    }
+}
+
+/// Ranks each mode's results by their weight over their place in that mode's
+/// own list, so every mode's best result competes with the others' best.
+/// Grouped, results are tagged with their mode and kept together, the groups
+/// ordered by their best result.
+pub fn merge_results(
+    sources: Vec<(SearchMode, Vec<SearchResult>)>,
+    config: &CombiConfig,
+) -> Vec<SearchResult> {
+    let mut scored: Vec<(f64, SearchResult)> = Vec::new();
+    for (mode, results) in sources {
+        let weight = weight(config, mode);
+        for (rank, mut result) in results.into_iter().take(config.per_mode).enumerate() {
+            if config.grouped {
+                result = result.with_section(mode.as_str());
+            }
+            scored.push((weight / (rank + 1) as f64, result));
+        }
+    }
+
+    // Stable, so ties keep the configured mode order
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    if config.grouped {
+        let mut sections: Vec<Option<String>> = Vec::new();
+        for (_, result) in &scored {
+            if !sections.contains(&result.section) {
+                sections.push(result.section.clone());
+            }
+        }
+        scored.sort_by_key(|(_, result)| sections.iter().position(|s| *s == result.section));
+    }
+
+    scored
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, result)| result)
+        .collect()
+}
+
+/// Searches every configured mode at once and merges the results
+pub fn search_combi(query: &str, config: &Config, apps: &[Application]) -> Vec<SearchResult> {
+    if query.trim().is_empty() {
+        return providers::search(SearchMode::Apps, query, config, apps);
+    }
+
+    let sources = thread::scope(|scope| {
+        let handles: Vec<_> = combi_modes(config)
+            .into_iter()
+            .map(|mode| {
+                let handle = scope.spawn(move || source_results(mode, query, config, apps));
+                (mode, handle)
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|(mode, handle)| (mode, handle.join().unwrap_or_default()))
+            .collect()
+    });
+
+    let mut results = merge_results(sources, &config.combi);
+    if results.is_empty() {
+        results.push(web_fallback(query, &config.web));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(combi: &[&str], enabled: &[&str]) -> Config {
+        let mut config = Config::theme_gruvbox();
+        config.combi.modes = combi.iter().map(|m| m.to_string()).collect();
+        config.modes.enabled = enabled.iter().map(|m| m.to_string()).collect();
+        config
+    }
+
+    #[test]
+    fn combines_only_enabled_modes() {
+        let config = config(&["apps", "files", "nope", "apps", "combi", "run"], &[]);
+        assert_eq!(
+            combi_modes(&config),
+            [SearchMode::Apps, SearchMode::Files, SearchMode::Run]
+        );
+
+        let config = self::config(&["apps", "files", "run"], &["apps", "run", "combi"]);
+        assert_eq!(combi_modes(&config), [SearchMode::Apps, SearchMode::Run]);
+    }
+
+    #[test]
+    fn default_list_skips_the_file_walk() {
+        let modes = combi_modes(&Config::theme_gruvbox());
+        assert!(modes.contains(&SearchMode::Apps));
+        assert!(!modes.contains(&SearchMode::Files));
+    }
+
+    #[test]
+    fn merges_by_weight_over_rank() {
+        let result = |name: &str, mode| SearchResult::new(name.to_string(), String::new(), mode);
+        let mut combi = CombiConfig {
+            weights: [("calc".to_string(), 3.0)].into_iter().collect(),
+            ..CombiConfig::default()
+        };
+        let sources = vec![
+            (
+                SearchMode::Apps,
+                vec![
+                    result("a1", SearchMode::Apps),
+                    result("a2", SearchMode::Apps),
+                ],
+            ),
+            (SearchMode::Calc, vec![result("c1", SearchMode::Calc)]),
+        ];
+
+        let names = |results: Vec<SearchResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.name).collect()
+        };
+        assert_eq!(
+            names(merge_results(sources.clone(), &combi)),
+            ["c1", "a1", "a2"]
+        );
+
+        combi.per_mode = 1;
+        assert_eq!(names(merge_results(sources, &combi)), ["c1", "a1"]);
+    }
+}
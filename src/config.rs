@@ -1,6 +1,7 @@
 use cocoa::base::id;
 use objc::{class, msg_send, sel, sel_impl};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -10,6 +11,40 @@ pub struct Config {
     pub colors: ColorConfig,
     pub font: FontConfig,
     pub theme: String,
+    #[serde(default)]
+    pub clipboard: ClipboardConfig,
+    #[serde(default = "default_terminal")]
+    pub terminal: String, // "Terminal", "iTerm", a command with {cmd}, or a program taking -e
+    #[serde(default = "default_editor")]
+    pub editor: String, // A command, optionally with {path}
+    #[serde(default)]
+    pub files: FilesConfig,
+    #[serde(default)]
+    pub projects: ProjectsConfig,
+    #[serde(default)]
+    pub web: WebConfig,
+    #[serde(default)]
+    pub snippets: SnippetsConfig,
+    #[serde(default)]
+    pub notes: NotesConfig,
+    #[serde(default)]
+    pub contacts: ContactsConfig,
+    #[serde(default)]
+    pub pass: PassConfig,
+    #[serde(default)]
+    pub combi: CombiConfig,
+    #[serde(default)]
+    pub modes: ModesConfig,
+    #[serde(default)]
+    pub keys: KeysConfig,
+}
+
+fn default_terminal() -> String {
+    "Terminal".to_string()
+}
+
+fn default_editor() -> String {
+    "open -a 'Visual Studio Code'".to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -34,6 +69,208 @@ pub struct FontConfig {
     pub family: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    pub max_entries: usize,
+    pub max_entry_bytes: usize,
+    pub poll_interval_ms: u64,
+    pub ignore_types: Vec<String>, // Pasteboard types that mark secrets
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        ClipboardConfig {
+            max_entries: 200,
+            max_entry_bytes: 64 * 1024,
+            poll_interval_ms: 500,
+            ignore_types: vec![
+                "org.nspasteboard.ConcealedType".to_string(),
+                "org.nspasteboard.TransientType".to_string(),
+                "org.nspasteboard.AutoGeneratedType".to_string(),
+                "com.agilebits.onepassword".to_string(),
+                "x-kde-passwordManagerHint".to_string(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct FilesConfig {
+    pub locate: bool,      // Also search the whole disk through the locate database
+    pub locate_db: String, // An mlocate.db to read; empty tries the usual places, then runs `locate`
+}
+
+impl Default for FilesConfig {
+    fn default() -> Self {
+        FilesConfig {
+            locate: true,
+            locate_db: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ProjectsConfig {
+    pub roots: Vec<String>, // Directories searched for git repositories, `~` allowed
+    pub max_depth: usize,
+}
+
+impl Default for ProjectsConfig {
+    fn default() -> Self {
+        ProjectsConfig {
+            roots: vec![
+                "~/Developer".to_string(),
+                "~/Projects".to_string(),
+                "~/src".to_string(),
+                "~/code".to_string(),
+            ],
+            max_depth: 3,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct WebConfig {
+    pub search_url: String, // Fallback search, `{query}` is replaced by the encoded query
+    pub keywords: HashMap<String, String>, // Keyword -> URL template, e.g. "gh" -> GitHub search
+}
+
+impl Default for WebConfig {
+    fn default() -> Self {
+        let keywords = [
+            ("g", "https://www.google.com/search?q={query}"),
+            ("ddg", "https://duckduckgo.com/?q={query}"),
+            ("gh", "https://github.com/search?q={query}"),
+            ("crates", "https://crates.io/search?q={query}"),
+            ("docs", "https://docs.rs/releases/search?query={query}"),
+            (
+                "w",
+                "https://en.wikipedia.org/wiki/Special:Search?search={query}",
+            ),
+            ("yt", "https://www.youtube.com/results?search_query={query}"),
+            ("maps", "https://maps.apple.com/?q={query}"),
+        ];
+        WebConfig {
+            search_url: "https://duckduckgo.com/?q={query}".to_string(),
+            keywords: keywords
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SnippetsConfig {
+    pub dir: String, // Folder of snippet files, `~` allowed; empty for rofi-mac/snippets in the config dir
+    pub items: HashMap<String, String>, // Name -> template, alongside the files
+    pub paste: bool, // Enter types the text into the frontmost app instead of only copying it
+}
+
+impl Default for SnippetsConfig {
+    fn default() -> Self {
+        SnippetsConfig {
+            dir: String::new(),
+            items: HashMap::new(),
+            paste: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct NotesConfig {
+    pub file: String, // todo.txt, or a Markdown file when it ends in .md; `~` allowed
+}
+
+impl Default for NotesConfig {
+    fn default() -> Self {
+        NotesConfig {
+            file: "~/todo.txt".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ContactsConfig {
+    pub dir: String, // Folder of .vcf files, searched a few levels deep, `~` allowed; empty for rofi-mac/contacts in the config dir
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PassConfig {
+    pub dir: String, // Password store, `~` allowed; empty for $PASSWORD_STORE_DIR or ~/.password-store
+    pub show_command: String, // Prints the decrypted entry, password first; {entry} is the quoted entry name
+    pub otp_command: String,  // Prints the entry's current one-time code
+    pub fields: Vec<String>,  // `name: value` lines offered as copy actions
+}
+
+impl Default for PassConfig {
+    fn default() -> Self {
+        PassConfig {
+            dir: String::new(),
+            show_command: "pass show {entry}".to_string(),
+            otp_command: "pass otp {entry}".to_string(),
+            fields: vec!["login".to_string(), "url".to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CombiConfig {
+    pub default: bool,      // Unprefixed queries search every mode instead of only apps
+    pub modes: Vec<String>, // Modes searched together, by name, e.g. "apps", "run"; "files" walks the disk on every keystroke
+    pub weights: HashMap<String, f64>, // Mode -> weight when ranking; unlisted modes weigh 1
+    pub per_mode: usize,    // Most results taken from each mode
+    pub grouped: bool,      // Show each mode's results under its own header instead of interleaved
+}
+
+impl Default for CombiConfig {
+    fn default() -> Self {
+        let modes = [
+            "apps",
+            "calc",
+            "date",
+            "web",
+            "run",
+            "projects",
+            "bookmarks",
+            "snippets",
+        ];
+        // Calculations, dates and URLs only show up when the query is one
+        let weights = [("apps", 2.0), ("calc", 3.0), ("date", 3.0), ("web", 2.5)];
+        CombiConfig {
+            default: false,
+            modes: modes.iter().map(|m| m.to_string()).collect(),
+            weights: weights.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            per_mode: 5,
+            grouped: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ModesConfig {
+    pub start: String, // Mode to open in, by name, taking the query as typed; empty to go by prefixes
+    pub enabled: Vec<String>, // Modes that can be used, by name; empty for all
+    pub prefixes: HashMap<String, String>, // Mode -> prefix, replacing the built-in one; "" for none
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct KeysConfig {
+    pub bindings: HashMap<String, String>, // Chord -> action, e.g. "ctrl+j": "select-down", over the defaults; "none" unbinds
+    pub custom: Vec<String>, // Commands for custom-1 to custom-9; {path} and {name} are the selected result's, quoted
+}
+
 impl Config {
     pub fn load() -> Self {
         let config_path = Self::config_path();
@@ -81,6 +318,19 @@ impl Config {
                 family: "JetBrains Mono".to_string(), // Monospace for unixporn aesthetic
             },
             theme: "gruvbox".to_string(),
+            clipboard: ClipboardConfig::default(),
+            terminal: default_terminal(),
+            editor: default_editor(),
+            files: FilesConfig::default(),
+            projects: ProjectsConfig::default(),
+            web: WebConfig::default(),
+            snippets: SnippetsConfig::default(),
+            notes: NotesConfig::default(),
+            contacts: ContactsConfig::default(),
+            pass: PassConfig::default(),
+            combi: CombiConfig::default(),
+            modes: ModesConfig::default(),
+            keys: KeysConfig::default(),
         }
     }
 
@@ -103,6 +353,19 @@ impl Config {
                 family: "Monaco".to_string(),
             },
             theme: "8bit".to_string(),
+            clipboard: ClipboardConfig::default(),
+            terminal: default_terminal(),
+            editor: default_editor(),
+            files: FilesConfig::default(),
+            projects: ProjectsConfig::default(),
+            web: WebConfig::default(),
+            snippets: SnippetsConfig::default(),
+            notes: NotesConfig::default(),
+            contacts: ContactsConfig::default(),
+            pass: PassConfig::default(),
+            combi: CombiConfig::default(),
+            modes: ModesConfig::default(),
+            keys: KeysConfig::default(),
         }
     }
 
@@ -125,6 +388,19 @@ impl Config {
                 family: "Monaco".to_string(),
             },
             theme: "catppuccin".to_string(),
+            clipboard: ClipboardConfig::default(),
+            terminal: default_terminal(),
+            editor: default_editor(),
+            files: FilesConfig::default(),
+            projects: ProjectsConfig::default(),
+            web: WebConfig::default(),
+            snippets: SnippetsConfig::default(),
+            notes: NotesConfig::default(),
+            contacts: ContactsConfig::default(),
+            pass: PassConfig::default(),
+            combi: CombiConfig::default(),
+            modes: ModesConfig::default(),
+            keys: KeysConfig::default(),
         }
     }
 
@@ -152,6 +428,19 @@ impl Config {
                 family: "SF Pro Display".to_string(), // macOS system font
             },
             theme: "modern".to_string(),
+            clipboard: ClipboardConfig::default(),
+            terminal: default_terminal(),
+            editor: default_editor(),
+            files: FilesConfig::default(),
+            projects: ProjectsConfig::default(),
+            web: WebConfig::default(),
+            snippets: SnippetsConfig::default(),
+            notes: NotesConfig::default(),
+            contacts: ContactsConfig::default(),
+            pass: PassConfig::default(),
+            combi: CombiConfig::default(),
+            modes: ModesConfig::default(),
+            keys: KeysConfig::default(),
         }
     }
 
@@ -0,0 +1,142 @@
+use crate::config::ContactsConfig;
+use crate::projects::expand_home;
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::vcard::{parse_vcards, Contact};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+// Address book syncers nest one folder per collection
+const MAX_DEPTH: usize = 3;
+
+pub fn contacts_dir(config: &ContactsConfig) -> PathBuf {
+    if config.dir.is_empty() {
+        dirs::config_dir()
+            .unwrap_or_default()
+            .join("rofi-mac")
+            .join("contacts")
+    } else {
+        expand_home(&config.dir)
+    }
+}
+
+fn collect_vcf_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        match entry.file_type() {
+            Ok(t) if t.is_dir() && !hidden && depth < MAX_DEPTH => {
+                collect_vcf_files(&path, depth + 1, files)
+            }
+            Ok(t) if t.is_file() => {
+                let is_vcf = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| e.eq_ignore_ascii_case("vcf"));
+                if is_vcf {
+                    files.push(path);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Every card in the contacts folder, sorted by name
+pub fn load_contacts(config: &ContactsConfig) -> Vec<Contact> {
+    let mut files = Vec::new();
+    collect_vcf_files(&contacts_dir(config), 0, &mut files);
+
+    let mut contacts: Vec<Contact> = files
+        .iter()
+        .filter_map(|path| fs::read(path).ok())
+        .flat_map(|bytes| parse_vcards(&String::from_utf8_lossy(&bytes)))
+        .collect();
+    contacts.sort_by_key(|c| c.name.to_lowercase());
+    contacts.dedup();
+    contacts
+}
+
+fn contact_hint(contact: &Contact) -> String {
+    [
+        contact.emails.first(),
+        contact.phones.first(),
+        Some(&contact.org),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|s| !s.is_empty())
+    .cloned()
+    .collect::<Vec<_>>()
+    .join(" · ")
+}
+
+fn contact_to_result(contact: &Contact) -> SearchResult {
+    // Enter writes to the first address, or copies the number when there is none
+    let (path, action) = match (contact.emails.first(), contact.phones.first()) {
+        (Some(email), _) => (format!("mailto:{}", email), ResultAction::Open),
+        (None, Some(phone)) => (phone.clone(), ResultAction::Copy(phone.clone())),
+        (None, None) => (String::new(), ResultAction::Copy(contact.name.clone())),
+    };
+    let mut result = SearchResult::new(contact.name.clone(), path, SearchMode::Contacts)
+        .with_action(action)
+        .with_hint(&contact_hint(contact));
+
+    for email in &contact.emails {
+        result = result.with_alt_action(
+            &format!("Copy email {}", email),
+            ResultAction::Copy(email.clone()),
+        );
+    }
+    for phone in &contact.phones {
+        result = result.with_alt_action(
+            &format!("Copy phone {}", phone),
+            ResultAction::Copy(phone.clone()),
+        );
+    }
+    result
+}
+
+pub fn search_contacts(query: &str, config: &ContactsConfig) -> Vec<SearchResult> {
+    let contacts = load_contacts(config);
+
+    if query.is_empty() {
+        return contacts.iter().take(15).map(contact_to_result).collect();
+    }
+
+    let matcher = get_matcher();
+    let mut scored: Vec<(&Contact, i64)> = contacts
+        .iter()
+        .filter_map(|contact| {
+            // The name counts most, then nickname and company, then addresses
+            let name = matcher.fuzzy_match(&contact.name, query).map(|s| s * 3);
+            let nickname = matcher.fuzzy_match(&contact.nickname, query).map(|s| s * 2);
+            let org = matcher.fuzzy_match(&contact.org, query);
+            let email = contact
+                .emails
+                .iter()
+                .filter_map(|e| matcher.fuzzy_match(e, query))
+                .max();
+            let score = [name, nickname, org, email].into_iter().flatten().max()?;
+            Some((contact, score))
+        })
+        .collect();
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(contact, _)| contact_to_result(contact))
+        .collect()
+}
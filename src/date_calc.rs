@@ -0,0 +1,753 @@
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::timezone::{
+    civil_from_days, civil_from_seconds, days_from_civil, days_in_month, find_zone, weekday_name,
+    zoneinfo_dir, TimeZone, SECONDS_PER_DAY,
+};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Everything an evaluation depends on, so results are reproducible with a fixed clock
+pub struct Clock {
+    pub now: i64, // Unix seconds
+    pub local: TimeZone,
+    pub zoneinfo: PathBuf,
+}
+
+impl Clock {
+    pub fn system() -> Self {
+        let zoneinfo = zoneinfo_dir();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Clock {
+            now,
+            local: TimeZone::local(&zoneinfo),
+            zoneinfo,
+        }
+    }
+
+    /// Today's local date (`YYYY-MM-DD`) and time (`HH:MM`)
+    pub fn local_date_time(&self) -> (String, String) {
+        let (year, month, day, hour, minute, _) = civil_from_seconds(self.local.to_local(self.now));
+        (
+            format!("{:04}-{:02}-{:02}", year, month, day),
+            format!("{:02}:{:02}", hour, minute),
+        )
+    }
+
+    /// Local time as `YYYY-MM-DDThh:mm:ss`
+    pub fn local_timestamp(&self) -> String {
+        let (year, month, day, hour, minute, second) =
+            civil_from_seconds(self.local.to_local(self.now));
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year, month, day, hour, minute, second
+        )
+    }
+}
+
+/// Latest year we calculate with; keeping instants within a million years of
+/// the epoch leaves the calendar arithmetic far from `i64` overflow
+const MAX_YEAR: i64 = 999_999;
+
+fn in_range(utc: i64) -> bool {
+    utc.div_euclid(SECONDS_PER_DAY).abs() <= days_from_civil(MAX_YEAR, 12, 31)
+}
+
+/// A point in time together with the zone it should be shown in
+#[derive(Debug, Clone)]
+struct Instant {
+    utc: i64,
+    zone: TimeZone,
+    date_only: bool,
+}
+
+/// Calendar-aware duration: months and days follow the wall clock across DST changes
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Duration {
+    months: i64,
+    days: i64,
+    seconds: i64,
+}
+
+impl Duration {
+    fn negate(self) -> Self {
+        Duration {
+            months: -self.months,
+            days: -self.days,
+            seconds: -self.seconds,
+        }
+    }
+}
+
+/// A formatted answer: what to show and what Enter copies
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateAnswer {
+    pub display: String,
+    pub value: String,
+}
+
+// Common abbreviations are treated as fixed offsets, so "3pm PST" means UTC-8
+// even in summer. CST is taken as US Central.
+const ZONE_ABBREVIATIONS: &[(&str, i32)] = &[
+    ("utc", 0),
+    ("gmt", 0),
+    ("z", 0),
+    ("wet", 0),
+    ("west", 3600),
+    ("bst", 3600),
+    ("cet", 3600),
+    ("cest", 7200),
+    ("eet", 7200),
+    ("eest", 10_800),
+    ("msk", 10_800),
+    ("ist", 19_800),
+    ("pkt", 18_000),
+    ("ict", 25_200),
+    ("sgt", 28_800),
+    ("hkt", 28_800),
+    ("awst", 28_800),
+    ("jst", 32_400),
+    ("kst", 32_400),
+    ("acst", 34_200),
+    ("aest", 36_000),
+    ("aedt", 39_600),
+    ("nzst", 43_200),
+    ("nzdt", 46_800),
+    ("hst", -36_000),
+    ("akst", -32_400),
+    ("akdt", -28_800),
+    ("pst", -28_800),
+    ("pdt", -25_200),
+    ("mst", -25_200),
+    ("mdt", -21_600),
+    ("cst", -21_600),
+    ("cdt", -18_000),
+    ("est", -18_000),
+    ("edt", -14_400),
+    ("ast", -14_400),
+    ("adt", -10_800),
+    ("brt", -10_800),
+    ("art", -10_800),
+];
+
+fn resolve_zone(name: &str, clock: &Clock) -> Option<TimeZone> {
+    let lower = name.trim().to_lowercase();
+    if lower == "local" || lower == "here" {
+        return Some(clock.local.clone());
+    }
+    if let Some((abbr, offset)) = ZONE_ABBREVIATIONS.iter().find(|(abbr, _)| *abbr == lower) {
+        return Some(TimeZone::fixed(&abbr.to_uppercase(), *offset));
+    }
+
+    // "+05:30", "utc+2", "gmt-3"
+    let offset_text = lower
+        .strip_prefix("utc")
+        .or_else(|| lower.strip_prefix("gmt"))
+        .unwrap_or(&lower);
+    if offset_text.starts_with('+') || offset_text.starts_with('-') {
+        let (sign, digits) = offset_text.split_at(1);
+        let (hours, minutes) = digits.split_once(':').unwrap_or((digits, "0"));
+        let hours: i32 = hours.parse().ok()?;
+        let minutes: i32 = minutes.parse().ok()?;
+        let seconds = (hours * 3600 + minutes * 60) * if sign == "-" { -1 } else { 1 };
+        return Some(TimeZone::fixed(&format_offset(seconds), seconds));
+    }
+
+    find_zone(&clock.zoneinfo, name)
+}
+
+/// Splits at the last occurrence of any separator (surrounded by spaces)
+fn rsplit_keyword<'a>(text: &'a str, keywords: &[&str]) -> Option<(&'a str, &'a str, usize)> {
+    let lower = text.to_ascii_lowercase();
+    keywords
+        .iter()
+        .enumerate()
+        .filter_map(|(i, k)| lower.rfind(k).map(|pos| (pos, i, k.len())))
+        .max_by_key(|(pos, ..)| *pos)
+        .map(|(pos, i, len)| (text[..pos].trim(), text[pos + len..].trim(), i))
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Option<T> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Parses "2026-12-01" into days since the epoch
+fn parse_date(text: &str) -> Option<i64> {
+    let mut parts = text.split('-');
+    let year: i64 = parse_number(parts.next()?)?;
+    let month: u32 = parse_number(parts.next()?)?;
+    let day: u32 = parse_number(parts.next()?)?;
+    if parts.next().is_some() || !(1..=12).contains(&month) || year > MAX_YEAR {
+        return None;
+    }
+    if day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+/// Parses "15:30", "15:30:05", "3pm", "3:30pm", "noon" into seconds after midnight
+fn parse_time_of_day(text: &str) -> Option<i64> {
+    let lower = text.to_lowercase();
+    match lower.as_str() {
+        "noon" => return Some(12 * 3600),
+        "midnight" => return Some(0),
+        _ => {}
+    }
+
+    let (clock, meridiem) = if let Some(rest) = lower.strip_suffix("am") {
+        (rest.trim(), Some(0))
+    } else if let Some(rest) = lower.strip_suffix("pm") {
+        (rest.trim(), Some(12))
+    } else {
+        (lower.as_str(), None)
+    };
+
+    let mut parts = clock.split(':');
+    let hour: i64 = parse_number(parts.next()?)?;
+    let minute: i64 = parts.next().map_or(Some(0), parse_number)?;
+    let second: i64 = parts.next().map_or(Some(0), parse_number)?;
+    if parts.next().is_some() || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let hour = match meridiem {
+        Some(shift) if (1..=12).contains(&hour) => hour % 12 + shift,
+        Some(_) => return None,
+        // A bare number is not a time ("15" could be anything), require a colon
+        None if !clock.contains(':') => return None,
+        None if hour > 23 => return None,
+        None => hour,
+    };
+    Some(hour * 3600 + minute * 60 + second)
+}
+
+/// Splits an optional trailing "Z" / "+02:00" offset off an ISO time
+fn split_iso_offset(time: &str) -> (&str, Option<&str>) {
+    if let Some(rest) = time.strip_suffix(['Z', 'z']) {
+        return (rest, Some("utc"));
+    }
+    match time.rfind(['+', '-']) {
+        Some(pos) if pos > 0 => (&time[..pos], Some(&time[pos..])),
+        _ => (time, None),
+    }
+}
+
+fn parse_instant(text: &str, clock: &Clock) -> Option<Instant> {
+    let text = text.trim();
+    let lower = text.to_lowercase();
+    let local_midnight = |days_offset: i64| {
+        let today = clock.local.to_local(clock.now).div_euclid(SECONDS_PER_DAY);
+        Instant {
+            utc: clock
+                .local
+                .local_to_utc((today + days_offset) * SECONDS_PER_DAY),
+            zone: clock.local.clone(),
+            date_only: true,
+        }
+    };
+
+    match lower.as_str() {
+        "now" => {
+            return Some(Instant {
+                utc: clock.now,
+                zone: clock.local.clone(),
+                date_only: false,
+            })
+        }
+        "today" => return Some(local_midnight(0)),
+        "tomorrow" => return Some(local_midnight(1)),
+        "yesterday" => return Some(local_midnight(-1)),
+        _ => {}
+    }
+
+    // Unix timestamps: "@1700000000" or a bare 9-10 digit (seconds) / 12-13 digit (ms) number
+    let digits = lower.strip_prefix('@').unwrap_or(&lower);
+    if let Some(value) = parse_number::<i64>(digits) {
+        let utc = match digits.len() {
+            9 | 10 => value,
+            12 | 13 => value / 1000,
+            _ if lower.starts_with('@') => value,
+            _ => return None,
+        };
+        if !in_range(utc) {
+            return None;
+        }
+        return Some(Instant {
+            utc,
+            zone: clock.local.clone(),
+            date_only: false,
+        });
+    }
+
+    // "<date>[T| ]<time> [zone]", "<time> [zone]" or "<date> [zone]"
+    let (first, rest) = match text.split_once([' ', 'T']) {
+        Some((first, rest)) => (first, rest.trim()),
+        None => (text, ""),
+    };
+    let (days, rest) = match parse_date(first) {
+        Some(days) => (Some(days), rest),
+        None => (None, text),
+    };
+
+    // Everything after the time is a zone; times may have a space before am/pm
+    let mut words: Vec<&str> = rest.split_whitespace().collect();
+    let mut seconds = None;
+    let mut zone_name = None;
+    if !words.is_empty() {
+        let (time, offset) = split_iso_offset(words[0]);
+        if let Some(parsed) = parse_time_of_day(time) {
+            seconds = Some(parsed);
+            zone_name = offset.map(str::to_string);
+            words.remove(0);
+        } else if words.len() > 1 {
+            if let Some(parsed) = parse_time_of_day(&format!("{}{}", words[0], words[1])) {
+                seconds = Some(parsed);
+                words.drain(..2);
+            }
+        }
+    }
+    if days.is_none() && seconds.is_none() {
+        return None;
+    }
+    if !words.is_empty() {
+        if zone_name.is_some() {
+            return None;
+        }
+        zone_name = Some(words.join(" "));
+    }
+
+    let zone = match zone_name {
+        Some(name) => resolve_zone(&name, clock)?,
+        None => clock.local.clone(),
+    };
+    // A bare time means today in the zone it was given in
+    let days = days.unwrap_or_else(|| zone.to_local(clock.now).div_euclid(SECONDS_PER_DAY));
+    let local = days * SECONDS_PER_DAY + seconds.unwrap_or(0);
+
+    Some(Instant {
+        utc: zone.local_to_utc(local),
+        zone,
+        date_only: seconds.is_none(),
+    })
+}
+
+/// Parses "90 days", "2w", "1h 30m", "1.5 hours"
+fn parse_duration(text: &str) -> Option<Duration> {
+    let mut duration = Duration::default();
+    let mut tokens: Vec<String> = Vec::new();
+
+    // Split "1h30m" / "90days" into number and unit tokens
+    for word in text.split_whitespace() {
+        let mut current = String::new();
+        for c in word.chars() {
+            let is_number = c.is_ascii_digit() || c == '.';
+            let was_number = current
+                .chars()
+                .last()
+                .map(|l| l.is_ascii_digit() || l == '.');
+            if was_number.is_some_and(|was| was != is_number) {
+                tokens.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+        tokens.push(current);
+    }
+    if tokens.is_empty() || !tokens.len().is_multiple_of(2) {
+        return None;
+    }
+
+    for pair in tokens.chunks(2) {
+        let amount: f64 = pair[0].parse().ok()?;
+        let whole = amount.fract() == 0.0;
+        let (field, value) = match pair[1].to_lowercase().trim_end_matches(',') {
+            "s" | "sec" | "secs" | "second" | "seconds" => {
+                (&mut duration.seconds, whole_units(amount, 1.0)?)
+            }
+            "m" | "min" | "mins" | "minute" | "minutes" => {
+                (&mut duration.seconds, whole_units(amount, 60.0)?)
+            }
+            "h" | "hr" | "hrs" | "hour" | "hours" => {
+                (&mut duration.seconds, whole_units(amount, 3600.0)?)
+            }
+            "d" | "day" | "days" if whole => (&mut duration.days, whole_units(amount, 1.0)?),
+            "d" | "day" | "days" => (&mut duration.seconds, whole_units(amount, 86_400.0)?),
+            "w" | "wk" | "wks" | "week" | "weeks" if whole => {
+                (&mut duration.days, whole_units(amount, 7.0)?)
+            }
+            "mo" | "month" | "months" if whole => (&mut duration.months, whole_units(amount, 1.0)?),
+            "y" | "yr" | "yrs" | "year" | "years" if whole => {
+                (&mut duration.months, whole_units(amount, 12.0)?)
+            }
+            _ => return None,
+        };
+        *field = field.checked_add(value)?;
+    }
+    Some(duration)
+}
+
+/// `amount * per_unit` truncated to an integer, or `None` where the cast would saturate
+fn whole_units(amount: f64, per_unit: f64) -> Option<i64> {
+    let value = (amount * per_unit).trunc();
+    (value.abs() < 9.0e18).then_some(value as i64)
+}
+
+/// Adds a duration, or `None` when the result leaves the supported range
+fn add_duration(instant: &Instant, duration: Duration) -> Option<Instant> {
+    let zone = &instant.zone;
+    let local = zone.to_local(instant.utc);
+    let days = local.div_euclid(SECONDS_PER_DAY);
+    let time = local.rem_euclid(SECONDS_PER_DAY);
+
+    // Month arithmetic clamps to the end of the month: Jan 31 + 1 month = Feb 28
+    let (year, month, day) = civil_from_days(days);
+    let total_months = (year * 12 + (month as i64 - 1)).checked_add(duration.months)?;
+    let (year, month) = (
+        total_months.div_euclid(12),
+        total_months.rem_euclid(12) as u32 + 1,
+    );
+    if year.abs() > MAX_YEAR {
+        return None;
+    }
+    let day = day.min(days_in_month(year, month));
+    let days = days_from_civil(year, month, day).checked_add(duration.days)?;
+    if !in_range(days.checked_mul(SECONDS_PER_DAY)?) {
+        return None;
+    }
+
+    let utc = zone
+        .local_to_utc(days * SECONDS_PER_DAY + time)
+        .checked_add(duration.seconds)?;
+    if !in_range(utc) {
+        return None;
+    }
+    Some(Instant {
+        utc,
+        zone: zone.clone(),
+        date_only: instant.date_only && duration.seconds == 0,
+    })
+}
+
+fn format_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.abs();
+    format!("{}{:02}:{:02}", sign, offset / 3600, offset % 3600 / 60)
+}
+
+/// ISO 8601 with the zone's offset, e.g. 2026-12-01T15:00:00+01:00
+fn format_iso(instant: &Instant) -> String {
+    let offset = instant.zone.offset_at(instant.utc);
+    let (year, month, day, hour, minute, second) = civil_from_seconds(instant.utc + offset as i64);
+    let suffix = if offset == 0 {
+        "Z".to_string()
+    } else {
+        format_offset(offset)
+    };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}",
+        year, month, day, hour, minute, second, suffix
+    )
+}
+
+fn format_instant(instant: &Instant) -> DateAnswer {
+    let local = instant.zone.to_local(instant.utc);
+    let days = local.div_euclid(SECONDS_PER_DAY);
+    let (year, month, day, hour, minute, second) = civil_from_seconds(local);
+    let date = format!("{:04}-{:02}-{:02}", year, month, day);
+
+    if instant.date_only {
+        return DateAnswer {
+            display: format!("{} {}", weekday_name(days), date),
+            value: date,
+        };
+    }
+
+    let time = if second == 0 {
+        format!("{:02}:{:02}", hour, minute)
+    } else {
+        format!("{:02}:{:02}:{:02}", hour, minute, second)
+    };
+    DateAnswer {
+        display: format!(
+            "{} {} {} {}",
+            weekday_name(days),
+            date,
+            time,
+            instant.zone.abbreviation_at(instant.utc)
+        ),
+        value: format_iso(instant),
+    }
+}
+
+fn format_difference(from: &Instant, to: &Instant) -> DateAnswer {
+    let (sign, seconds) = {
+        // Whole-day comparisons go through the calendar so DST does not leave 23h remainders
+        let diff = if from.date_only && to.date_only {
+            (from.zone.to_local(from.utc) - to.zone.to_local(to.utc)).div_euclid(SECONDS_PER_DAY)
+                * SECONDS_PER_DAY
+        } else {
+            from.utc - to.utc
+        };
+        (if diff < 0 { "-" } else { "" }, diff.abs())
+    };
+
+    let days = seconds / SECONDS_PER_DAY;
+    let hours = seconds % SECONDS_PER_DAY / 3600;
+    let minutes = seconds % 3600 / 60;
+    let unit = if days == 1 { "day" } else { "days" };
+
+    let display = match (days, hours, minutes) {
+        (_, 0, 0) => format!("{}{} {}", sign, days, unit),
+        (0, _, _) => format!("{}{}h {}m", sign, hours, minutes),
+        _ => format!("{}{} {} {}h {}m", sign, days, unit, hours, minutes),
+    };
+    DateAnswer {
+        value: display.clone(),
+        display,
+    }
+}
+
+/// Evaluates a date expression against the given clock.
+///
+/// Supported forms: `now`, `today`, ISO dates and times, unix timestamps,
+/// `<point> ± <duration>`, `<point> - <point>`, `<point> in <zone>` and
+/// `<point> to unix`.
+pub fn evaluate(query: &str, clock: &Clock) -> Vec<DateAnswer> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    // Conversions: "3pm PST in Berlin", "2026-12-01T10:00Z to unix"
+    if let Some((point, target, _)) = rsplit_keyword(query, &[" in ", " to "]) {
+        if let Some(instant) = evaluate_instant(point, clock) {
+            let target_lower = target.to_lowercase();
+            if matches!(target_lower.as_str(), "unix" | "epoch" | "timestamp") {
+                let value = instant.utc.to_string();
+                return vec![DateAnswer {
+                    display: value.clone(),
+                    value,
+                }];
+            }
+            if target_lower == "iso" {
+                let value = format_iso(&instant);
+                return vec![DateAnswer {
+                    display: value.clone(),
+                    value,
+                }];
+            }
+            if let Some(zone) = resolve_zone(target, clock) {
+                let converted = Instant {
+                    utc: instant.utc,
+                    zone,
+                    date_only: false,
+                };
+                return vec![format_instant(&converted)];
+            }
+        }
+    }
+
+    // Differences: "2026-12-01 - today"
+    if let Some((left, right, 0)) = rsplit_keyword(query, &[" - "]) {
+        if let (Some(from), Some(to)) = (evaluate_instant(left, clock), parse_instant(right, clock))
+        {
+            return vec![format_difference(&from, &to)];
+        }
+    }
+
+    let Some(instant) = evaluate_instant(query, clock) else {
+        return Vec::new();
+    };
+
+    let mut answers = vec![format_instant(&instant)];
+    if !instant.date_only {
+        let value = instant.utc.to_string();
+        answers.push(DateAnswer {
+            display: value.clone(),
+            value,
+        });
+    }
+    answers
+}
+
+/// A point optionally followed by `+ duration` / `- duration` terms
+fn evaluate_instant(text: &str, clock: &Clock) -> Option<Instant> {
+    if let Some(instant) = parse_instant(text, clock) {
+        return Some(instant);
+    }
+    let (left, right, index) = rsplit_keyword(text, &[" + ", " - "])?;
+    let duration = parse_duration(right)?;
+    let duration = if index == 1 {
+        duration.negate()
+    } else {
+        duration
+    };
+    add_duration(&evaluate_instant(left, clock)?, duration)
+}
+
+pub fn search_dates(query: &str) -> Vec<SearchResult> {
+    evaluate(query, &Clock::system())
+        .into_iter()
+        .map(|answer| {
+            SearchResult::new(answer.display, answer.value.clone(), SearchMode::Date)
+                .with_action(ResultAction::Copy(answer.value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Saturday 2026-01-31 12:00:00Z, 13:00 in Berlin
+    fn clock() -> Clock {
+        let zoneinfo = PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/zoneinfo"
+        ));
+        Clock {
+            now: 1_769_860_800,
+            local: TimeZone::load(&zoneinfo, "Europe/Berlin").unwrap(),
+            zoneinfo,
+        }
+    }
+
+    fn values(query: &str) -> Vec<String> {
+        evaluate(query, &clock())
+            .into_iter()
+            .map(|a| a.value)
+            .collect()
+    }
+
+    fn first(query: &str) -> DateAnswer {
+        evaluate(query, &clock())
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| panic!("no answer for {:?}", query))
+    }
+
+    #[test]
+    fn relative_days() {
+        assert_eq!(first("today").display, "Sat 2026-01-31");
+        assert_eq!(first("tomorrow").value, "2026-02-01");
+        assert_eq!(first("yesterday").value, "2026-01-30");
+        assert_eq!(values("now"), ["2026-01-31T13:00:00+01:00", "1769860800"]);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(
+            parse_duration("1h 30m"),
+            Some(Duration {
+                seconds: 5400,
+                ..Duration::default()
+            })
+        );
+        assert_eq!(
+            parse_duration("2w3d"),
+            Some(Duration {
+                days: 17,
+                ..Duration::default()
+            })
+        );
+        assert_eq!(
+            parse_duration("1.5 days"),
+            Some(Duration {
+                seconds: 129_600,
+                ..Duration::default()
+            })
+        );
+        assert_eq!(parse_duration("1.5 months"), None);
+        assert_eq!(parse_duration("3 fortnights"), None);
+        assert_eq!(parse_duration("90"), None);
+
+        assert_eq!(first("today + 90 days").value, "2026-05-01");
+        assert_eq!(
+            first("2026-01-31 10:00 + 1h 30m").display,
+            "Sat 2026-01-31 11:30 CET"
+        );
+        assert_eq!(first("today - 2 weeks + 1 day").value, "2026-01-18");
+    }
+
+    #[test]
+    fn month_end_clamping() {
+        assert_eq!(first("today + 1 month").display, "Sat 2026-02-28");
+        assert_eq!(first("2024-01-31 + 1 month").value, "2024-02-29");
+        assert_eq!(first("2024-02-29 + 1 year").value, "2025-02-28");
+        assert_eq!(first("2026-03-31 - 1 month").value, "2026-02-28");
+        assert_eq!(first("2026-01-31 + 13 months").value, "2027-02-28");
+    }
+
+    #[test]
+    fn calendar_arithmetic_follows_the_wall_clock_across_dst() {
+        assert_eq!(
+            first("2026-03-28 12:00 + 1 day").value,
+            "2026-03-29T12:00:00+02:00"
+        );
+        assert_eq!(
+            first("2026-03-28 12:00 + 24h").value,
+            "2026-03-29T13:00:00+02:00"
+        );
+        assert_eq!(first("2026-03-30 - 2026-03-29").value, "1 day");
+    }
+
+    #[test]
+    fn differences() {
+        assert_eq!(first("2026-12-25 - today").value, "328 days");
+        assert_eq!(first("2026-01-01 - 2026-01-31").value, "-30 days");
+        assert_eq!(first("2026-01-31 18:30 - now").value, "5h 30m");
+    }
+
+    #[test]
+    fn epoch_conversions() {
+        assert_eq!(values("2026-01-01T00:00Z to unix"), ["1767225600"]);
+        assert_eq!(values("@0")[0], "1970-01-01T01:00:00+01:00");
+        assert_eq!(
+            first("1767225600 in utc").display,
+            "Thu 2026-01-01 00:00 UTC"
+        );
+        assert_eq!(first("1767225600000 in utc").value, "2026-01-01T00:00:00Z");
+        assert_eq!(
+            values("2026-01-31T13:00:00+01:00 to iso"),
+            ["2026-01-31T13:00:00+01:00"]
+        );
+        // Short numbers are not timestamps
+        assert!(values("12345").is_empty());
+    }
+
+    #[test]
+    fn zone_conversions() {
+        assert_eq!(
+            first("3pm PST in Berlin").display,
+            "Sun 2026-02-01 00:00 CET"
+        );
+        assert_eq!(
+            first("2026-07-01 09:00 America/New_York in berlin").value,
+            "2026-07-01T15:00:00+02:00"
+        );
+        assert_eq!(first("noon utc+5:30 in utc").value, "2026-01-31T06:30:00Z");
+    }
+
+    #[test]
+    fn overflow_gives_no_result() {
+        assert!(values("today + 99999999999999999 years").is_empty());
+        assert!(values("today + 999999999999999999999 years").is_empty());
+        assert!(values("today - 99999999999999999 weeks").is_empty());
+        assert!(values("now + 99999999999999999999 hours").is_empty());
+        assert!(values("@9223372036854775807").is_empty());
+        assert!(values("9999999-01-01").is_empty());
+        assert!(values("999999-12-31 + 1 day").is_empty());
+    }
+
+    #[test]
+    fn ignores_plain_words() {
+        assert!(values("firefox").is_empty());
+        assert!(values("15").is_empty());
+        assert!(values("").is_empty());
+    }
+}
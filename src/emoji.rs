@@ -0,0 +1,334 @@
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Code point and name for every named character, generated from UnicodeData.txt (15.1).
+// Algorithmically named ranges (CJK ideographs, Hangul syllables, Tangut...) are left out.
+// build.rs deflates both data files; they are inflated on first use.
+static UNICODE_NAMES: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/unicode-names.txt.deflate"));
+
+// CLDR emoji annotations (common/annotations/en.xml): keywords such as "happy" for 😀
+static CLDR_ANNOTATIONS: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/cldr-annotations-en.xml.deflate"));
+
+static NAMES: OnceLock<String> = OnceLock::new();
+static ANNOTATIONS: OnceLock<Annotations> = OnceLock::new();
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+const MAX_RESULTS: usize = 15;
+// Slots kept free of emoji for characters found by Unicode name
+const NAME_SLOTS: usize = 5;
+
+/// CLDR keywords by emoji, with the emoji presentation selector removed
+type Annotations = HashMap<String, Vec<String>>;
+
+fn inflate(data: &[u8]) -> String {
+    miniz_oxide::inflate::decompress_to_vec(data)
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default()
+}
+
+fn unicode_names() -> &'static str {
+    NAMES.get_or_init(|| inflate(UNICODE_NAMES))
+}
+
+fn annotations() -> &'static Annotations {
+    ANNOTATIONS.get_or_init(|| parse_annotations(&inflate(CLDR_ANNOTATIONS)))
+}
+
+/// Reads the keyword lists of a CLDR annotations file, one
+/// `<annotation cp="😀">face | grin</annotation>` element per line.
+/// The `type="tts"` short names are skipped; the emoji crate has those.
+fn parse_annotations(xml: &str) -> Annotations {
+    let mut annotations = Annotations::new();
+    for line in xml.lines() {
+        let Some(rest) = line.trim().strip_prefix("<annotation cp=\"") else {
+            continue;
+        };
+        let Some((cp, rest)) = rest.split_once('"') else {
+            continue;
+        };
+        let Some(keywords) = rest
+            .strip_prefix('>')
+            .and_then(|r| r.strip_suffix("</annotation>"))
+        else {
+            continue;
+        };
+        annotations.insert(
+            unescape_xml(cp).replace('\u{fe0f}', ""),
+            keywords
+                .split('|')
+                .map(|k| unescape_xml(k.trim()))
+                .filter(|k| !k.is_empty())
+                .collect(),
+        );
+    }
+    annotations
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Parses `U+1F600`, `u+e9` or `0x41` into a character
+fn parse_code_point(query: &str) -> Option<char> {
+    let hex = query
+        .strip_prefix("U+")
+        .or_else(|| query.strip_prefix("u+"))
+        .or_else(|| query.strip_prefix("0x"))?;
+    parse_hex(hex)
+}
+
+fn parse_hex(hex: &str) -> Option<char> {
+    if hex.is_empty() || hex.len() > 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+}
+
+/// `U+` notation for every scalar in a string
+pub fn code_points(text: &str) -> String {
+    text.chars()
+        .map(|c| format!("U+{:04X}", c as u32))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Looks up the Unicode name of a character
+pub fn char_name(c: char) -> Option<&'static str> {
+    let key = format!("{:04X};", c as u32);
+    unicode_names()
+        .lines()
+        .find_map(|line| line.strip_prefix(key.as_str()))
+}
+
+/// Every character whose name contains all words of the query
+fn search_names(query: &str, limit: usize) -> Vec<(char, &'static str)> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_uppercase).collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(char, &'static str)> = unicode_names()
+        .lines()
+        .filter_map(|line| {
+            let (hex, name) = line.split_once(';')?;
+            if !words.iter().all(|w| name.contains(w.as_str())) {
+                return None;
+            }
+            let c = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)?;
+            Some((c, name))
+        })
+        .collect();
+
+    // Shorter names are the closer match ("SNOWMAN" before "SNOWMAN WITHOUT SNOW")
+    matches.sort_by_key(|(_, name)| name.len());
+    matches.truncate(limit);
+    matches
+}
+
+fn emoji_to_result(emoji: &'static emojis::Emoji) -> SearchResult {
+    let mut result = SearchResult::new(
+        emoji.name().to_string(),
+        emoji.as_str().to_string(),
+        SearchMode::Emoji,
+    )
+    .with_action(ResultAction::Copy(emoji.as_str().to_string()));
+
+    if let Some(variants) = emoji.skin_tones() {
+        for variant in variants.skip(1) {
+            // Variant names read "thumbs up: medium skin tone"
+            let tone = variant
+                .name()
+                .split_once(": ")
+                .map(|(_, tone)| tone)
+                .unwrap_or(variant.name());
+            let label = format!("{} {}", variant.as_str(), tone);
+            result = result.with_alt_action(&label, ResultAction::Copy(variant.as_str().into()));
+        }
+    }
+
+    result.with_alt_action(
+        "Copy code point",
+        ResultAction::Copy(code_points(emoji.as_str())),
+    )
+}
+
+fn char_to_result(c: char, name: &str) -> SearchResult {
+    let text = c.to_string();
+    SearchResult::new(name.to_lowercase(), text.clone(), SearchMode::Emoji)
+        .with_action(ResultAction::Copy(text.clone()))
+        .with_alt_action("Copy code point", ResultAction::Copy(code_points(&text)))
+}
+
+pub fn search_emoji(query: &str) -> Vec<SearchResult> {
+    search_emoji_with(query, annotations())
+}
+
+/// The character for a code point, named if it is not an emoji
+fn code_point_result(c: char) -> SearchResult {
+    match emojis::get(&c.to_string()) {
+        Some(emoji) => emoji_to_result(emoji),
+        None => char_to_result(c, char_name(c).unwrap_or("unnamed character")),
+    }
+}
+
+fn search_emoji_with(query: &str, annotations: &Annotations) -> Vec<SearchResult> {
+    let query = query.trim();
+
+    if query.is_empty() {
+        return emojis::iter()
+            .take(MAX_RESULTS)
+            .map(emoji_to_result)
+            .collect();
+    }
+
+    if let Some(c) = parse_code_point(query) {
+        return vec![code_point_result(c)];
+    }
+
+    // Emoji by CLDR short name, keyword or shortcode
+    let matcher = get_matcher();
+    let mut scored: Vec<_> = emojis::iter()
+        .filter_map(|emoji| {
+            let keywords = annotations
+                .get(&emoji.as_str().replace('\u{fe0f}', ""))
+                .into_iter()
+                .flatten()
+                .map(String::as_str);
+            std::iter::once(emoji.name())
+                .chain(emoji.shortcodes())
+                .chain(keywords)
+                .filter_map(|key| matcher.fuzzy_match(key, query))
+                .max()
+                .map(|score| (emoji, score))
+        })
+        .collect();
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+    // A bare code point such as "1f600" or "2603", then other characters by
+    // Unicode name. Emoji carry a variation selector the plain character lacks.
+    let is_emoji = |c: char| {
+        emojis::get(&c.to_string()).is_some() || emojis::get(&format!("{}\u{fe0f}", c)).is_some()
+    };
+    let bare_code_point = (query.len() >= 4 && query.contains(|c: char| c.is_ascii_digit()))
+        .then(|| parse_hex(query))
+        .flatten();
+    let mut characters: Vec<SearchResult> =
+        bare_code_point.map(code_point_result).into_iter().collect();
+    characters.extend(
+        search_names(query, MAX_RESULTS * 2)
+            .into_iter()
+            .filter(|(c, _)| !is_emoji(*c) && Some(*c) != bare_code_point)
+            .map(|(c, name)| char_to_result(c, name)),
+    );
+
+    // Fuzzy emoji matches are plentiful, so keep room for the other matches
+    let reserved = characters.len().min(NAME_SLOTS);
+    let mut results: Vec<SearchResult> = scored
+        .into_iter()
+        .take(MAX_RESULTS - reserved)
+        .map(|(emoji, _)| emoji_to_result(emoji))
+        .collect();
+    let remaining = MAX_RESULTS - results.len();
+    results.extend(characters.into_iter().take(remaining));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_annotations() -> Annotations {
+        parse_annotations(include_str!("../tests/fixtures/cldr-annotations-en.xml"))
+    }
+
+    fn paths(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.path.as_str()).collect()
+    }
+
+    #[test]
+    fn parses_cldr_annotations() {
+        let annotations = fixture_annotations();
+        assert_eq!(
+            annotations["😀"],
+            ["face", "grin", "grinning face", "happy", "smile"]
+        );
+        // Keys drop the presentation selector, and entities are decoded
+        assert!(annotations["❤"].contains(&"love".to_string()));
+        assert_eq!(annotations["&"], ["ampersand", "and"]);
+        // Short names (type="tts") are not keywords
+        assert!(!annotations["🐶"].contains(&"dog face".to_string()));
+    }
+
+    #[test]
+    fn bundles_cldr_keywords() {
+        assert!(annotations()["😀"].contains(&"grin".to_string()));
+    }
+
+    #[test]
+    fn finds_emoji_by_keyword() {
+        let annotations = fixture_annotations();
+        let results = search_emoji_with("happy", &annotations);
+        assert_eq!(results[0].path, "😀");
+        assert!(paths(&search_emoji_with("puppy", &annotations)).contains(&"🐶"));
+        assert!(!paths(&search_emoji_with("happy", &Annotations::new())).contains(&"😀"));
+    }
+
+    #[test]
+    fn finds_emoji_by_name_and_shortcode() {
+        let none = Annotations::new();
+        assert_eq!(search_emoji_with("thumbs up", &none)[0].path, "👍");
+        assert_eq!(search_emoji_with("+1", &none)[0].path, "👍");
+        // Skin tones are offered as secondary actions
+        let thumbs = &search_emoji_with("thumbs up", &none)[0];
+        assert_eq!(thumbs.alt_actions.len(), 6);
+    }
+
+    #[test]
+    fn code_points() {
+        let none = Annotations::new();
+        assert_eq!(paths(&search_emoji_with("U+1F600", &none)), ["😀"]);
+        let e_acute = search_emoji_with("u+e9", &none);
+        assert_eq!(e_acute[0].name, "latin small letter e with acute");
+        assert_eq!(super::code_points("é"), "U+00E9");
+        assert_eq!(parse_code_point("U+110000"), None);
+        assert_eq!(parse_code_point("U+"), None);
+    }
+
+    #[test]
+    fn unicode_names() {
+        assert_eq!(char_name('☃'), Some("SNOWMAN"));
+        assert_eq!(char_name('A'), Some("LATIN CAPITAL LETTER A"));
+        let names = search_names("snowman", 5);
+        assert_eq!(names[0], ('☃', "SNOWMAN"));
+    }
+
+    #[test]
+    fn keeps_room_for_name_and_code_point_matches() {
+        let none = Annotations::new();
+        let results = search_emoji_with("arrow", &none);
+        assert_eq!(results.len(), MAX_RESULTS);
+        // Plenty of emoji match, but named characters such as ⟶ still show
+        let named = results
+            .iter()
+            .filter(|r| r.name == r.name.to_lowercase() && emojis::get(&r.path).is_none());
+        assert_eq!(named.count(), NAME_SLOTS);
+
+        // A bare code point is offered alongside fuzzy matches
+        assert!(paths(&search_emoji_with("2192", &none)).contains(&"→"));
+        assert!(!paths(&search_emoji_with("cafe", &none)).contains(&"\u{cafe}"));
+    }
+}
@@ -0,0 +1,398 @@
+use crate::window_switcher::{WindowIcon, WindowInfo, WindowManager};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ClientMessageEvent, ConnectionExt, EventMask, GetPropertyReply, Window,
+};
+use x11rb::rust_connection::RustConnection;
+
+// Cell icons are drawn at this size
+const ICON_SIZE: u32 = 48;
+
+// _NET_WM_DESKTOP of windows shown on every desktop
+const ALL_DESKTOPS: u32 = 0xFFFF_FFFF;
+
+// Longest property read, in 32-bit units; large enough for a 256px icon set
+const MAX_PROPERTY_LENGTH: u32 = 1 << 20;
+
+x11rb::atom_manager! {
+    pub Atoms: AtomsCookie {
+        _NET_CLIENT_LIST,
+        _NET_CLIENT_LIST_STACKING,
+        _NET_ACTIVE_WINDOW,
+        _NET_CURRENT_DESKTOP,
+        _NET_DESKTOP_NAMES,
+        _NET_WM_NAME,
+        _NET_WM_DESKTOP,
+        _NET_WM_ICON,
+        _NET_WM_STATE,
+        _NET_WM_STATE_SKIP_TASKBAR,
+        _NET_WM_WINDOW_TYPE,
+        _NET_WM_WINDOW_TYPE_DOCK,
+        _NET_WM_WINDOW_TYPE_DESKTOP,
+        UTF8_STRING,
+    }
+}
+
+/// Picks the smallest icon at least `wanted` pixels wide from a _NET_WM_ICON
+/// value (width, height, then the pixels, repeated), or the largest one when
+/// all are smaller
+pub fn parse_net_wm_icon(data: &[u32], wanted: u32) -> Option<WindowIcon> {
+    let mut icons = Vec::new();
+    let mut rest = data;
+    while let [width, height, tail @ ..] = rest {
+        let len = (*width as usize).checked_mul(*height as usize)?;
+        if len == 0 || tail.len() < len {
+            break;
+        }
+        icons.push(WindowIcon {
+            width: *width,
+            height: *height,
+            argb: tail[..len].to_vec(),
+        });
+        rest = &tail[len..];
+    }
+
+    let best_fit = icons
+        .iter()
+        .filter(|icon| icon.width >= wanted)
+        .min_by_key(|icon| icon.width)
+        .cloned();
+    best_fit.or_else(|| icons.into_iter().max_by_key(|icon| icon.width))
+}
+
+/// WM_CLASS holds the instance and class names, each NUL-terminated
+pub fn parse_wm_class(value: &[u8]) -> String {
+    let mut parts = value
+        .split(|b| *b == 0)
+        .map(|part| String::from_utf8_lossy(part).to_string());
+    let instance = parts.next().unwrap_or_default();
+    parts.next().filter(|c| !c.is_empty()).unwrap_or(instance)
+}
+
+/// _NET_DESKTOP_NAMES: UTF-8 names, each NUL-terminated
+pub fn parse_desktop_names(value: &[u8]) -> Vec<String> {
+    let mut names: Vec<String> = value
+        .split(|b| *b == 0)
+        .map(|name| String::from_utf8_lossy(name).to_string())
+        .collect();
+    if value.ends_with(&[0]) {
+        names.pop();
+    }
+    names
+}
+
+pub struct EwmhWindowManager {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+impl EwmhWindowManager {
+    /// Connects to `display`, or $DISPLAY when None; None without an X server
+    pub fn connect(display: Option<&str>) -> Option<Self> {
+        let (conn, screen) = x11rb::connect(display).ok()?;
+        let root = conn.setup().roots.get(screen)?.root;
+        let atoms = Atoms::new(&conn).ok()?.reply().ok()?;
+        Some(EwmhWindowManager { conn, root, atoms })
+    }
+
+    fn property(&self, window: Window, property: Atom, kind: Atom) -> Option<GetPropertyReply> {
+        self.conn
+            .get_property(false, window, property, kind, 0, MAX_PROPERTY_LENGTH)
+            .ok()?
+            .reply()
+            .ok()
+            .filter(|reply| reply.type_ != x11rb::NONE)
+    }
+
+    fn cardinals(&self, window: Window, property: Atom, kind: impl Into<Atom>) -> Vec<u32> {
+        self.property(window, property, kind.into())
+            .and_then(|reply| reply.value32().map(|values| values.collect()))
+            .unwrap_or_default()
+    }
+
+    fn title(&self, window: Window) -> String {
+        if let Some(reply) = self.property(window, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING)
+        {
+            return String::from_utf8_lossy(&reply.value).to_string();
+        }
+        // The ICCCM name is Latin-1
+        self.property(window, AtomEnum::WM_NAME.into(), AtomEnum::ANY.into())
+            .map(|reply| reply.value.iter().map(|b| *b as char).collect())
+            .unwrap_or_default()
+    }
+
+    /// Panels, desktop windows and anything asking to stay off the taskbar
+    fn is_hidden(&self, window: Window) -> bool {
+        let types = self.cardinals(window, self.atoms._NET_WM_WINDOW_TYPE, AtomEnum::ATOM);
+        let states = self.cardinals(window, self.atoms._NET_WM_STATE, AtomEnum::ATOM);
+        types.iter().any(|t| {
+            *t == self.atoms._NET_WM_WINDOW_TYPE_DOCK
+                || *t == self.atoms._NET_WM_WINDOW_TYPE_DESKTOP
+        }) || states.contains(&self.atoms._NET_WM_STATE_SKIP_TASKBAR)
+    }
+
+    fn window_info(&self, window: Window) -> WindowInfo {
+        let class = self
+            .property(window, AtomEnum::WM_CLASS.into(), AtomEnum::STRING.into())
+            .map(|reply| parse_wm_class(&reply.value))
+            .unwrap_or_default();
+        let desktop = self
+            .cardinals(window, self.atoms._NET_WM_DESKTOP, AtomEnum::CARDINAL)
+            .first()
+            .copied()
+            .filter(|d| *d != ALL_DESKTOPS);
+        let icon = parse_net_wm_icon(
+            &self.cardinals(window, self.atoms._NET_WM_ICON, AtomEnum::CARDINAL),
+            ICON_SIZE,
+        );
+        WindowInfo {
+            id: window as u64,
+            title: self.title(window),
+            class,
+            desktop,
+            icon,
+        }
+    }
+
+    /// A client message to the root window, the way pagers ask the window
+    /// manager for things
+    fn send_message(&self, window: Window, message: Atom, data: [u32; 5]) -> bool {
+        let event = ClientMessageEvent::new(32, window, message, data);
+        self.conn
+            .send_event(
+                false,
+                self.root,
+                EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                event,
+            )
+            .is_ok()
+    }
+}
+
+impl WindowManager for EwmhWindowManager {
+    fn list_windows(&self) -> Vec<WindowInfo> {
+        // The stacking list is bottom to top; the plain one is mapping order
+        let mut windows = self.cardinals(
+            self.root,
+            self.atoms._NET_CLIENT_LIST_STACKING,
+            AtomEnum::WINDOW,
+        );
+        if windows.is_empty() {
+            windows = self.cardinals(self.root, self.atoms._NET_CLIENT_LIST, AtomEnum::WINDOW);
+        }
+        windows
+            .into_iter()
+            .rev()
+            .filter(|window| !self.is_hidden(*window))
+            .map(|window| self.window_info(window))
+            .collect()
+    }
+
+    fn active_window(&self) -> Option<u64> {
+        self.cardinals(self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW)
+            .first()
+            .filter(|w| **w != x11rb::NONE)
+            .map(|w| *w as u64)
+    }
+
+    fn activate(&self, id: u64) -> bool {
+        let window = id as Window;
+        let desktop = self
+            .cardinals(window, self.atoms._NET_WM_DESKTOP, AtomEnum::CARDINAL)
+            .first()
+            .copied()
+            .filter(|d| *d != ALL_DESKTOPS);
+        if let Some(desktop) = desktop {
+            let current = self.atoms._NET_CURRENT_DESKTOP;
+            self.send_message(self.root, current, [desktop, x11rb::CURRENT_TIME, 0, 0, 0]);
+        }
+        // Source 2 marks a pager, which window managers let take focus
+        let active = self.atoms._NET_ACTIVE_WINDOW;
+        self.send_message(window, active, [2, x11rb::CURRENT_TIME, 0, 0, 0])
+            && self.conn.flush().is_ok()
+    }
+
+    fn desktop_names(&self) -> Vec<String> {
+        self.property(
+            self.root,
+            self.atoms._NET_DESKTOP_NAMES,
+            self.atoms.UTF8_STRING,
+        )
+        .map(|reply| parse_desktop_names(&reply.value))
+        .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use x11rb::protocol::xproto::{
+        ChangeWindowAttributesAux, CreateWindowAux, PropMode, WindowClass,
+    };
+    use x11rb::protocol::Event;
+    use x11rb::wrapper::ConnectionExt as _;
+
+    /// An Xvfb server on a display of its choosing, stopped when dropped
+    struct Xvfb {
+        server: Child,
+        display: String,
+    }
+
+    impl Xvfb {
+        fn start() -> Self {
+            let mut server = Command::new("Xvfb")
+                .args(["-displayfd", "1", "-nolisten", "tcp"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("Xvfb is not installed");
+            // The display number is written once connections are accepted
+            let mut number = String::new();
+            BufReader::new(server.stdout.take().unwrap())
+                .read_line(&mut number)
+                .unwrap();
+            Xvfb {
+                server,
+                display: format!(":{}", number.trim()),
+            }
+        }
+    }
+
+    impl Drop for Xvfb {
+        fn drop(&mut self) {
+            let _ = self.server.kill();
+            let _ = self.server.wait();
+        }
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb; run with --ignored"]
+    fn lists_and_activates_windows_under_xvfb() {
+        let xvfb = Xvfb::start();
+        // This connection plays the window manager: it sets the EWMH
+        // properties and receives the requests a pager sends
+        let (conn, screen) = x11rb::connect(Some(&xvfb.display)).unwrap();
+        let root = conn.setup().roots[screen].root;
+        let atoms = Atoms::new(&conn).unwrap().reply().unwrap();
+
+        let create = |title: &str, class: &[u8], desktop: u32| {
+            let window = conn.generate_id().unwrap();
+            conn.create_window(
+                x11rb::COPY_DEPTH_FROM_PARENT,
+                window,
+                root,
+                0,
+                0,
+                100,
+                100,
+                0,
+                WindowClass::INPUT_OUTPUT,
+                x11rb::COPY_FROM_PARENT,
+                &CreateWindowAux::new(),
+            )
+            .unwrap();
+            let utf8 = atoms.UTF8_STRING;
+            conn.change_property8(
+                PropMode::REPLACE,
+                window,
+                atoms._NET_WM_NAME,
+                utf8,
+                title.as_bytes(),
+            )
+            .unwrap();
+            conn.change_property8(
+                PropMode::REPLACE,
+                window,
+                AtomEnum::WM_CLASS,
+                AtomEnum::STRING,
+                class,
+            )
+            .unwrap();
+            conn.change_property32(
+                PropMode::REPLACE,
+                window,
+                atoms._NET_WM_DESKTOP,
+                AtomEnum::CARDINAL,
+                &[desktop],
+            )
+            .unwrap();
+            window
+        };
+        let editor = create("main.rs — rufi", b"code\0Code\0", 1);
+        let terminal = create("ssh box", b"xterm\0XTerm\0", ALL_DESKTOPS);
+        let panel = create("panel", b"panel\0Panel\0", ALL_DESKTOPS);
+
+        let set = |window, property, kind: AtomEnum, values: &[u32]| {
+            conn.change_property32(PropMode::REPLACE, window, property, kind, values)
+                .unwrap();
+        };
+        set(
+            editor,
+            atoms._NET_WM_ICON,
+            AtomEnum::CARDINAL,
+            &[1, 1, 1, 2, 2, 2, 2, 2, 2],
+        );
+        set(
+            panel,
+            atoms._NET_WM_WINDOW_TYPE,
+            AtomEnum::ATOM,
+            &[atoms._NET_WM_WINDOW_TYPE_DOCK],
+        );
+        set(
+            root,
+            atoms._NET_CLIENT_LIST,
+            AtomEnum::WINDOW,
+            &[editor, terminal, panel],
+        );
+        set(
+            root,
+            atoms._NET_ACTIVE_WINDOW,
+            AtomEnum::WINDOW,
+            &[terminal],
+        );
+        conn.change_property8(
+            PropMode::REPLACE,
+            root,
+            atoms._NET_DESKTOP_NAMES,
+            atoms.UTF8_STRING,
+            b"web\0code\0",
+        )
+        .unwrap();
+        let redirect =
+            ChangeWindowAttributesAux::new().event_mask(EventMask::SUBSTRUCTURE_REDIRECT);
+        conn.change_window_attributes(root, &redirect)
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let wm = EwmhWindowManager::connect(Some(&xvfb.display)).unwrap();
+        let windows = wm.list_windows();
+        // No stacking list, so newest first; the dock is left out
+        let titles: Vec<&str> = windows.iter().map(|w| w.title.as_str()).collect();
+        assert_eq!(titles, ["ssh box", "main.rs — rufi"]);
+        assert_eq!(windows[0].class, "XTerm");
+        assert_eq!(windows[0].desktop, None);
+        assert_eq!(windows[1].class, "Code");
+        assert_eq!(windows[1].desktop, Some(1));
+        assert_eq!(windows[1].icon.as_ref().map(|icon| icon.width), Some(2));
+        assert_eq!(wm.active_window(), Some(terminal as u64));
+        assert_eq!(wm.desktop_names(), ["web", "code"]);
+
+        // Activating switches to the window's desktop, then asks for focus
+        assert!(wm.activate(editor as u64));
+        let mut messages = Vec::new();
+        while messages.len() < 2 {
+            if let Event::ClientMessage(message) = conn.wait_for_event().unwrap() {
+                messages.push(message);
+            }
+        }
+        assert_eq!(messages[0].type_, atoms._NET_CURRENT_DESKTOP);
+        assert_eq!(messages[0].data.as_data32()[0], 1);
+        assert_eq!(messages[1].type_, atoms._NET_ACTIVE_WINDOW);
+        assert_eq!(messages[1].window, editor);
+        assert_eq!(messages[1].data.as_data32()[0], 2);
+    }
+}
@@ -1,4 +1,6 @@
-use crate::search_mode::SearchResult;
+use crate::config::FilesConfig;
+use crate::locate::locate_files;
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use std::fs;
@@ -7,72 +9,110 @@ use std::sync::OnceLock;
 
 static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
 
+// Candidates gathered from each source before fuzzy ranking, and results shown
+const MAX_CANDIDATES: usize = 50;
+const MAX_RESULTS: usize = 8;
+
 fn get_matcher() -> &'static SkimMatcherV2 {
     MATCHER.get_or_init(SkimMatcherV2::default)
 }
 
-fn search_recursive(
+/// Hidden files and heavy system/build directories, skipped by every search
+pub fn is_ignored_name(file_name: &str) -> bool {
+    file_name.starts_with('.')
+        || file_name == "Library"
+        || file_name == "node_modules"
+        || file_name == "target"
+}
+
+/// What the directory walker does after visiting an entry
+pub enum Walk {
+    Continue,
+    SkipDir,
+    Stop,
+}
+
+/// Walks `dir` depth-first, skipping hidden entries and heavy system/build
+/// directories. `visit` sees every other entry with its file name.
+pub fn walk_dir(dir: &Path, max_depth: usize, visit: &mut dyn FnMut(&fs::DirEntry, &str) -> Walk) {
+    walk_recursive(dir, max_depth, 0, visit);
+}
+
+// Returns false once the visitor asked to stop
+fn walk_recursive(
     dir: &Path,
-    query: &str,
-    results: &mut Vec<SearchResult>,
-    max_results: usize,
     max_depth: usize,
     current_depth: usize,
-) {
-    if results.len() >= max_results || current_depth > max_depth {
-        return;
+    visit: &mut dyn FnMut(&fs::DirEntry, &str) -> Walk,
+) -> bool {
+    if current_depth > max_depth {
+        return true;
     }
 
     let Ok(entries) = fs::read_dir(dir) else {
-        return;
+        return true;
     };
 
     for entry in entries.flatten() {
-        if results.len() >= max_results {
-            break;
-        }
-
         let Ok(file_name) = entry.file_name().into_string() else {
             continue;
         };
 
         // Skip hidden files/directories and system directories
-        if file_name.starts_with('.')
-            || file_name == "Library"
-            || file_name == "node_modules"
-            || file_name == "target"
-        {
+        if is_ignored_name(&file_name) {
             continue;
         }
 
-        // Case-insensitive search
-        if file_name.to_lowercase().contains(&query.to_lowercase()) {
-            if let Ok(path) = entry.path().canonicalize() {
-                results.push(SearchResult::new(
-                    file_name.clone(),
-                    path.to_string_lossy().to_string(),
-                    crate::search_mode::SearchMode::Files,
-                ));
-            }
+        match visit(&entry, &file_name) {
+            Walk::Stop => return false,
+            Walk::SkipDir => continue,
+            Walk::Continue => {}
         }
 
         // Recursively search subdirectories
         if let Ok(metadata) = entry.metadata() {
-            if metadata.is_dir() {
-                search_recursive(
-                    &entry.path(),
-                    query,
-                    results,
-                    max_results,
-                    max_depth,
-                    current_depth + 1,
-                );
+            if metadata.is_dir()
+                && !walk_recursive(&entry.path(), max_depth, current_depth + 1, visit)
+            {
+                return false;
             }
         }
     }
+    true
+}
+
+fn file_result(name: String, path: String) -> SearchResult {
+    SearchResult::new(name, path.clone(), SearchMode::Files)
+        .with_alt_action("Move to Trash", ResultAction::MoveToTrash(path))
 }
 
-pub fn search_files(query: &str) -> Vec<SearchResult> {
+fn search_recursive(
+    dir: &Path,
+    query: &str,
+    results: &mut Vec<SearchResult>,
+    max_results: usize,
+    max_depth: usize,
+) {
+    let query = query.to_lowercase();
+    walk_dir(dir, max_depth, &mut |entry, file_name| {
+        if results.len() >= max_results {
+            return Walk::Stop;
+        }
+
+        // Case-insensitive search
+        if file_name.to_lowercase().contains(&query) {
+            if let Ok(path) = entry.path().canonicalize() {
+                results.push(file_result(
+                    file_name.to_string(),
+                    path.to_string_lossy().to_string(),
+                ));
+            }
+        }
+        Walk::Continue
+    });
+}
+
+pub fn search_files(query: &str, config: &FilesConfig) -> Vec<SearchResult> {
     if query.is_empty() {
         return Vec::new();
     }
@@ -84,7 +124,17 @@ pub fn search_files(query: &str) -> Vec<SearchResult> {
 
     // Search with reduced depth and max results for better performance
     // Depth of 4 is enough for most files while being fast
-    search_recursive(&home, query, &mut results, 50, 4, 0);
+    search_recursive(&home, query, &mut results, MAX_CANDIDATES, 4);
+
+    // The rest of the disk from the locate database
+    for path in locate_files(query, config, MAX_CANDIDATES) {
+        let path = path.to_string_lossy().to_string();
+        if results.iter().any(|r| r.path == path) {
+            continue;
+        }
+        let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+        results.push(file_result(name, path));
+    }
 
     // Apply fuzzy matching on results
     let matcher = get_matcher();
@@ -101,7 +151,7 @@ pub fn search_files(query: &str) -> Vec<SearchResult> {
     scored
         .into_iter()
         .map(|(result, _)| result)
-        .take(8)
+        .take(MAX_RESULTS)
         .collect()
 }
 
@@ -124,11 +174,7 @@ pub fn search_files_random(count: usize) -> Vec<SearchResult> {
                         continue;
                     }
                     if let Ok(path) = entry.path().canonicalize() {
-                        results.push(SearchResult::new(
-                            file_name,
-                            path.to_string_lossy().to_string(),
-                            crate::search_mode::SearchMode::Files,
-                        ));
+                        results.push(file_result(file_name, path.to_string_lossy().to_string()));
                     }
                 }
                 if results.len() >= 20 {
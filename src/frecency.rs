@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Usage {
+    pub count: u32,
+    pub last_used: u64, // Unix seconds
+}
+
+/// How often and how recently results were picked, keyed by `mode:id`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Frecency {
+    pub entries: HashMap<String, Usage>,
+}
+
+impl Frecency {
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    pub fn record(&mut self, key: &str, now: u64) {
+        let usage = self.entries.entry(key.to_string()).or_default();
+        usage.count += 1;
+        usage.last_used = now;
+    }
+
+    /// Use count weighted by recency, in the spirit of Firefox's frecency buckets
+    pub fn score(&self, key: &str, now: u64) -> i64 {
+        let Some(usage) = self.entries.get(key) else {
+            return 0;
+        };
+        let age = now.saturating_sub(usage.last_used);
+        let weight = match age {
+            a if a < 4 * DAY => 100,
+            a if a < 14 * DAY => 70,
+            a if a < 31 * DAY => 50,
+            a if a < 90 * DAY => 30,
+            _ => 10,
+        };
+        usage.count as i64 * weight
+    }
+}
+
+pub fn frecency_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap()
+        .join("rofi-mac")
+        .join("frecency.json")
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Records that a result was picked
+pub fn record_use(key: &str) {
+    let path = frecency_path();
+    let mut frecency = Frecency::load(&path);
+    frecency.record(key, now_secs());
+    frecency.save(&path);
+}
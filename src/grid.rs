@@ -0,0 +1,245 @@
+use crate::keymap::Action;
+use crate::search_mode::SearchResult;
+
+/// Where each result sits in the results grid. Results fill rows left to
+/// right; a result whose section differs from the one before starts a new
+/// row under a header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridLayout {
+    /// Row and column of each result
+    pub cells: Vec<(usize, usize)>,
+    /// Section titles with the row they head
+    pub headers: Vec<(usize, String)>,
+}
+
+impl GridLayout {
+    pub fn new(results: &[SearchResult], columns: usize) -> Self {
+        let columns = columns.max(1);
+        let mut cells = Vec::with_capacity(results.len());
+        let mut headers = Vec::new();
+        let mut row = 0;
+        let mut col = 0;
+        let mut section: Option<&String> = None;
+
+        for (index, result) in results.iter().enumerate() {
+            let new_section = result.section.as_ref().filter(|s| Some(*s) != section);
+            if let Some(title) = new_section {
+                if index > 0 && col > 0 {
+                    row += 1;
+                    col = 0;
+                }
+                headers.push((row, title.clone()));
+            }
+            section = result.section.as_ref();
+
+            if col == columns {
+                row += 1;
+                col = 0;
+            }
+            cells.push((row, col));
+            col += 1;
+        }
+
+        GridLayout { cells, headers }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.cells.last().map_or(0, |(row, _)| row + 1)
+    }
+
+    /// Headers drawn above `row`, including its own
+    pub fn headers_through(&self, row: usize) -> usize {
+        self.headers.iter().filter(|(r, _)| *r <= row).count()
+    }
+
+    /// Distance from the top of the grid to where `row` starts, for rows
+    /// `row_height` apart with headers `header_height` tall
+    pub fn row_top(&self, row: usize, row_height: f64, header_height: f64) -> f64 {
+        row as f64 * row_height + self.headers_through(row) as f64 * header_height
+    }
+
+    /// The results whose rows lie wholly within `height` below `top`, in order
+    pub fn visible(
+        &self,
+        top: f64,
+        height: f64,
+        row_height: f64,
+        header_height: f64,
+    ) -> Vec<usize> {
+        // Half a point of slack for scroll positions between pixels
+        let bottom = top + height + 0.5;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, (row, _))| {
+                let row_top = self.row_top(*row, row_height, header_height);
+                row_top >= top - 0.5 && row_top + row_height <= bottom
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// The result a row up or down from `index`, in the same column or the
+    /// last one of a shorter row, wrapping around at the ends
+    pub fn vertical(&self, index: usize, down: bool) -> usize {
+        let rows = self.rows();
+        let Some(&(row, col)) = self.cells.get(index) else {
+            return 0;
+        };
+        let target = if down {
+            (row + 1) % rows
+        } else {
+            (row + rows - 1) % rows
+        };
+        // Every row starts at column 0, so there is always a match
+        self.cells
+            .iter()
+            .rposition(|(r, c)| *r == target && *c <= col)
+            .unwrap_or(index)
+    }
+
+    /// The result `rows` rows below `index` (above when negative), stopping
+    /// at the first and last rows. Already there, it goes to the first or
+    /// last result.
+    pub fn rows_away(&self, index: usize, rows: isize) -> usize {
+        let Some(&(row, col)) = self.cells.get(index) else {
+            return 0;
+        };
+        let last_row = self.rows().saturating_sub(1);
+        let target = (row as isize + rows).clamp(0, last_row as isize) as usize;
+        if target == row {
+            return if rows > 0 { self.cells.len() - 1 } else { 0 };
+        }
+        self.cells
+            .iter()
+            .rposition(|(r, c)| *r == target && *c <= col)
+            .unwrap_or(index)
+    }
+
+    /// Where a navigation action moves the selection from `index`, with
+    /// `page_rows` rows in view; None for other actions
+    pub fn navigate(&self, action: Action, index: usize, page_rows: usize) -> Option<usize> {
+        let count = self.cells.len();
+        if count == 0 {
+            return None;
+        }
+        let page = page_rows.max(1) as isize;
+        match action {
+            Action::SelectNext => Some((index + 1) % count),
+            Action::SelectPrev => Some((index + count - 1) % count),
+            Action::SelectDown => Some(self.vertical(index, true)),
+            Action::SelectUp => Some(self.vertical(index, false)),
+            Action::PageDown => Some(self.rows_away(index, page)),
+            Action::PageUp => Some(self.rows_away(index, -page)),
+            Action::SelectFirst => Some(0),
+            Action::SelectLast => Some(count - 1),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_mode::SearchMode;
+
+    fn results(count: usize) -> Vec<SearchResult> {
+        (0..count)
+            .map(|i| SearchResult::new(i.to_string(), String::new(), SearchMode::Apps))
+            .collect()
+    }
+
+    /// Seven results in rows of three: 0 1 2 / 3 4 5 / 6
+    fn layout() -> GridLayout {
+        GridLayout::new(&results(7), 3)
+    }
+
+    fn go(layout: &GridLayout, action: Action, index: usize) -> Option<usize> {
+        layout.navigate(action, index, 2)
+    }
+
+    #[test]
+    fn fills_rows_and_starts_sections_on_new_rows() {
+        assert_eq!(layout().rows(), 3);
+        assert_eq!(layout().cells[6], (2, 0));
+
+        let mut sectioned = results(5);
+        for result in &mut sectioned[..2] {
+            result.section = Some("Recent".to_string());
+        }
+        for result in &mut sectioned[2..] {
+            result.section = Some("All".to_string());
+        }
+        let layout = GridLayout::new(&sectioned, 3);
+        assert_eq!(layout.cells, [(0, 0), (0, 1), (1, 0), (1, 1), (1, 2)]);
+        assert_eq!(
+            layout.headers,
+            [(0, "Recent".to_string()), (1, "All".to_string())]
+        );
+        assert_eq!(layout.row_top(1, 40.0, 20.0), 80.0);
+    }
+
+    #[test]
+    fn next_and_prev_wrap_around() {
+        let layout = layout();
+        assert_eq!(go(&layout, Action::SelectNext, 2), Some(3));
+        assert_eq!(go(&layout, Action::SelectNext, 6), Some(0));
+        assert_eq!(go(&layout, Action::SelectPrev, 0), Some(6));
+    }
+
+    #[test]
+    fn up_and_down_keep_the_column() {
+        let layout = layout();
+        assert_eq!(go(&layout, Action::SelectDown, 1), Some(4));
+        assert_eq!(go(&layout, Action::SelectUp, 4), Some(1));
+        // The partial last row has only its first column
+        assert_eq!(go(&layout, Action::SelectDown, 5), Some(6));
+        // Wrapping between the last and first rows
+        assert_eq!(go(&layout, Action::SelectDown, 6), Some(0));
+        assert_eq!(go(&layout, Action::SelectUp, 2), Some(6));
+    }
+
+    #[test]
+    fn tab_and_shift_tab_follow_the_default_bindings() {
+        let keymap = crate::keymap::Keymap::new(&Default::default());
+        let action = |text| keymap.action(&crate::keymap::KeyChord::parse(text).unwrap());
+        let layout = layout();
+        assert_eq!(go(&layout, action("tab").unwrap(), 6), Some(0));
+        assert_eq!(go(&layout, action("shift+tab").unwrap(), 0), Some(6));
+    }
+
+    #[test]
+    fn pages_stop_at_the_ends() {
+        let layout = layout();
+        assert_eq!(go(&layout, Action::PageDown, 1), Some(6));
+        assert_eq!(go(&layout, Action::PageDown, 6), Some(6));
+        assert_eq!(go(&layout, Action::PageUp, 5), Some(2));
+        assert_eq!(go(&layout, Action::PageUp, 2), Some(0));
+        // On the last row already, page down goes to the last result
+        assert_eq!(go(&layout, Action::PageDown, 5), Some(6));
+        assert_eq!(layout.navigate(Action::PageDown, 0, 1), Some(3));
+        assert_eq!(layout.navigate(Action::PageUp, 2, 1), Some(0));
+    }
+
+    #[test]
+    fn home_and_end_jump_to_the_ends() {
+        let layout = layout();
+        assert_eq!(go(&layout, Action::SelectFirst, 4), Some(0));
+        assert_eq!(go(&layout, Action::SelectLast, 1), Some(6));
+    }
+
+    #[test]
+    fn numbers_only_whole_rows_in_view() {
+        let layout = layout();
+        // Rows 40 tall; the view shows the second row and half of the third
+        assert_eq!(layout.visible(40.0, 60.0, 40.0, 0.0), [3, 4, 5]);
+        assert_eq!(layout.visible(39.8, 80.0, 40.0, 0.0), [3, 4, 5, 6]);
+        assert!(layout.visible(0.0, 30.0, 40.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn other_actions_and_empty_grids_do_not_move() {
+        assert_eq!(go(&layout(), Action::Accept, 1), None);
+        assert_eq!(go(&GridLayout::new(&[], 3), Action::SelectNext, 0), None);
+    }
+}
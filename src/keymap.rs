@@ -0,0 +1,410 @@
+use crate::config::KeysConfig;
+use crate::search_mode::SearchResult;
+use crate::terminal::shell_quote;
+use std::collections::HashMap;
+
+// NSEvent modifier flags
+pub const SHIFT: u64 = 1 << 17;
+pub const CONTROL: u64 = 1 << 18;
+pub const OPTION: u64 = 1 << 19;
+pub const COMMAND: u64 = 1 << 20;
+
+// macOS virtual key codes of keys without a printable character, and of the
+// digit row, which some layouts only reach with Shift
+const NAMED_KEYS: &[(u16, &str)] = &[
+    (36, "enter"),
+    (76, "enter"), // Keypad
+    (48, "tab"),
+    (49, "space"),
+    (51, "backspace"),
+    (53, "escape"),
+    (117, "delete"),
+    (115, "home"),
+    (119, "end"),
+    (116, "pageup"),
+    (121, "pagedown"),
+    (123, "left"),
+    (124, "right"),
+    (125, "down"),
+    (126, "up"),
+    (29, "0"),
+    (18, "1"),
+    (19, "2"),
+    (20, "3"),
+    (21, "4"),
+    (23, "5"),
+    (22, "6"),
+    (26, "7"),
+    (28, "8"),
+    (25, "9"),
+];
+
+const KEY_ALIASES: &[(&str, &str)] = &[
+    ("return", "enter"),
+    ("esc", "escape"),
+    ("del", "delete"),
+    ("pgup", "pageup"),
+    ("page_up", "pageup"),
+    ("pgdn", "pagedown"),
+    ("page_down", "pagedown"),
+    ("plus", "+"),
+];
+
+const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    ("right", "select-next"),
+    ("left", "select-prev"),
+    ("down", "select-down"),
+    ("up", "select-up"),
+    ("ctrl+n", "select-down"),
+    ("ctrl+p", "select-up"),
+    ("pagedown", "page-down"),
+    ("pageup", "page-up"),
+    ("home", "select-first"),
+    ("end", "select-last"),
+    ("tab", "select-next"),
+    ("shift+tab", "select-prev"),
+    ("enter", "accept"),
+    ("alt+enter", "accept-alt"),
+    ("ctrl+w", "delete-word"),
+    ("alt+backspace", "delete-word"),
+    ("shift+right", "mode-next"),
+    ("shift+left", "mode-prev"),
+    ("escape", "cancel"),
+    ("alt+1", "accept-1"),
+    ("alt+2", "accept-2"),
+    ("alt+3", "accept-3"),
+    ("alt+4", "accept-4"),
+    ("alt+5", "accept-5"),
+    ("alt+6", "accept-6"),
+    ("alt+7", "accept-7"),
+    ("alt+8", "accept-8"),
+    ("alt+9", "accept-9"),
+    ("cmd+1", "accept-1"),
+    ("cmd+2", "accept-2"),
+    ("cmd+3", "accept-3"),
+    ("cmd+4", "accept-4"),
+    ("cmd+5", "accept-5"),
+    ("cmd+6", "accept-6"),
+    ("cmd+7", "accept-7"),
+    ("cmd+8", "accept-8"),
+    ("cmd+9", "accept-9"),
+];
+
+/// Everything a key can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    SelectNext,
+    SelectPrev,
+    SelectDown,
+    SelectUp,
+    PageDown,
+    PageUp,
+    SelectFirst,
+    SelectLast,
+    Accept,
+    AcceptAlt,
+    DeleteWord,
+    ModeNext,
+    ModePrev,
+    Cancel,
+    /// Activates the Nth result in view, from 1, as numbered on the grid
+    AcceptVisible(usize),
+    /// Runs the Nth of the configured custom commands, from 1
+    Custom(usize),
+}
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Action> {
+        let name = name.trim().to_lowercase();
+        let action = match name.as_str() {
+            "select-next" => Action::SelectNext,
+            "select-prev" => Action::SelectPrev,
+            "select-down" => Action::SelectDown,
+            "select-up" => Action::SelectUp,
+            "page-down" => Action::PageDown,
+            "page-up" => Action::PageUp,
+            "select-first" => Action::SelectFirst,
+            "select-last" => Action::SelectLast,
+            "accept" => Action::Accept,
+            "accept-alt" => Action::AcceptAlt,
+            "delete-word" => Action::DeleteWord,
+            "mode-next" => Action::ModeNext,
+            "mode-prev" => Action::ModePrev,
+            "cancel" => Action::Cancel,
+            _ => {
+                let (kind, n) = name.rsplit_once('-')?;
+                let n: usize = n.parse().ok().filter(|n| (1..=9).contains(n))?;
+                return match kind {
+                    "accept" => Some(Action::AcceptVisible(n)),
+                    "custom" => Some(Action::Custom(n)),
+                    _ => None,
+                };
+            }
+        };
+        Some(action)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub key: String, // A key name such as "enter" or "pagedown", else the lower-case character
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub cmd: bool,
+}
+
+impl KeyChord {
+    /// Parses chords such as "ctrl+n", "Alt+Enter", "shift+tab" or "cmd+plus"
+    pub fn parse(text: &str) -> Option<KeyChord> {
+        let text = text.trim().to_lowercase();
+        // A trailing "+" is the key itself, as in "ctrl++"
+        let (modifiers, key) = match text.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None if text == "+" => ("", "+"),
+            None => match text.rsplit_once('+') {
+                Some((modifiers, key)) => (modifiers, key),
+                None => ("", text.as_str()),
+            },
+        };
+
+        let key = KEY_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == key)
+            .map_or(key, |(_, name)| *name);
+        let known = NAMED_KEYS.iter().any(|(_, name)| *name == key);
+        if key.is_empty() || (!known && key.chars().count() != 1) {
+            return None;
+        }
+
+        let mut chord = KeyChord {
+            key: key.to_string(),
+            ctrl: false,
+            alt: false,
+            shift: false,
+            cmd: false,
+        };
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier.trim() {
+                "ctrl" | "control" => chord.ctrl = true,
+                "alt" | "option" | "opt" => chord.alt = true,
+                "shift" => chord.shift = true,
+                "cmd" | "command" | "super" => chord.cmd = true,
+                _ => return None,
+            }
+        }
+        Some(chord)
+    }
+
+    /// The chord of a key-down event, from its key code, its characters
+    /// ignoring modifiers and its modifier flags
+    pub fn from_event(key_code: u16, characters: &str, modifier_flags: u64) -> Option<KeyChord> {
+        let key = match NAMED_KEYS.iter().find(|(code, _)| *code == key_code) {
+            Some((_, name)) => name.to_string(),
+            None => characters.chars().next()?.to_lowercase().to_string(),
+        };
+        Some(KeyChord {
+            key,
+            ctrl: modifier_flags & CONTROL != 0,
+            alt: modifier_flags & OPTION != 0,
+            shift: modifier_flags & SHIFT != 0,
+            cmd: modifier_flags & COMMAND != 0,
+        })
+    }
+}
+
+/// Key chords and the actions they run: the defaults, with the configured
+/// bindings on top
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: HashMap<KeyChord, Action>,
+}
+
+impl Keymap {
+    pub fn new(config: &KeysConfig) -> Self {
+        let defaults = DEFAULT_BINDINGS
+            .iter()
+            .map(|(chord, action)| (chord.to_string(), action.to_string()));
+        let mut bindings = HashMap::new();
+        // Unknown chords and actions are skipped, "none" drops a default
+        for (chord, action) in defaults.chain(config.bindings.clone()) {
+            let Some(chord) = KeyChord::parse(&chord) else {
+                continue;
+            };
+            if action.trim().eq_ignore_ascii_case("none") {
+                bindings.remove(&chord);
+            } else if let Some(action) = Action::from_name(&action) {
+                bindings.insert(chord, action);
+            }
+        }
+        Keymap { bindings }
+    }
+
+    pub fn action(&self, chord: &KeyChord) -> Option<Action> {
+        self.bindings.get(chord).copied()
+    }
+}
+
+/// A custom command for the selected result, with `{path}` and `{name}`
+/// replaced by its quoted path and name
+pub fn custom_command(template: &str, result: &SearchResult) -> String {
+    // One pass, so placeholders inside a substituted value stay quoted
+    let mut command = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        command.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("{path}") {
+            command.push_str(&shell_quote(&result.path));
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{name}") {
+            command.push_str(&shell_quote(&result.name));
+            rest = after;
+        } else {
+            command.push('{');
+            rest = &rest[1..];
+        }
+    }
+    command.push_str(rest);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_mode::SearchMode;
+
+    fn chord(text: &str) -> KeyChord {
+        KeyChord::parse(text).unwrap()
+    }
+
+    #[test]
+    fn parses_chords() {
+        let ctrl_n = chord("ctrl+n");
+        assert_eq!(ctrl_n.key, "n");
+        assert!(ctrl_n.ctrl && !ctrl_n.alt && !ctrl_n.shift && !ctrl_n.cmd);
+
+        let alt_enter = chord("Alt+Enter");
+        assert_eq!(alt_enter.key, "enter");
+        assert!(alt_enter.alt && !alt_enter.ctrl);
+        assert_eq!(chord("option+return"), alt_enter);
+
+        let shift_tab = chord("shift+tab");
+        assert_eq!(shift_tab.key, "tab");
+        assert!(shift_tab.shift);
+
+        let cmd_plus = chord("cmd+plus");
+        assert_eq!(cmd_plus.key, "+");
+        assert!(cmd_plus.cmd);
+        assert_eq!(chord("command++"), cmd_plus);
+        assert_eq!(chord("+").key, "+");
+    }
+
+    #[test]
+    fn rejects_unknown_chords() {
+        assert_eq!(KeyChord::parse("hyper+n"), None);
+        assert_eq!(KeyChord::parse("ctrl+nope"), None);
+        assert_eq!(KeyChord::parse("ctrl+"), None);
+        assert_eq!(KeyChord::parse(""), None);
+    }
+
+    #[test]
+    fn events_match_parsed_chords() {
+        assert_eq!(
+            KeyChord::from_event(36, "\r", OPTION),
+            Some(chord("alt+enter"))
+        );
+        assert_eq!(
+            KeyChord::from_event(48, "\t", SHIFT),
+            Some(chord("shift+tab"))
+        );
+        assert_eq!(
+            KeyChord::from_event(45, "N", CONTROL),
+            Some(chord("ctrl+n"))
+        );
+        // The digit row by key code, whatever the layout types there
+        assert_eq!(KeyChord::from_event(18, "&", COMMAND), Some(chord("cmd+1")));
+        assert_eq!(KeyChord::from_event(45, "", 0), None);
+    }
+
+    #[test]
+    fn parses_action_names() {
+        assert_eq!(Action::from_name(" Page-Down "), Some(Action::PageDown));
+        assert_eq!(
+            Action::from_name("accept-3"),
+            Some(Action::AcceptVisible(3))
+        );
+        assert_eq!(Action::from_name("custom-9"), Some(Action::Custom(9)));
+        assert_eq!(Action::from_name("custom-10"), None);
+        assert_eq!(Action::from_name("accept-0"), None);
+        assert_eq!(Action::from_name("launch"), None);
+    }
+
+    #[test]
+    fn overrides_and_unbinds_defaults() {
+        let defaults = Keymap::new(&KeysConfig::default());
+        assert_eq!(defaults.action(&chord("ctrl+n")), Some(Action::SelectDown));
+        assert_eq!(defaults.action(&chord("ctrl+w")), Some(Action::DeleteWord));
+        assert_eq!(defaults.action(&chord("ctrl+j")), None);
+
+        let bindings = [
+            ("ctrl+j", "select-down"),
+            ("Ctrl+N", "page-down"),
+            ("ctrl+w", "none"),
+            ("ctrl+k", "no-such-action"),
+            ("hyper+x", "accept"),
+            ("ctrl+e", "custom-1"),
+        ];
+        let keymap = Keymap::new(&KeysConfig {
+            bindings: bindings
+                .iter()
+                .map(|(chord, action)| (chord.to_string(), action.to_string()))
+                .collect(),
+            custom: Vec::new(),
+        });
+        assert_eq!(keymap.action(&chord("ctrl+j")), Some(Action::SelectDown));
+        assert_eq!(keymap.action(&chord("ctrl+n")), Some(Action::PageDown));
+        assert_eq!(keymap.action(&chord("ctrl+w")), None);
+        assert_eq!(keymap.action(&chord("ctrl+k")), None);
+        assert_eq!(keymap.action(&chord("ctrl+e")), Some(Action::Custom(1)));
+        // Other defaults stay
+        assert_eq!(
+            keymap.action(&chord("alt+backspace")),
+            Some(Action::DeleteWord)
+        );
+    }
+
+    #[test]
+    fn custom_commands_quote_the_result() {
+        let result = SearchResult::new(
+            "it's here".to_string(),
+            "/tmp/a b".to_string(),
+            SearchMode::Files,
+        );
+        assert_eq!(
+            custom_command("open -R {path} # {name}", &result),
+            format!(
+                "open -R {} # {}",
+                shell_quote("/tmp/a b"),
+                shell_quote("it's here")
+            )
+        );
+    }
+
+    #[test]
+    fn custom_commands_do_not_expand_placeholders_in_values() {
+        let result = SearchResult::new(
+            "$(touch pwned)".to_string(),
+            "https://example.com/{name}".to_string(),
+            SearchMode::Bookmarks,
+        );
+        assert_eq!(
+            custom_command("echo {path} {name} {other}", &result),
+            "echo 'https://example.com/{name}' '$(touch pwned)' {other}"
+        );
+        assert_eq!(
+            custom_command("{path{name}", &result),
+            "{path'$(touch pwned)'"
+        );
+    }
+}
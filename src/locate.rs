@@ -0,0 +1,266 @@
+use crate::config::FilesConfig;
+use crate::file_search::is_ignored_name;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const MLOCATE_MAGIC: &[u8] = b"\0mlocate";
+
+// Tried in order when no database is configured; only mlocate's format is
+// read directly, plocate's compressed one goes through `locate`
+const MLOCATE_DBS: &[&str] = &[
+    "/var/lib/mlocate/mlocate.db",
+    "/var/cache/locate/mlocate.db",
+];
+
+// Enough `locate` output to find `limit` name matches among path matches
+const LOCATE_OUTPUT_LINES: usize = 2000;
+
+// Entries read from the database per query. A system database holds millions,
+// too many to read through on every keystroke, so rare names far down the
+// tree can be missed.
+const MAX_SCANNED_ENTRIES: usize = 250_000;
+
+/// Reads up to the next NUL into `buf`, without the NUL. None at the end of
+/// the data or when it stops mid-string.
+fn read_cstr(reader: &mut dyn BufRead, buf: &mut Vec<u8>) -> Option<()> {
+    buf.clear();
+    reader.read_until(0, buf).ok()?;
+    (buf.pop() == Some(0)).then_some(())
+}
+
+/// `needle` is already lower case. Databases hold millions of names, so ASCII
+/// ones are compared in place.
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    if haystack.is_ascii() && needle.is_ascii() {
+        haystack
+            .as_bytes()
+            .windows(needle.len().max(1))
+            .any(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+    } else {
+        haystack.to_lowercase().contains(needle)
+    }
+}
+
+/// Walks an mlocate database (see mlocate.db(5)), calling `visit` with each
+/// entry's directory and name until it returns false. The database is read as
+/// it goes, since it can be hundreds of megabytes. None when the data is not
+/// an mlocate database; a truncated file stops early.
+pub fn read_mlocate(
+    reader: &mut dyn BufRead,
+    visit: &mut dyn FnMut(&str, &str) -> bool,
+) -> Option<()> {
+    // Header: magic, configuration size, version, visibility flag, padding
+    let mut header = [0; 16];
+    reader.read_exact(&mut header).ok()?;
+    if !header.starts_with(MLOCATE_MAGIC) {
+        return None;
+    }
+    let conf_size = u32::from_be_bytes(header[8..12].try_into().ok()?) as u64;
+    let mut buf = Vec::new();
+    read_cstr(reader, &mut buf)?; // Root of the indexed tree
+    io::copy(&mut reader.take(conf_size), &mut io::sink()).ok()?;
+
+    let mut name = Vec::new();
+    loop {
+        // Directory header: 8 bytes of seconds, 4 of nanoseconds, 4 of padding
+        let mut dir_header = [0; 16];
+        if reader.read_exact(&mut dir_header).is_err() || read_cstr(reader, &mut buf).is_none() {
+            return Some(());
+        }
+        let dir = String::from_utf8_lossy(&buf);
+        loop {
+            let mut kind = [0];
+            if reader.read_exact(&mut kind).is_err() {
+                return Some(());
+            }
+            // 0 is a file, 1 a subdirectory, 2 ends the directory
+            if kind[0] == 2 {
+                break;
+            }
+            if read_cstr(reader, &mut name).is_none() {
+                return Some(());
+            }
+            if !visit(&dir, &String::from_utf8_lossy(&name)) {
+                return Some(());
+            }
+        }
+    }
+}
+
+/// Passes `keep` the entries whose name contains `query` (lower case) among the
+/// first `max_entries`, until it returns false
+fn find_in_mlocate(
+    reader: &mut dyn BufRead,
+    query: &str,
+    max_entries: usize,
+    keep: &mut dyn FnMut(PathBuf) -> bool,
+) -> Option<()> {
+    let mut scanned = 0;
+    read_mlocate(reader, &mut |dir, name| {
+        scanned += 1;
+        if scanned > max_entries {
+            return false;
+        }
+        !contains_ignore_case(name, query) || keep(Path::new(dir).join(name))
+    })
+}
+
+/// Whether a path goes through anything the home directory walk skips
+pub fn is_ignored_path(path: &Path) -> bool {
+    path.components().any(|component| match component {
+        std::path::Component::Normal(name) => name.to_str().is_none_or(is_ignored_name),
+        _ => false,
+    })
+}
+
+/// Opens the database for one search; nothing is kept between searches
+fn mlocate_db(config: &FilesConfig) -> Option<BufReader<File>> {
+    // Usually only readable by the mlocate group, hence the fallback
+    let file = if config.locate_db.is_empty() {
+        MLOCATE_DBS.iter().find_map(|path| File::open(path).ok())
+    } else {
+        File::open(crate::projects::expand_home(&config.locate_db)).ok()
+    }?;
+    Some(BufReader::with_capacity(1 << 16, file))
+}
+
+fn run_locate(query: &str) -> Vec<PathBuf> {
+    // -l is understood by mlocate, plocate and the BSD locate on macOS
+    let Ok(output) = Command::new("locate")
+        .arg("-i")
+        .arg("-l")
+        .arg(LOCATE_OUTPUT_LINES.to_string())
+        .arg(query)
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Paths anywhere on disk whose file name contains `query` (ignoring case),
+/// from the mlocate database when readable and `locate` otherwise. Stale
+/// entries and ignored directories are dropped.
+pub fn locate_files(query: &str, config: &FilesConfig, limit: usize) -> Vec<PathBuf> {
+    if !config.locate || query.is_empty() {
+        return Vec::new();
+    }
+    let query = query.to_lowercase();
+    let name_matches = |path: &Path| {
+        path.file_name()
+            .is_some_and(|n| contains_ignore_case(&n.to_string_lossy(), &query))
+    };
+
+    // Adds a path unless it is ignored, gone or already there
+    let mut found: Vec<PathBuf> = Vec::new();
+    let mut keep = |path: PathBuf| {
+        if found.len() >= limit || is_ignored_path(&path) {
+            return found.len() < limit;
+        }
+        if let Ok(path) = path.canonicalize() {
+            if !found.contains(&path) {
+                found.push(path);
+            }
+        }
+        found.len() < limit
+    };
+
+    let read = mlocate_db(config)
+        .and_then(|mut db| find_in_mlocate(&mut db, &query, MAX_SCANNED_ENTRIES, &mut keep));
+    if read.is_none() {
+        for path in run_locate(&query) {
+            if name_matches(&path) && !keep(path) {
+                break;
+            }
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A database with `/`, holding `etc/` and `notes.txt`, then `/etc` with `hosts`
+    fn database() -> Vec<u8> {
+        let mut data = MLOCATE_MAGIC.to_vec();
+        data.extend(3u32.to_be_bytes()); // Configuration size
+        data.extend([0, 0, 0, 0]); // Version, visibility, padding
+        data.extend(b"/\0");
+        data.extend(b"abc");
+        for (dir, entries) in [
+            ("/", &[(1, "etc"), (0, "notes.txt")][..]),
+            ("/etc", &[(0, "hosts")]),
+        ] {
+            data.extend([0; 16]);
+            data.extend(dir.as_bytes());
+            data.push(0);
+            for (kind, name) in entries {
+                data.push(*kind);
+                data.extend(name.as_bytes());
+                data.push(0);
+            }
+            data.push(2);
+        }
+        data
+    }
+
+    fn entries(data: &[u8], limit: usize) -> Option<Vec<String>> {
+        let mut seen = Vec::new();
+        read_mlocate(&mut &data[..], &mut |dir, name| {
+            seen.push(format!("{} {}", dir, name));
+            seen.len() < limit
+        })?;
+        Some(seen)
+    }
+
+    #[test]
+    fn walks_mlocate_entries() {
+        assert_eq!(
+            entries(&database(), usize::MAX).unwrap(),
+            ["/ etc", "/ notes.txt", "/etc hosts"]
+        );
+        assert_eq!(entries(&database(), 1).unwrap(), ["/ etc"]);
+    }
+
+    #[test]
+    fn stops_at_truncated_data() {
+        let data = database();
+        assert_eq!(
+            entries(&data[..data.len() - 4], usize::MAX).unwrap(),
+            ["/ etc", "/ notes.txt"]
+        );
+        assert_eq!(entries(b"\0plocate-and-more", usize::MAX), None);
+        assert_eq!(entries(b"", usize::MAX), None);
+    }
+
+    #[test]
+    fn scans_a_bounded_number_of_entries() {
+        let find = |query: &str, max_entries: usize| {
+            let mut found = Vec::new();
+            find_in_mlocate(&mut &database()[..], query, max_entries, &mut |path| {
+                found.push(path);
+                true
+            })
+            .unwrap();
+            found
+        };
+        assert_eq!(find("hosts", 10), [PathBuf::from("/etc/hosts")]);
+        // `hosts` is the third entry
+        assert!(find("hosts", 2).is_empty());
+        assert_eq!(find("t", 10).len(), 3);
+    }
+
+    #[test]
+    fn compares_ignoring_case() {
+        assert!(contains_ignore_case("README.md", "readme"));
+        assert!(contains_ignore_case("Überblick.txt", "über"));
+        assert!(!contains_ignore_case("notes", "todo"));
+    }
+}
@@ -1,9 +1,45 @@
 mod app_search;
+mod bookmarks;
+mod calculator;
+mod clipboard;
+mod clipboard_history;
+mod combi;
 mod config;
+mod contacts;
+mod date_calc;
 mod delegate;
+mod emoji;
+#[cfg(target_os = "linux")]
+mod ewmh;
 mod file_search;
+mod frecency;
+mod grid;
+mod keymap;
+mod locate;
+mod man_pages;
+mod modes;
+mod notes;
+mod pass;
+mod processes;
+mod projects;
+mod providers;
 mod search_mode;
+mod snippets;
+mod sqlite;
+mod ssh_config;
+mod ssh_hosts;
 mod system_commands;
+mod template;
+mod terminal;
+mod timezone;
+mod todo_txt;
+mod trash;
+mod tmux;
+mod units;
+mod vcard;
+mod web_search;
+mod window_switcher;
+mod workspaces;
 pub mod ui;
 pub mod window;
 
@@ -23,6 +59,9 @@ struct Args {
     text_color: Option<String>,
     selection_color: Option<String>,
     theme: Option<String>,
+    mode: Option<String>,
+    modes: Option<String>, // Comma-separated mode names
+    command: Vec<String>, // Subcommand and its arguments, e.g. `clipd clear`
 }
 
 fn parse_args() -> Result<Args, lexopt::Error> {
@@ -35,6 +74,9 @@ fn parse_args() -> Result<Args, lexopt::Error> {
         text_color: None,
         selection_color: None,
         theme: None,
+        mode: None,
+        modes: None,
+        command: Vec::new(),
     };
 
     let mut parser = lexopt::Parser::from_env();
@@ -48,9 +90,14 @@ fn parse_args() -> Result<Args, lexopt::Error> {
             Long("text-color") => args.text_color = Some(parser.value()?.parse()?),
             Long("selection-color") => args.selection_color = Some(parser.value()?.parse()?),
             Short('t') | Long("theme") => args.theme = Some(parser.value()?.parse()?),
+            Short('m') | Long("mode") => args.mode = Some(parser.value()?.parse()?),
+            Long("modes") => args.modes = Some(parser.value()?.parse()?),
             Long("help") => {
                 eprintln!("rufi - A minimal macOS application launcher\n");
-                eprintln!("USAGE: rufi [OPTIONS]\n");
+                eprintln!("USAGE: rufi [OPTIONS] [COMMAND]\n");
+                eprintln!("COMMANDS:");
+                eprintln!("  clipd                       Record clipboard history in the foreground");
+                eprintln!("  clipd clear                 Clear unpinned clipboard history\n");
                 eprintln!("OPTIONS:");
                 eprintln!("  -w, --width <WIDTH>         Window width");
                 eprintln!("      --height <HEIGHT>       Window height");
@@ -60,9 +107,12 @@ fn parse_args() -> Result<Args, lexopt::Error> {
                 eprintln!("      --text-color <HEX>      Text color");
                 eprintln!("      --selection-color <HEX> Selection color");
                 eprintln!("  -t, --theme <THEME>         Theme (gruvbox, 8bit, catppuccin, modern)");
+                eprintln!("  -m, --mode <MODE>           Start in a mode, e.g. files, run, all");
+                eprintln!("      --modes <MODES>         Comma-separated modes to enable");
                 eprintln!("      --help                  Show this help");
                 std::process::exit(0);
             }
+            Value(value) => args.command.push(value.string()?),
             _ => return Err(arg.unexpected()),
         }
     }
@@ -75,6 +125,33 @@ fn main() {
         std::process::exit(1);
     });
 
+    let modes = args.modes.iter().flat_map(|m| m.split(','));
+    for name in args.mode.iter().map(String::as_str).chain(modes) {
+        if search_mode::SearchMode::from_name(name).is_none() {
+            eprintln!("Error: unknown mode '{}'", name.trim());
+            std::process::exit(1);
+        }
+    }
+
+    if let Some((command, rest)) = args.command.split_first() {
+        match command.as_str() {
+            "clipd" => {
+                let config = config::Config::load();
+                clipboard_history::run_clipd(rest, &config.clipboard);
+                return;
+            }
+            // Started by pass mode to clear a copied secret later
+            "clip-clear" => {
+                clipboard::run_clip_clear(rest);
+                return;
+            }
+            other => {
+                eprintln!("Error: unknown command '{}'", other);
+                std::process::exit(1);
+            }
+        }
+    }
+
     unsafe {
         let app = NSApp();
 
@@ -119,6 +196,12 @@ fn main() {
                 _ => config::Config::theme_gruvbox(),
             };
         }
+        if let Some(modes) = args.modes {
+            config.modes.enabled = modes.split(',').map(|m| m.trim().to_string()).collect();
+        }
+        if let Some(mode) = args.mode {
+            config.modes.start = mode;
+        }
 
         // Index applications (do this first before creating window)
         let apps = app_search::index_applications();
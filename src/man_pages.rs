@@ -0,0 +1,513 @@
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::terminal::shell_quote;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+// Loaded once per launch; re-reading the JSON, or re-running apropos when
+// it is stale, on every keystroke stalls typing
+static MAN_PAGES: OnceLock<Vec<ManPage>> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+// Man pages change with installs only, so the index is kept for a day
+const CACHE_TTL_SECS: u64 = 24 * 3600;
+
+// Used when neither MANPATH nor `manpath` says otherwise
+const DEFAULT_MANPATH: &[&str] = &[
+    "/usr/share/man",
+    "/usr/local/share/man",
+    "/opt/homebrew/share/man",
+    "/Library/Developer/CommandLineTools/usr/share/man",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManPage {
+    pub name: String,
+    pub section: String, // "1", "3pm", "1ssl", ...
+    pub description: String,
+}
+
+impl ManPage {
+    /// The `man` invocation that shows this page
+    pub fn command(&self) -> String {
+        format!(
+            "man {} {}",
+            shell_quote(&self.section),
+            shell_quote(&self.name)
+        )
+    }
+}
+
+/// Parses `apropos`/`whatis` output. Both the man-db form `ls (1) - ...` and the
+/// BSD form `gzip(1), gunzip(1) - ...` are understood; each name listed before
+/// the dash becomes its own page.
+pub fn parse_apropos(output: &str) -> Vec<ManPage> {
+    let mut pages = Vec::new();
+
+    for line in output.lines() {
+        let Some((names, description)) = line.split_once(" - ") else {
+            continue;
+        };
+        for entry in names.split(',') {
+            let entry = entry.trim();
+            let Some((name, rest)) = entry.split_once('(') else {
+                continue;
+            };
+            let Some(section) = rest.strip_suffix(')') else {
+                continue;
+            };
+            let name = name.trim();
+            if name.is_empty() || section.is_empty() {
+                continue;
+            }
+            pages.push(ManPage {
+                name: name.to_string(),
+                section: section.to_string(),
+                description: description.trim().to_string(),
+            });
+        }
+    }
+
+    pages
+}
+
+/// Splits a page file name such as `git-commit.1.gz` into name and section.
+/// `dir_section` is the suffix of its `manN` directory, which the file's
+/// section has to start with.
+pub fn parse_man_filename(file_name: &str, dir_section: &str) -> Option<(String, String)> {
+    let base = [".gz", ".bz2", ".xz", ".zst", ".Z"]
+        .iter()
+        .find_map(|ext| file_name.strip_suffix(ext))
+        .unwrap_or(file_name);
+    let (name, section) = base.rsplit_once('.')?;
+    let dir_prefix = dir_section.chars().next()?;
+    (!name.is_empty() && section.starts_with(dir_prefix))
+        .then(|| (name.to_string(), section.to_string()))
+}
+
+// Special characters from groff_char(7) that turn up in descriptions; any
+// other is dropped
+const SPECIAL_CHARS: &[(&str, &str)] = &[
+    ("em", "—"),
+    ("en", "–"),
+    ("hy", "-"),
+    ("mi", "-"),
+    ("aq", "'"),
+    ("dq", "\""),
+    ("lq", "“"),
+    ("rq", "”"),
+    ("oq", "‘"),
+    ("cq", "’"),
+    ("bu", "•"),
+    ("co", "©"),
+    ("rg", "®"),
+    ("tm", "™"),
+    ("mu", "×"),
+    ("de", "°"),
+    ("->", "→"),
+    ("<=", "≤"),
+    (">=", "≥"),
+];
+
+/// The name of a `\(xx`, `\[name]` or single-character escape argument,
+/// `opener` being the character after the escape
+fn escape_name(opener: Option<char>, chars: &mut std::str::Chars) -> String {
+    match opener {
+        Some('(') => chars.take(2).collect(),
+        Some('[') => chars.take_while(|&c| c != ']').collect(),
+        Some(c) => c.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Removes the roff escapes that show up in NAME lines
+fn strip_roff(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // Font changes: \fB, \fI, \fR, \fP, \f(CW, \f[B]
+            Some('f') => {
+                let opener = chars.next();
+                escape_name(opener, &mut chars);
+            }
+            // Special characters, \(em or \[em], and strings such as \*(lq
+            Some(kind @ ('(' | '[' | '*')) => {
+                let opener = if kind == '*' {
+                    chars.next()
+                } else {
+                    Some(kind)
+                };
+                let name = escape_name(opener, &mut chars);
+                if let Some((_, replacement)) = SPECIAL_CHARS.iter().find(|(n, _)| *n == name) {
+                    out.push_str(replacement);
+                }
+            }
+            Some('-') => out.push('-'),
+            Some('e') => out.push('\\'),
+            Some('&') | Some('c') => {}
+            Some(' ') => out.push(' '),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extracts the one-line description from a page's NAME section, in either
+/// man (`name \- text`) or mdoc (`.Nd text`) markup
+pub fn parse_name_section(roff: &str) -> Option<String> {
+    let mut in_name = false;
+    let mut text = String::new();
+
+    for line in roff.lines() {
+        let line = line.trim_end();
+        if let Some(heading) = line
+            .strip_prefix(".SH")
+            .or_else(|| line.strip_prefix(".Sh"))
+        {
+            if in_name {
+                break;
+            }
+            in_name = heading
+                .trim()
+                .trim_matches('"')
+                .eq_ignore_ascii_case("NAME");
+            continue;
+        }
+        if !in_name {
+            continue;
+        }
+        if let Some(description) = line.strip_prefix(".Nd ") {
+            return Some(strip_roff(description)).filter(|d| !d.is_empty());
+        }
+        // Other requests (.Nm, .PP, comments) carry no description
+        if line.starts_with('.') || line.starts_with('\'') {
+            continue;
+        }
+        text.push(' ');
+        text.push_str(line);
+    }
+
+    let text = strip_roff(&text);
+    let (_, description) = text.split_once(" - ")?;
+    Some(description.trim().to_string()).filter(|d| !d.is_empty())
+}
+
+/// Directories listed in MANPATH or by `manpath`; an empty MANPATH entry
+/// stands for the defaults
+fn man_dirs() -> Vec<PathBuf> {
+    let from_command = || {
+        let output = Command::new("manpath").output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let manpath = std::env::var("MANPATH")
+        .ok()
+        .or_else(from_command)
+        .unwrap_or_default();
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for entry in manpath.split(':') {
+        if entry.is_empty() {
+            dirs.extend(DEFAULT_MANPATH.iter().map(PathBuf::from));
+        } else {
+            dirs.push(PathBuf::from(entry));
+        }
+    }
+    let mut seen = HashSet::new();
+    dirs.retain(|d| seen.insert(d.clone()));
+    dirs
+}
+
+/// First few KB of an uncompressed page, enough to reach the NAME section
+fn read_page_head(path: &PathBuf) -> Option<String> {
+    let mut buffer = vec![0u8; 4096];
+    let read = fs::File::open(path).ok()?.read(&mut buffer).ok()?;
+    buffer.truncate(read);
+    Some(String::from_utf8_lossy(&buffer).to_string())
+}
+
+/// Every page file under the man directories, in MANPATH order
+fn scan_man_dirs() -> Vec<(ManPage, PathBuf)> {
+    let mut pages = Vec::new();
+
+    for dir in man_dirs() {
+        let Ok(sections) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut sections: Vec<_> = sections.flatten().collect();
+        sections.sort_by_key(|e| e.file_name());
+
+        for section_dir in sections {
+            let dir_name = section_dir.file_name().to_string_lossy().to_string();
+            // Skips cat pages and localized trees
+            let Some(dir_section) = dir_name.strip_prefix("man") else {
+                continue;
+            };
+            let Ok(files) = fs::read_dir(section_dir.path()) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let file_name = file.file_name().to_string_lossy().to_string();
+                if let Some((name, section)) = parse_man_filename(&file_name, dir_section) {
+                    let page = ManPage {
+                        name,
+                        section,
+                        description: String::new(),
+                    };
+                    pages.push((page, file.path()));
+                }
+            }
+        }
+    }
+
+    pages
+}
+
+/// Builds the index from the page files, taking descriptions from `apropos`
+/// where its database exists and from the pages themselves otherwise
+pub fn index_man_pages() -> Vec<ManPage> {
+    let apropos = Command::new("apropos")
+        .arg(".")
+        .output()
+        .map(|o| parse_apropos(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default();
+    let mut descriptions: HashMap<(String, String), String> = apropos
+        .iter()
+        .map(|p| ((p.name.clone(), p.section.clone()), p.description.clone()))
+        .collect();
+
+    let mut pages = Vec::new();
+    let mut seen = HashSet::new();
+    for (mut page, path) in scan_man_dirs() {
+        let key = (page.name.clone(), page.section.clone());
+        if !seen.insert(key.clone()) {
+            continue;
+        }
+        page.description = match descriptions.remove(&key) {
+            Some(description) => description,
+            // Compressed pages are only described when apropos knows them
+            None if path.extension().is_some_and(|e| e == page.section.as_str()) => {
+                read_page_head(&path)
+                    .and_then(|roff| parse_name_section(&roff))
+                    .unwrap_or_default()
+            }
+            None => String::new(),
+        };
+        pages.push(page);
+    }
+
+    // Pages apropos found somewhere outside the scanned directories
+    pages.extend(
+        apropos
+            .into_iter()
+            .filter(|p| seen.insert((p.name.clone(), p.section.clone()))),
+    );
+    pages.sort_by(|a, b| a.name.cmp(&b.name).then(a.section.cmp(&b.section)));
+    pages
+}
+
+/// None without a cache folder, in which case the index is rebuilt each run
+fn cache_path() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("rofi-mac").join("man_pages.json"))
+}
+
+fn load_cache() -> Option<Vec<ManPage>> {
+    let path = cache_path()?;
+    let elapsed = fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+    if elapsed.as_secs() >= CACHE_TTL_SECS {
+        return None;
+    }
+    let contents = fs::read_to_string(&path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn save_cache(pages: &[ManPage]) {
+    let Some(path) = cache_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string(pages) {
+        let _ = fs::write(&path, json);
+    }
+}
+
+pub fn load_man_pages() -> Vec<ManPage> {
+    if let Some(cached) = load_cache() {
+        return cached;
+    }
+    let pages = index_man_pages();
+    save_cache(&pages);
+    pages
+}
+
+fn page_to_result(page: &ManPage) -> SearchResult {
+    let mut result = SearchResult::new(
+        format!("{}({})", page.name, page.section),
+        page.command(),
+        SearchMode::Man,
+    )
+    .with_alt_action("Copy man command", ResultAction::Copy(page.command()));
+    if !page.description.is_empty() {
+        result = result.with_hint(&page.description);
+    }
+    result
+}
+
+pub fn search_man_pages(query: &str) -> Vec<SearchResult> {
+    let pages = MAN_PAGES.get_or_init(load_man_pages);
+
+    if query.is_empty() {
+        // Commands are what people look up most
+        return pages
+            .iter()
+            .filter(|p| p.section == "1")
+            .take(15)
+            .map(page_to_result)
+            .collect();
+    }
+
+    let matcher = get_matcher();
+    let mut scored: Vec<(&ManPage, i64)> = pages
+        .iter()
+        .filter_map(|page| {
+            // Names count for more than words in the description; an exact name
+            // wins outright, commands before library calls
+            let name_score = matcher.fuzzy_match(&page.name, query).map(|s| s * 3);
+            let description_score = matcher.fuzzy_match(&page.description, query);
+            let mut score = name_score.max(description_score)?;
+            if page.name.eq_ignore_ascii_case(query.trim()) {
+                score += 1000;
+            }
+            if page.section == "1" {
+                score += 5;
+            }
+            Some((page, score))
+        })
+        .collect();
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(page, _)| page_to_result(page))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(name: &str, section: &str, description: &str) -> ManPage {
+        ManPage {
+            name: name.to_string(),
+            section: section.to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_man_db_apropos() {
+        let output = "ls (1)               - list directory contents\n\
+                      printf (3)           - formatted output conversion\n\
+                      openssl-req (1ssl)   - PKCS#10 certificate request - and more\n\
+                      nothing appropriate.\n";
+        assert_eq!(
+            parse_apropos(output),
+            [
+                page("ls", "1", "list directory contents"),
+                page("printf", "3", "formatted output conversion"),
+                page(
+                    "openssl-req",
+                    "1ssl",
+                    "PKCS#10 certificate request - and more"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_bsd_apropos() {
+        let output = "gzip(1), gunzip(1), zcat(1) - compression/decompression tool\n\
+                      git-commit(1)            - Record changes to the repository\n\
+                      broken(, (1) - no name\n";
+        assert_eq!(
+            parse_apropos(output),
+            [
+                page("gzip", "1", "compression/decompression tool"),
+                page("gunzip", "1", "compression/decompression tool"),
+                page("zcat", "1", "compression/decompression tool"),
+                page("git-commit", "1", "Record changes to the repository"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_man_filenames() {
+        let parse = |file, dir| parse_man_filename(file, dir);
+        assert_eq!(
+            parse("git-commit.1.gz", "1"),
+            Some(("git-commit".into(), "1".into()))
+        );
+        assert_eq!(parse("Carp.3pm", "3"), Some(("Carp".into(), "3pm".into())));
+        assert_eq!(
+            parse("openssl.1ssl.Z", "1"),
+            Some(("openssl".into(), "1ssl".into()))
+        );
+        assert_eq!(parse("ls.1", "3"), None);
+        assert_eq!(parse("README", "1"), None);
+        assert_eq!(parse(".1.gz", "1"), None);
+    }
+
+    #[test]
+    fn parses_man_name_sections() {
+        let man = ".TH LS 1\n.SH NAME\nls \\- list directory\n.\\\" comment\ncontents\n.SH SYNOPSIS\nls - not this\n";
+        assert_eq!(
+            parse_name_section(man).as_deref(),
+            Some("list directory contents")
+        );
+        let mdoc = ".Dd May 1\n.Sh \"NAME\"\n.Nm gzip\n.Nd compression\\(emdecompression tool\n.Sh SYNOPSIS\n";
+        assert_eq!(
+            parse_name_section(mdoc).as_deref(),
+            Some("compression—decompression tool")
+        );
+        assert_eq!(parse_name_section(".SH SYNOPSIS\nls - list\n"), None);
+        assert_eq!(parse_name_section(".SH NAME\nls\n.SH SYNOPSIS\n"), None);
+    }
+
+    #[test]
+    fn strips_roff_escapes() {
+        assert_eq!(
+            strip_roff("\\fBls\\fR \\- list \\fIdirectory\\fP   contents"),
+            "ls - list directory contents"
+        );
+        assert_eq!(
+            strip_roff("\\f(CWcode\\fP and \\f[B]bold\\f[]\\&"),
+            "code and bold"
+        );
+        // Two-character and bracketed names, decoded or dropped
+        assert_eq!(
+            strip_roff("a\\(emb \\[lq]c\\[rq] \\*(lqd\\*(rq e\\(xx f\\e"),
+            "a—b “c” “d” e f\\"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::config::Config;
+use crate::search_mode::SearchMode;
+use crate::window_switcher::has_window_manager;
+
+// Apps mode has no prefix of its own, it takes everything else. Only the
+// modes typed most often get one out of the box, so common first characters
+// stay searchable; `modes.prefixes` adds the rest.
+const DEFAULT_PREFIXES: &[(SearchMode, &str)] = &[
+    (SearchMode::Files, "/"),
+    (SearchMode::Run, ":"),
+    (SearchMode::Calc, "="),
+    (SearchMode::Clipboard, "\""),
+    (SearchMode::Emoji, ";"),
+];
+
+// The placeholder only has room for a few
+const MAX_HINTS: usize = 6;
+
+/// The modes that can be used, in their usual order. Names that match no
+/// mode are ignored; if none match, every mode is enabled. Windows mode is
+/// left out when there is no window backend, as on macOS for now.
+pub fn enabled_modes(config: &Config) -> Vec<SearchMode> {
+    let enabled: Vec<SearchMode> = config
+        .modes
+        .enabled
+        .iter()
+        .filter_map(|name| SearchMode::from_name(name))
+        .collect();
+    SearchMode::MODES
+        .iter()
+        .copied()
+        .filter(|mode| enabled.is_empty() || enabled.contains(mode))
+        .filter(|mode| *mode != SearchMode::Windows || has_window_manager())
+        .collect()
+}
+
+/// The configured prefix of a mode, else its built-in one; empty for none
+pub fn prefix(mode: SearchMode, config: &Config) -> &str {
+    config
+        .modes
+        .prefixes
+        .iter()
+        .find(|(name, _)| SearchMode::from_name(name) == Some(mode))
+        .map(|(_, prefix)| prefix.as_str())
+        .or_else(|| {
+            DEFAULT_PREFIXES
+                .iter()
+                .find(|(m, _)| *m == mode)
+                .map(|(_, prefix)| *prefix)
+        })
+        .unwrap_or("")
+}
+
+/// Where unprefixed queries go: combi when configured as the default, else
+/// apps, else the first enabled mode
+pub fn default_mode(config: &Config) -> SearchMode {
+    let enabled = enabled_modes(config);
+    if config.combi.default && enabled.contains(&SearchMode::Combi) {
+        SearchMode::Combi
+    } else if enabled.contains(&SearchMode::Apps) {
+        SearchMode::Apps
+    } else {
+        enabled.first().copied().unwrap_or(SearchMode::Apps)
+    }
+}
+
+/// The mode to open in, when one is configured and enabled
+pub fn start_mode(config: &Config) -> Option<SearchMode> {
+    SearchMode::from_name(&config.modes.start).filter(|mode| enabled_modes(config).contains(mode))
+}
+
+/// Splits a query into its mode and the text after the prefix. The longest
+/// matching prefix wins, so `//` can mean something other than `/`.
+pub fn detect_mode(query: &str, config: &Config) -> (SearchMode, String) {
+    let mut best: Option<(SearchMode, &str)> = None;
+    for mode in enabled_modes(config) {
+        let prefix = prefix(mode, config);
+        if !prefix.is_empty()
+            && query.starts_with(prefix)
+            && best.is_none_or(|(_, longest)| prefix.len() > longest.len())
+        {
+            best = Some((mode, prefix));
+        }
+    }
+    match best {
+        Some((mode, prefix)) => (mode, query[prefix.len()..].to_string()),
+        None => (default_mode(config), query.to_string()),
+    }
+}
+
+/// The enabled mode after `current`, or before it, wrapping around
+pub fn cycle_mode(current: SearchMode, config: &Config, forward: bool) -> SearchMode {
+    let modes = enabled_modes(config);
+    let Some(index) = modes.iter().position(|mode| *mode == current) else {
+        return modes.first().copied().unwrap_or(current);
+    };
+    let next = if forward {
+        (index + 1) % modes.len()
+    } else {
+        (index + modes.len() - 1) % modes.len()
+    };
+    modes[next]
+}
+
+/// "/ files   : run ..." for the enabled modes that have a prefix, cut off
+/// after the first few
+pub fn prefix_hints(config: &Config) -> String {
+    let mut hints: Vec<String> = enabled_modes(config)
+        .into_iter()
+        .filter(|mode| !prefix(*mode, config).is_empty())
+        .map(|mode| format!("{} {}", prefix(mode, config), mode.label()))
+        .collect();
+    if hints.len() > MAX_HINTS {
+        hints.truncate(MAX_HINTS);
+        hints.push("…".to_string());
+    }
+    hints.join("   ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(prefixes: &[(&str, &str)]) -> Config {
+        let mut config = Config::theme_gruvbox();
+        config.modes.prefixes = prefixes
+            .iter()
+            .map(|(mode, prefix)| (mode.to_string(), prefix.to_string()))
+            .collect();
+        config
+    }
+
+    #[test]
+    fn only_common_modes_have_default_prefixes() {
+        let config = config(&[]);
+        assert_eq!(
+            detect_mode("/notes", &config),
+            (SearchMode::Files, "notes".to_string())
+        );
+        assert_eq!(
+            detect_mode("\"quote", &config),
+            (SearchMode::Clipboard, "quote".to_string())
+        );
+        // Other modes have no prefix until one is configured
+        for query in ["%host", "#repo", "$session", "?ls", ".all", "@now"] {
+            assert_eq!(detect_mode(query, &config).0, SearchMode::Apps, "{}", query);
+        }
+    }
+
+    #[test]
+    fn configured_prefixes_replace_and_disable() {
+        let config = config(&[("ssh", "%"), ("files", "f "), ("emoji", "")]);
+        assert_eq!(
+            detect_mode("%box", &config),
+            (SearchMode::Ssh, "box".to_string())
+        );
+        assert_eq!(
+            detect_mode("f notes", &config),
+            (SearchMode::Files, "notes".to_string())
+        );
+        assert_eq!(detect_mode("/notes", &config).0, SearchMode::Apps);
+        assert_eq!(detect_mode(";tada", &config).0, SearchMode::Apps);
+    }
+
+    #[test]
+    fn hints_list_prefixed_modes_and_stop_early() {
+        let labels = |config: &Config| {
+            [
+                SearchMode::Files,
+                SearchMode::Run,
+                SearchMode::Calc,
+                SearchMode::Clipboard,
+                SearchMode::Emoji,
+            ]
+            .map(|mode| format!("{} {}", prefix(mode, config), mode.label()))
+            .join("   ")
+        };
+        let plain = config(&[]);
+        assert_eq!(prefix_hints(&plain), labels(&plain));
+
+        let busy = config(&[("ssh", "%"), ("tmux", "$"), ("man", "?")]);
+        let hints = prefix_hints(&busy);
+        assert_eq!(hints.split("   ").count(), MAX_HINTS + 1);
+        assert!(hints.ends_with("   …"));
+    }
+}
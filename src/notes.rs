@@ -0,0 +1,275 @@
+use crate::config::NotesConfig;
+use crate::date_calc::Clock;
+use crate::projects::expand_home;
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::todo_txt::{mark_done, new_task, parse_line, TodoFilter, TodoItem};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+/// Markdown files get `- [ ]` task lines; everything else is todo.txt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteFormat {
+    TodoTxt,
+    Markdown,
+}
+
+impl NoteFormat {
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("md") | Some("markdown") => NoteFormat::Markdown,
+            _ => NoteFormat::TodoTxt,
+        }
+    }
+}
+
+/// A line of the notes file that is an entry, with the line kept verbatim so
+/// edits can find it again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Note {
+    pub line: String,
+    pub item: TodoItem,
+}
+
+pub fn notes_path(config: &NotesConfig) -> PathBuf {
+    expand_home(&config.file)
+}
+
+/// Parses an entry line. Markdown entries are list items, optionally with a
+/// checkbox; their text is read like todo.txt for dates and tags.
+pub fn parse_note(format: NoteFormat, line: &str) -> Option<TodoItem> {
+    match format {
+        NoteFormat::TodoTxt => parse_line(line),
+        NoteFormat::Markdown => {
+            let item = line.trim_start().strip_prefix("- ")?;
+            let (done, text) = if let Some(text) = item.strip_prefix("[ ] ") {
+                (false, text)
+            } else if let Some(text) = item
+                .strip_prefix("[x] ")
+                .or_else(|| item.strip_prefix("[X] "))
+            {
+                (true, text)
+            } else {
+                (false, item)
+            };
+            let mut parsed = parse_line(text)?;
+            parsed.done = done;
+            Some(parsed)
+        }
+    }
+}
+
+/// The line appended for a new entry
+pub fn format_note(format: NoteFormat, text: &str, date: &str, time: &str) -> String {
+    match format {
+        NoteFormat::TodoTxt => new_task(text, date),
+        NoteFormat::Markdown => format!("- [ ] {} {} {}", date, time, text.trim()),
+    }
+}
+
+/// The line with its entry marked done
+pub fn complete_note(format: NoteFormat, line: &str, date: &str) -> String {
+    match format {
+        NoteFormat::TodoTxt => mark_done(line, date),
+        NoteFormat::Markdown => {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let item = line
+                .trim_start()
+                .strip_prefix("- ")
+                .unwrap_or(line.trim_start());
+            let text = item.strip_prefix("[ ] ").unwrap_or(item);
+            if text.starts_with("[x] ") || text.starts_with("[X] ") {
+                line.to_string()
+            } else {
+                format!("{}- [x] {}", indent, text)
+            }
+        }
+    }
+}
+
+pub fn load_notes(path: &Path) -> Vec<Note> {
+    let format = NoteFormat::for_path(path);
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            parse_note(format, line).map(|item| Note {
+                line: line.to_string(),
+                item,
+            })
+        })
+        .collect()
+}
+
+/// Rewrites the file with the first line equal to `line` replaced, or dropped
+/// when `replacement` is None
+fn rewrite_line(path: &Path, line: &str, replacement: Option<&str>) {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return;
+    };
+    let mut lines: Vec<&str> = contents.lines().collect();
+    let Some(index) = lines.iter().position(|l| *l == line) else {
+        return;
+    };
+    match replacement {
+        Some(new_line) => lines[index] = new_line,
+        None => {
+            lines.remove(index);
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    if !updated.is_empty() {
+        updated.push('\n');
+    }
+    let _ = fs::write(path, updated);
+}
+
+fn append_line(path: &Path, line: &str) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let mut contents = fs::read_to_string(path).unwrap_or_default();
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(line);
+    contents.push('\n');
+    let _ = fs::write(path, contents);
+}
+
+/// Adds, completes or deletes an entry in the notes file
+pub fn apply_action(action: &ResultAction, config: &NotesConfig) {
+    let path = notes_path(config);
+    let format = NoteFormat::for_path(&path);
+    let (date, time) = Clock::system().local_date_time();
+
+    match action {
+        ResultAction::AddNote(text) => append_line(&path, &format_note(format, text, &date, &time)),
+        ResultAction::CompleteNote(line) => {
+            rewrite_line(&path, line, Some(&complete_note(format, line, &date)))
+        }
+        ResultAction::DeleteNote(line) => rewrite_line(&path, line, None),
+        _ => {}
+    }
+}
+
+fn note_hint(item: &TodoItem) -> String {
+    let mut parts = Vec::new();
+    if item.done {
+        parts.push("done".to_string());
+    }
+    if let Some(priority) = item.priority {
+        parts.push(format!("({})", priority));
+    }
+    if let Some(due) = &item.due {
+        parts.push(format!("due {}", due));
+    } else if let Some(created) = &item.creation_date {
+        parts.push(created.clone());
+    }
+    parts.join(" · ")
+}
+
+fn note_to_result(note: &Note, path: &str) -> SearchResult {
+    let name = if note.item.done {
+        format!("✓ {}", note.item.text)
+    } else {
+        note.item.text.clone()
+    };
+    let mut result = SearchResult::new(name, path.to_string(), SearchMode::Notes);
+    if !note.item.done {
+        result = result
+            .with_action(ResultAction::CompleteNote(note.line.clone()))
+            .with_alt_action("Mark done", ResultAction::CompleteNote(note.line.clone()));
+    }
+    let hint = note_hint(&note.item);
+    if !hint.is_empty() {
+        result = result.with_hint(&hint);
+    }
+    result
+        .with_alt_action(
+            "Delete",
+            ResultAction::Confirm(
+                format!("Delete \"{}\"", note.item.text),
+                Box::new(ResultAction::DeleteNote(note.line.clone())),
+            ),
+        )
+        .with_alt_action("Open file", ResultAction::Open)
+        .with_alt_action("Copy text", ResultAction::Copy(note.item.text.clone()))
+}
+
+/// Open entries first, by priority, then by due date, then in file order
+fn sort_key(item: &TodoItem) -> (bool, char, String) {
+    (
+        item.done,
+        item.priority.unwrap_or('~'), // Sorts after every priority letter
+        item.due.clone().unwrap_or_else(|| "9999".to_string()),
+    )
+}
+
+/// `+ text` (with a space) captures a new entry; anything else searches, where
+/// `@context` and `+project` words filter the entries
+pub fn search_notes(query: &str, config: &NotesConfig) -> Vec<SearchResult> {
+    let path = notes_path(config);
+    let path_str = path.to_string_lossy().to_string();
+
+    let capture = query.strip_prefix(' ').map(str::trim).unwrap_or_default();
+    let add_result = |text: &str| {
+        SearchResult::new(
+            format!("Add: {}", text),
+            path_str.clone(),
+            SearchMode::Notes,
+        )
+        .with_action(ResultAction::AddNote(text.to_string()))
+        .with_hint(&format!(
+            "to {}",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ))
+    };
+    if !capture.is_empty() {
+        return vec![add_result(capture)];
+    }
+
+    let notes = load_notes(&path);
+    let filter = TodoFilter::parse(query);
+    let matcher = get_matcher();
+
+    let mut scored: Vec<(&Note, i64)> = notes
+        .iter()
+        .filter(|note| filter.matches_tags(&note.item))
+        .filter_map(|note| {
+            if filter.text.is_empty() {
+                return Some((note, 0));
+            }
+            matcher
+                .fuzzy_match(&note.item.text, &filter.text)
+                .map(|score| (note, score))
+        })
+        .collect();
+
+    if filter.text.is_empty() {
+        scored.sort_by_key(|(note, _)| sort_key(&note.item));
+    } else {
+        scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    }
+
+    let mut results: Vec<SearchResult> = scored
+        .into_iter()
+        .take(15)
+        .map(|(note, _)| note_to_result(note, &path_str))
+        .collect();
+
+    // Nothing found for plain text: offer to add it instead
+    if results.is_empty() && !query.trim().is_empty() {
+        results.push(add_result(query.trim()));
+    }
+    results
+}
@@ -0,0 +1,270 @@
+use crate::clipboard::copy_secret;
+use crate::config::PassConfig;
+use crate::projects::expand_home;
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::terminal::shell_quote;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+// Stores are shallow; this only guards against symlink loops
+const MAX_DEPTH: usize = 16;
+
+/// The store folder: the configured one, else $PASSWORD_STORE_DIR like `pass`
+/// itself, else ~/.password-store
+pub fn store_dir(config: &PassConfig) -> PathBuf {
+    if !config.dir.is_empty() {
+        return expand_home(&config.dir);
+    }
+    match std::env::var("PASSWORD_STORE_DIR") {
+        Ok(dir) if !dir.is_empty() => expand_home(&dir),
+        _ => dirs::home_dir().unwrap_or_default().join(".password-store"),
+    }
+}
+
+fn collect_entries(dir: &Path, prefix: &str, depth: usize, entries: &mut Vec<String>) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        // .git, .gpg-id, .extensions
+        if file_name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        // Follows symlinks, which people use to share folders between stores
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if depth < MAX_DEPTH {
+                let nested = format!("{}{}/", prefix, file_name);
+                collect_entries(&path, &nested, depth + 1, entries);
+            }
+        } else if let Some(name) = file_name.strip_suffix(".gpg") {
+            entries.push(format!("{}{}", prefix, name));
+        }
+    }
+}
+
+/// Entry names as `pass` shows them (`web/github.com`), read from the file
+/// names only; nothing is decrypted
+pub fn list_entries(store: &Path) -> Vec<String> {
+    let mut entries = Vec::new();
+    collect_entries(store, "", 0, &mut entries);
+    entries.sort();
+    entries
+}
+
+/// A configured command with `{entry}` replaced by the quoted entry name;
+/// without the placeholder the name is appended
+pub fn entry_command(template: &str, entry: &str) -> String {
+    let quoted = shell_quote(entry);
+    if template.contains("{entry}") {
+        template.replace("{entry}", &quoted)
+    } else {
+        format!("{} {}", template, quoted)
+    }
+}
+
+/// Finds `field: value` among the lines after the password, ignoring the
+/// case of the field name
+pub fn parse_field(contents: &str, field: &str) -> Option<String> {
+    contents.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case(field)
+            .then(|| value.trim().to_string())
+    })
+}
+
+/// Runs a configured command for the entry and returns what it printed
+fn entry_output(template: &str, entry: &str) -> io::Result<String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(entry_command(template, entry))
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!("could not decrypt {}", entry)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Seconds a copied secret stays on the clipboard, $PASSWORD_STORE_CLIP_TIME
+/// like `pass -c`
+fn clip_time() -> u64 {
+    std::env::var("PASSWORD_STORE_CLIP_TIME")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(45)
+}
+
+/// Copies the entry's password, its first line. Like every copy here it goes
+/// through our clipboard, marked so clipboard managers leave it alone, rather
+/// than `pass -c`, which history tools cannot tell from other text; it is
+/// cleared after [`clip_time`] all the same.
+pub fn copy_password(entry: &str, config: &PassConfig) -> io::Result<()> {
+    let contents = entry_output(&config.show_command, entry)?;
+    let password = contents
+        .lines()
+        .next()
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| io::Error::other(format!("{} has no password", entry)))?;
+    copy_secret(password, clip_time())
+}
+
+/// Copies the current one-time code printed by the OTP command
+pub fn copy_otp(entry: &str, config: &PassConfig) -> io::Result<()> {
+    let output = entry_output(&config.otp_command, entry)?;
+    let code = output.trim();
+    if code.is_empty() {
+        return Err(io::Error::other(format!("{} has no OTP", entry)));
+    }
+    copy_secret(code, clip_time())
+}
+
+/// Decrypts the entry with the show command and copies one field
+pub fn copy_field(entry: &str, field: &str, config: &PassConfig) -> io::Result<()> {
+    let contents = entry_output(&config.show_command, entry)?;
+    let value = parse_field(&contents, field)
+        .ok_or_else(|| io::Error::other(format!("{} has no {} field", entry, field)))?;
+    copy_secret(&value, clip_time())
+}
+
+fn entry_to_result(entry: &str, config: &PassConfig) -> SearchResult {
+    let mut result = SearchResult::new(entry.to_string(), entry.to_string(), SearchMode::Pass)
+        .with_action(ResultAction::CopyPassword(entry.to_string()))
+        .with_hint("copy password")
+        .with_alt_action("Copy OTP", ResultAction::CopyPassOtp(entry.to_string()));
+    for field in &config.fields {
+        result = result.with_alt_action(
+            &format!("Copy {}", field),
+            ResultAction::CopyPassField(entry.to_string(), field.clone()),
+        );
+    }
+    result
+}
+
+pub fn search_pass(query: &str, config: &PassConfig) -> Vec<SearchResult> {
+    let entries = list_entries(&store_dir(config));
+
+    if query.is_empty() {
+        return entries
+            .iter()
+            .take(15)
+            .map(|e| entry_to_result(e, config))
+            .collect();
+    }
+
+    let matcher = get_matcher();
+    let mut scored: Vec<(&String, i64)> = entries
+        .iter()
+        .filter_map(|entry| {
+            // The last path component is usually the site or account name
+            let base = entry.rsplit('/').next().unwrap_or(entry);
+            let base_score = matcher.fuzzy_match(base, query).map(|s| s * 2);
+            let full_score = matcher.fuzzy_match(entry, query);
+            base_score.max(full_score).map(|score| (entry, score))
+        })
+        .collect();
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(entry, _)| entry_to_result(entry, config))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_commands_quote_the_entry() {
+        assert_eq!(
+            entry_command("pass show {entry}", "web/github.com"),
+            "pass show web/github.com"
+        );
+        assert_eq!(
+            entry_command("gopass show -o", "work/it's me"),
+            format!("gopass show -o {}", shell_quote("work/it's me"))
+        );
+        assert_eq!(
+            entry_command("pass otp {entry} | tr -d ' '", "a $(b)"),
+            "pass otp 'a $(b)' | tr -d ' '"
+        );
+    }
+
+    #[test]
+    fn parses_fields_after_the_password() {
+        let contents = "login: not-a-field\nLogin:  ada \nurl: https://example.com:8443/x\n";
+        assert_eq!(parse_field(contents, "login").as_deref(), Some("ada"));
+        assert_eq!(
+            parse_field(contents, "URL").as_deref(),
+            Some("https://example.com:8443/x")
+        );
+        assert_eq!(parse_field(contents, "otp"), None);
+        assert_eq!(parse_field("login: only a password", "login"), None);
+    }
+
+    #[test]
+    fn lists_entries_from_file_names() {
+        let store = std::env::temp_dir()
+            .join(format!("rufi-pass-{}", std::process::id()))
+            .join(".password-store");
+        let _ = fs::remove_dir_all(&store);
+        for file in [
+            "email.gpg",
+            "web/github.com.gpg",
+            "web/deep/er/bank.gpg",
+            "web/notes.txt",
+            ".gpg-id",
+            ".git/config.gpg",
+            "web/.gpg-id",
+        ] {
+            let path = store.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        std::os::unix::fs::symlink(store.join("web/deep"), store.join("shared")).unwrap();
+        // A loop back to the store stops at the depth limit
+        std::os::unix::fs::symlink(&store, store.join("web/loop")).unwrap();
+
+        let entries = list_entries(&store);
+        let outside_loop: Vec<&str> = entries
+            .iter()
+            .map(String::as_str)
+            .filter(|e| !e.contains("loop/"))
+            .collect();
+        assert_eq!(
+            outside_loop,
+            [
+                "email",
+                "shared/er/bank",
+                "web/deep/er/bank",
+                "web/github.com"
+            ]
+        );
+        assert!(entries.len() > outside_loop.len());
+
+        let config = PassConfig {
+            dir: store.to_string_lossy().to_string(),
+            ..PassConfig::default()
+        };
+        assert_eq!(store_dir(&config), store);
+
+        let _ = fs::remove_dir_all(store.parent().unwrap());
+    }
+}
@@ -0,0 +1,509 @@
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+// CPU time per pid from the previous listing, so CPU usage reflects the last
+// moment rather than the whole lifetime of the process
+static LAST_SAMPLE: Mutex<Option<(Instant, HashMap<u32, f64>)>> = Mutex::new(None);
+
+// Samples closer together than this are too noisy to compare
+const MIN_SAMPLE_SECS: f64 = 0.25;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Process {
+    pub pid: u32,
+    pub name: String,
+    pub command: String, // Full command line, or the executable when unreadable
+    pub exe: String,     // Executable path, empty when unknown
+    pub user: String,
+    pub cpu_time: f64, // Seconds of CPU used so far
+    pub age: f64,      // Seconds since the process started
+    pub memory: u64,   // Resident bytes
+    pub cpu_percent: f64,
+}
+
+/// Fields of `/proc/<pid>/stat` used for the listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct ProcStat {
+    pub comm: String,
+    pub utime: u64,     // Clock ticks
+    pub stime: u64,     // Clock ticks
+    pub starttime: u64, // Clock ticks after boot
+    pub rss_pages: u64,
+}
+
+/// Parses `/proc/<pid>/stat`. The command name is in parentheses and may itself
+/// contain spaces and parentheses, so the fields are counted from the last `)`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn parse_proc_stat(stat: &str) -> Option<ProcStat> {
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let comm = stat.get(open + 1..close)?.to_string();
+    // Field 3 (state) is the first after the name
+    let fields: Vec<&str> = stat.get(close + 1..)?.split_whitespace().collect();
+    let field = |n: usize| fields.get(n - 3).and_then(|f| f.parse::<u64>().ok());
+
+    Some(ProcStat {
+        comm,
+        utime: field(14)?,
+        stime: field(15)?,
+        starttime: field(22)?,
+        rss_pages: field(24)?,
+    })
+}
+
+/// The real uid from `/proc/<pid>/status`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn parse_status_uid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Joins a NUL-separated argument list (`/proc/<pid>/cmdline`)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn parse_cmdline(cmdline: &[u8]) -> String {
+    cmdline
+        .split(|&b| b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parses the `KERN_PROCARGS2` sysctl buffer: argc, the executable path, padding,
+/// then argc NUL-terminated arguments
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn parse_procargs2(buffer: &[u8]) -> Vec<String> {
+    let Some(argc) = buffer
+        .get(..4)
+        .map(|b| i32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+    else {
+        return Vec::new();
+    };
+
+    let mut parts = buffer[4..].split(|&b| b == 0).filter(|p| !p.is_empty());
+    let _exec_path = parts.next();
+    parts
+        .take(argc.max(0) as usize)
+        .map(|arg| String::from_utf8_lossy(arg).to_string())
+        .collect()
+}
+
+/// Human-readable size with binary units, e.g. "12.3 MB"
+pub fn format_memory(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn user_name(uid: u32, cache: &mut HashMap<u32, String>) -> String {
+    cache
+        .entry(uid)
+        .or_insert_with(|| unsafe {
+            let passwd = libc::getpwuid(uid as libc::uid_t);
+            if passwd.is_null() || (*passwd).pw_name.is_null() {
+                uid.to_string()
+            } else {
+                std::ffi::CStr::from_ptr((*passwd).pw_name)
+                    .to_string_lossy()
+                    .to_string()
+            }
+        })
+        .clone()
+}
+
+#[cfg(target_os = "linux")]
+fn list_processes() -> Vec<Process> {
+    use std::fs;
+
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as f64;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as u64;
+    let uptime: f64 = fs::read_to_string("/proc/uptime")
+        .ok()
+        .and_then(|s| s.split_whitespace().next()?.parse().ok())
+        .unwrap_or(0.0);
+    let mut users = HashMap::new();
+
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let dir = entry.path();
+            let stat = parse_proc_stat(&fs::read_to_string(dir.join("stat")).ok()?)?;
+            let uid = parse_status_uid(&fs::read_to_string(dir.join("status")).ok()?)?;
+            let exe = fs::read_link(dir.join("exe"))
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            // Kernel threads have an empty command line
+            let command = fs::read(dir.join("cmdline"))
+                .map(|c| parse_cmdline(&c))
+                .unwrap_or_default();
+
+            Some(Process {
+                pid,
+                command: if command.is_empty() {
+                    format!("[{}]", stat.comm)
+                } else {
+                    command
+                },
+                name: stat.comm,
+                exe,
+                user: user_name(uid, &mut users),
+                cpu_time: (stat.utime + stat.stime) as f64 / ticks,
+                age: (uptime - stat.starttime as f64 / ticks).max(0.0),
+                memory: stat.rss_pages * page_size,
+                cpu_percent: 0.0,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn process_arguments(pid: u32) -> Vec<String> {
+    let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, pid as libc::c_int];
+    let mut size: libc::size_t = 0;
+    unsafe {
+        // Ask for the size first; this fails for other users' processes
+        if libc::sysctl(
+            mib.as_mut_ptr(),
+            3,
+            std::ptr::null_mut(),
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        ) != 0
+        {
+            return Vec::new();
+        }
+        let mut buffer = vec![0u8; size];
+        if libc::sysctl(
+            mib.as_mut_ptr(),
+            3,
+            buffer.as_mut_ptr() as *mut libc::c_void,
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        ) != 0
+        {
+            return Vec::new();
+        }
+        buffer.truncate(size);
+        parse_procargs2(&buffer)
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+fn list_processes() -> Vec<Process> {
+    let mut users = HashMap::new();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+
+    // Task times are in Mach absolute time units
+    let mut timebase = libc::mach_timebase_info { numer: 0, denom: 0 };
+    unsafe { libc::mach_timebase_info(&mut timebase) };
+    let ns_per_tick = if timebase.denom == 0 {
+        1.0
+    } else {
+        timebase.numer as f64 / timebase.denom as f64
+    };
+
+    let pids: Vec<libc::c_int> = unsafe {
+        let count = libc::proc_listallpids(std::ptr::null_mut(), 0);
+        if count <= 0 {
+            return Vec::new();
+        }
+        // Leave room for processes started in between the two calls
+        let mut pids = vec![0 as libc::c_int; count as usize + 64];
+        let bytes = (pids.len() * std::mem::size_of::<libc::c_int>()) as libc::c_int;
+        let count = libc::proc_listallpids(pids.as_mut_ptr() as *mut libc::c_void, bytes);
+        pids.truncate(count.max(0) as usize);
+        pids
+    };
+
+    pids.into_iter()
+        .filter(|&pid| pid > 0)
+        .filter_map(|pid| {
+            let mut info: libc::proc_taskallinfo = unsafe { std::mem::zeroed() };
+            let size = std::mem::size_of::<libc::proc_taskallinfo>() as libc::c_int;
+            let read = unsafe {
+                libc::proc_pidinfo(
+                    pid,
+                    libc::PROC_PIDTASKALLINFO,
+                    0,
+                    &mut info as *mut _ as *mut libc::c_void,
+                    size,
+                )
+            };
+            // Fails for processes we may not inspect, which we could not signal either
+            if read != size {
+                return None;
+            }
+
+            let name_field = if info.pbsd.pbi_name[0] != 0 {
+                &info.pbsd.pbi_name[..]
+            } else {
+                &info.pbsd.pbi_comm[..]
+            };
+            let name_bytes: Vec<u8> = name_field
+                .iter()
+                .take_while(|&&c| c != 0)
+                .map(|&c| c as u8)
+                .collect();
+
+            let mut path_buffer = vec![0u8; libc::PROC_PIDPATHINFO_MAXSIZE as usize];
+            let path_len = unsafe {
+                libc::proc_pidpath(
+                    pid,
+                    path_buffer.as_mut_ptr() as *mut libc::c_void,
+                    path_buffer.len() as u32,
+                )
+            };
+            let exe = if path_len > 0 {
+                String::from_utf8_lossy(&path_buffer[..path_len as usize]).to_string()
+            } else {
+                String::new()
+            };
+
+            let args = process_arguments(pid as u32);
+            let task = &info.ptinfo;
+            let cpu_ticks = task.pti_total_user + task.pti_total_system;
+            let started =
+                info.pbsd.pbi_start_tvsec as f64 + info.pbsd.pbi_start_tvusec as f64 / 1_000_000.0;
+
+            Some(Process {
+                pid: pid as u32,
+                name: String::from_utf8_lossy(&name_bytes).to_string(),
+                command: if args.is_empty() {
+                    exe.clone()
+                } else {
+                    args.join(" ")
+                },
+                exe,
+                user: user_name(info.pbsd.pbi_uid, &mut users),
+                cpu_time: cpu_ticks as f64 * ns_per_tick / 1e9,
+                age: (now - started).max(0.0),
+                memory: task.pti_resident_size,
+                cpu_percent: 0.0,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn list_processes() -> Vec<Process> {
+    Vec::new()
+}
+
+/// Fills `cpu_percent` from the previous sample when there is a recent one,
+/// otherwise from the average over the process lifetime
+fn apply_cpu_usage(processes: &mut [Process]) {
+    let now = Instant::now();
+    let mut last = LAST_SAMPLE.lock().unwrap();
+
+    let previous = last
+        .as_ref()
+        .map(|(at, times)| (now.duration_since(*at).as_secs_f64(), times))
+        .filter(|(elapsed, _)| *elapsed >= MIN_SAMPLE_SECS);
+
+    for process in processes.iter_mut() {
+        let since_sample = previous.and_then(|(elapsed, times)| {
+            Some((process.cpu_time - times.get(&process.pid)?) / elapsed)
+        });
+        let lifetime = (process.age > 0.0).then(|| process.cpu_time / process.age);
+        process.cpu_percent = since_sample.or(lifetime).unwrap_or(0.0).max(0.0) * 100.0;
+    }
+
+    if previous.is_some() || last.is_none() {
+        *last = Some((now, processes.iter().map(|p| (p.pid, p.cpu_time)).collect()));
+    }
+}
+
+pub fn load_processes() -> Vec<Process> {
+    let own_pid = std::process::id();
+    let mut processes: Vec<Process> = list_processes()
+        .into_iter()
+        .filter(|p| p.pid != own_pid)
+        .collect();
+    apply_cpu_usage(&mut processes);
+    processes
+}
+
+/// Sends SIGTERM, or SIGKILL when `force` is set
+/// Sends SIGTERM, or SIGKILL when forced. Fails when the process is gone
+/// or belongs to someone else.
+pub fn send_signal(pid: u32, force: bool) -> io::Result<()> {
+    let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+fn process_to_result(process: &Process) -> SearchResult {
+    let label = format!("{} ({})", process.name, process.pid);
+    let hint = format!(
+        "{} · {} · {:.1}% · {}",
+        process.pid,
+        process.user,
+        process.cpu_percent,
+        format_memory(process.memory)
+    );
+
+    SearchResult::new(
+        process.name.clone(),
+        process.exe.clone(),
+        SearchMode::Processes,
+    )
+    .with_action(ResultAction::Confirm(
+        format!("Terminate {}", label),
+        Box::new(ResultAction::Kill(process.pid, false)),
+    ))
+    .with_hint(&hint)
+    .with_alt_action(
+        "Force kill (SIGKILL)",
+        ResultAction::Confirm(
+            format!("Force kill {}", label),
+            Box::new(ResultAction::Kill(process.pid, true)),
+        ),
+    )
+    .with_alt_action("Copy PID", ResultAction::Copy(process.pid.to_string()))
+    .with_alt_action(
+        "Copy command line",
+        ResultAction::Copy(process.command.clone()),
+    )
+}
+
+pub fn search_processes(query: &str) -> Vec<SearchResult> {
+    let processes = load_processes();
+
+    let mut scored: Vec<(&Process, i64)> = if query.is_empty() {
+        // Busiest first
+        let mut busiest: Vec<&Process> = processes.iter().collect();
+        busiest.sort_by(|a, b| {
+            b.cpu_percent
+                .total_cmp(&a.cpu_percent)
+                .then(b.memory.cmp(&a.memory))
+        });
+        busiest.into_iter().map(|p| (p, 0)).collect()
+    } else {
+        let matcher = get_matcher();
+        processes
+            .iter()
+            .filter_map(|process| {
+                // A pid typed in full always wins
+                if process.pid.to_string() == query.trim() {
+                    return Some((process, i64::MAX));
+                }
+                let name_score = matcher.fuzzy_match(&process.name, query).map(|s| s * 2);
+                let command_score = matcher.fuzzy_match(&process.command, query);
+                name_score.max(command_score).map(|score| (process, score))
+            })
+            .collect()
+    };
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(process, _)| process_to_result(process))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_stat_with_awkward_names() {
+        let stat = "1234 (tmux: server (1) ) S 1 1234 1234 0 -1 4194560 2000 0 0 0 \
+                    15 7 0 0 20 0 1 0 5000 12345678 900 18446744073709551615\n";
+        assert_eq!(
+            parse_proc_stat(stat),
+            Some(ProcStat {
+                comm: "tmux: server (1) ".to_string(),
+                utime: 15,
+                stime: 7,
+                starttime: 5000,
+                rss_pages: 900,
+            })
+        );
+        assert_eq!(parse_proc_stat("1234 (cut short) S 1 2 3"), None);
+        assert_eq!(parse_proc_stat("no name at all"), None);
+    }
+
+    #[test]
+    fn parses_real_uid() {
+        let status = "Name:\tbash\nUmask:\t0022\nState:\tS (sleeping)\nUid:\t1000\t0\t1000\t1000\nGid:\t100\t100\t100\t100\n";
+        assert_eq!(parse_status_uid(status), Some(1000));
+        assert_eq!(parse_status_uid("Name:\tbash\n"), None);
+    }
+
+    #[test]
+    fn joins_command_lines() {
+        assert_eq!(
+            parse_cmdline(b"/usr/bin/python3\0-m\0http.server\0"),
+            "/usr/bin/python3 -m http.server"
+        );
+        // Kernel threads have none
+        assert_eq!(parse_cmdline(b""), "");
+    }
+
+    #[test]
+    fn parses_procargs2() {
+        let mut buffer = 2i32.to_ne_bytes().to_vec();
+        buffer.extend(b"/usr/bin/vim\0\0\0\0vim\0notes.txt\0TERM=xterm-256color\0");
+        assert_eq!(parse_procargs2(&buffer), ["vim", "notes.txt"]);
+        assert!(parse_procargs2(b"\x01\0").is_empty());
+        let mut negative = (-1i32).to_ne_bytes().to_vec();
+        negative.extend(b"/bin/sh\0sh\0");
+        assert!(parse_procargs2(&negative).is_empty());
+    }
+
+    #[test]
+    fn formats_memory_in_binary_units() {
+        assert_eq!(format_memory(0), "0 B");
+        assert_eq!(format_memory(1023), "1023 B");
+        assert_eq!(format_memory(1536), "1.5 KB");
+        assert_eq!(format_memory(12_897_484), "12.3 MB");
+        assert_eq!(format_memory(3 << 40), "3072.0 GB");
+    }
+
+    #[test]
+    fn signalling_a_missing_process_fails() {
+        // Pids are capped well below this
+        let error = send_signal(i32::MAX as u32, false).unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(libc::ESRCH));
+    }
+}
@@ -0,0 +1,269 @@
+use crate::config::ProjectsConfig;
+use crate::file_search::{walk_dir, Walk};
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Project {
+    pub name: String,
+    pub path: String,
+}
+
+/// Cached scan; the settings are kept so a config change triggers a rescan
+#[derive(Debug, Serialize, Deserialize)]
+struct ProjectCache {
+    roots: Vec<String>,
+    max_depth: usize,
+    projects: Vec<Project>,
+}
+
+/// Expands a leading `~` to the home directory; `~user` is left alone
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = dirs::home_dir().unwrap_or_default();
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Collapses the home directory back to `~` for display
+pub fn display_path(path: &str) -> String {
+    display_path_in(path, &std::env::var("HOME").unwrap_or_default())
+}
+
+fn display_path_in(path: &str, home: &str) -> String {
+    let home = home.trim_end_matches('/');
+    match path.strip_prefix(home) {
+        // Only whole components, so /home/ada does not shorten /home/adam
+        Some(rest) if !home.is_empty() && (rest.is_empty() || rest.starts_with('/')) => {
+            format!("~{}", rest)
+        }
+        _ => path.to_string(),
+    }
+}
+
+fn is_git_repo(dir: &Path) -> bool {
+    // `.git` is a directory in clones and a file in worktrees and submodules
+    dir.join(".git").exists()
+}
+
+/// Finds git repositories under the roots. Repositories are not descended into.
+pub fn discover_projects(roots: &[PathBuf], max_depth: usize) -> Vec<Project> {
+    let mut projects = Vec::new();
+
+    for root in roots {
+        if is_git_repo(root) {
+            projects.push(Project {
+                name: root
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path: root.to_string_lossy().to_string(),
+            });
+            continue;
+        }
+
+        walk_dir(root, max_depth, &mut |entry, file_name| {
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            if !is_dir {
+                return Walk::SkipDir;
+            }
+            let path = entry.path();
+            if is_git_repo(&path) {
+                projects.push(Project {
+                    name: file_name.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                });
+                return Walk::SkipDir;
+            }
+            Walk::Continue
+        });
+    }
+
+    projects.sort_by_key(|p| p.name.to_lowercase());
+    projects.dedup_by(|a, b| a.path == b.path);
+    projects
+}
+
+fn cache_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap()
+        .join("rofi-mac")
+        .join("projects.json")
+}
+
+fn load_cache(config: &ProjectsConfig) -> Option<Vec<Project>> {
+    // Cache valid for 1 hour, like the app index
+    let elapsed = fs::metadata(cache_path())
+        .ok()?
+        .modified()
+        .ok()?
+        .elapsed()
+        .ok()?;
+    if elapsed.as_secs() >= 3600 {
+        return None;
+    }
+
+    let contents = fs::read_to_string(cache_path()).ok()?;
+    let cache: ProjectCache = serde_json::from_str(&contents).ok()?;
+    (cache.roots == config.roots && cache.max_depth == config.max_depth).then_some(cache.projects)
+}
+
+fn save_cache(config: &ProjectsConfig, projects: &[Project]) {
+    let path = cache_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let cache = ProjectCache {
+        roots: config.roots.clone(),
+        max_depth: config.max_depth,
+        projects: projects.to_vec(),
+    };
+    if let Ok(json) = serde_json::to_string(&cache) {
+        let _ = fs::write(&path, json);
+    }
+}
+
+pub fn index_projects(config: &ProjectsConfig) -> Vec<Project> {
+    if let Some(cached) = load_cache(config) {
+        return cached;
+    }
+
+    let roots: Vec<PathBuf> = config.roots.iter().map(|r| expand_home(r)).collect();
+    let projects = discover_projects(&roots, config.max_depth);
+    save_cache(config, &projects);
+    projects
+}
+
+fn project_to_result(project: &Project) -> SearchResult {
+    SearchResult::new(
+        project.name.clone(),
+        project.path.clone(),
+        SearchMode::Projects,
+    )
+    .with_alt_action("Open in editor", ResultAction::Edit(project.path.clone()))
+    .with_alt_action(
+        "Open terminal here",
+        ResultAction::TerminalAt(project.path.clone()),
+    )
+    .with_alt_action("Open in Finder", ResultAction::Reveal(project.path.clone()))
+    .with_alt_action("Copy path", ResultAction::Copy(project.path.clone()))
+}
+
+pub fn search_projects(query: &str, config: &ProjectsConfig) -> Vec<SearchResult> {
+    let projects = index_projects(config);
+
+    if query.is_empty() {
+        return projects.iter().take(15).map(project_to_result).collect();
+    }
+
+    let matcher = get_matcher();
+    let mut scored: Vec<_> = projects
+        .iter()
+        .filter_map(|project| {
+            // A name match counts for more than one somewhere in the path
+            let name_score = matcher.fuzzy_match(&project.name, query).map(|s| s * 2);
+            let path_score = matcher.fuzzy_match(&display_path(&project.path), query);
+            name_score.max(path_score).map(|score| (project, score))
+        })
+        .collect();
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(project, _)| project_to_result(project))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates each path under `root`; a trailing `/` makes a directory
+    fn create(root: &Path, paths: &[&str]) {
+        for path in paths {
+            let full = root.join(path);
+            if path.ends_with('/') {
+                fs::create_dir_all(&full).unwrap();
+            } else {
+                fs::create_dir_all(full.parent().unwrap()).unwrap();
+                fs::write(&full, "").unwrap();
+            }
+        }
+    }
+
+    fn names(projects: &[Project]) -> Vec<&str> {
+        projects.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn discovers_repositories_down_to_the_depth_limit() {
+        let root = std::env::temp_dir().join(format!("rufi-projects-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        create(
+            &root,
+            &[
+                "alpha/.git/",
+                "alpha/vendor/inner/.git/",
+                "group/beta/.git",
+                "group/deep/er/Gamma/.git/",
+                "node_modules/pkg/.git/",
+                ".hidden/repo/.git/",
+                "notes.txt",
+            ],
+        );
+
+        let projects = discover_projects(std::slice::from_ref(&root), 2);
+        // Nothing inside a repository, nothing ignored, nothing too deep
+        assert_eq!(names(&projects), ["alpha", "beta"]);
+        assert_eq!(projects[1].path, root.join("group/beta").to_string_lossy());
+        assert_eq!(
+            names(&discover_projects(std::slice::from_ref(&root), 3)),
+            ["alpha", "beta", "Gamma"]
+        );
+        assert_eq!(
+            names(&discover_projects(std::slice::from_ref(&root), 0)),
+            ["alpha"]
+        );
+
+        // A root that is a repository is the project itself
+        let alpha = root.join("alpha");
+        assert_eq!(names(&discover_projects(&[alpha], 3)), ["alpha"]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn expands_only_the_own_home() {
+        let home = dirs::home_dir().unwrap_or_default();
+        assert_eq!(expand_home("~"), home);
+        assert_eq!(expand_home("~/code/rufi"), home.join("code/rufi"));
+        assert_eq!(expand_home("~bob/code"), PathBuf::from("~bob/code"));
+        assert_eq!(expand_home("/tmp/~"), PathBuf::from("/tmp/~"));
+    }
+
+    #[test]
+    fn displays_paths_under_home_with_a_tilde() {
+        assert_eq!(display_path_in("/home/ada/code", "/home/ada"), "~/code");
+        assert_eq!(display_path_in("/home/ada", "/home/ada/"), "~");
+        assert_eq!(
+            display_path_in("/home/adam/code", "/home/ada"),
+            "/home/adam/code"
+        );
+        assert_eq!(display_path_in("/home/ada/code", ""), "/home/ada/code");
+    }
+}
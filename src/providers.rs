@@ -0,0 +1,102 @@
+use crate::{
+    app_search::{fuzzy_search, Application},
+    bookmarks::search_bookmarks,
+    calculator::{is_expression, search_calculator},
+    clipboard_history::search_clipboard,
+    combi::search_combi,
+    config::Config,
+    contacts::search_contacts,
+    date_calc::search_dates,
+    emoji::search_emoji,
+    file_search::{search_files, search_files_random},
+    man_pages::search_man_pages,
+    notes::search_notes,
+    pass::search_pass,
+    processes::search_processes,
+    projects::search_projects,
+    search_mode::{SearchMode, SearchResult},
+    snippets::search_snippets,
+    ssh_hosts::search_ssh,
+    system_commands::search_commands,
+    tmux::search_tmux,
+    trash::search_trash,
+    web_search::{match_keyword, search_web, web_fallback},
+    window_switcher::search_windows,
+    workspaces::search_workspaces,
+};
+
+pub fn app_results(apps: &[Application], query: &str) -> Vec<SearchResult> {
+    fuzzy_search(apps, query)
+        .into_iter()
+        .take(8)
+        .map(|app| SearchResult::new(app.name, app.path, SearchMode::Apps))
+        .collect()
+}
+
+/// Results of one mode for a query with its prefix already stripped
+pub fn search(
+    mode: SearchMode,
+    query: &str,
+    config: &Config,
+    apps: &[Application],
+) -> Vec<SearchResult> {
+    match mode {
+        SearchMode::Apps => {
+            if query.is_empty() {
+                // Show first 15 apps when empty (already sorted alphabetically)
+                apps.iter()
+                    .take(15)
+                    .map(|app| {
+                        SearchResult::new(app.name.clone(), app.path.clone(), SearchMode::Apps)
+                    })
+                    .collect()
+            } else {
+                // URLs and search keywords first, then the calculation when
+                // the query is an expression
+                let mut results = search_web(query, &config.web);
+                if is_expression(query) {
+                    results.extend(search_calculator(query));
+                }
+                results.extend(app_results(apps, query));
+                if results.is_empty() {
+                    results.push(web_fallback(query, &config.web));
+                }
+                results
+            }
+        }
+        SearchMode::Files => {
+            if query.is_empty() {
+                // Show 4 random files when empty
+                search_files_random(4)
+            } else {
+                search_files(query, &config.files)
+            }
+        }
+        SearchMode::Run => search_commands(query),
+        SearchMode::Calc => search_calculator(query),
+        SearchMode::Date => search_dates(query),
+        SearchMode::Clipboard => search_clipboard(query),
+        SearchMode::Emoji => search_emoji(query),
+        SearchMode::Ssh => search_ssh(query),
+        SearchMode::Projects => search_projects(query, &config.projects),
+        SearchMode::Workspaces => search_workspaces(query),
+        SearchMode::Bookmarks => search_bookmarks(query),
+        SearchMode::Web => {
+            let mut results = search_web(query, &config.web);
+            if !query.trim().is_empty() && match_keyword(query, &config.web).is_none() {
+                results.push(web_fallback(query, &config.web));
+            }
+            results
+        }
+        SearchMode::Processes => search_processes(query),
+        SearchMode::Tmux => search_tmux(query),
+        SearchMode::Man => search_man_pages(query),
+        SearchMode::Snippets => search_snippets(query, &config.snippets),
+        SearchMode::Notes => search_notes(query, &config.notes),
+        SearchMode::Contacts => search_contacts(query, &config.contacts),
+        SearchMode::Pass => search_pass(query, &config.pass),
+        SearchMode::Trash => search_trash(query),
+        SearchMode::Windows => search_windows(query),
+        SearchMode::Combi => search_combi(query, config, apps),
+    }
+}
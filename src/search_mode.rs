@@ -5,23 +5,183 @@ pub enum SearchMode {
     Apps,
     Files,
     Run,
+    Calc,
+    Date,
+    Clipboard,
+    Emoji,
+    Ssh,
+    Projects,
+    Workspaces,
+    Bookmarks,
+    Web,
+    Processes,
+    Tmux,
+    Man,
+    Snippets,
+    Notes,
+    Contacts,
+    Pass,
+    Trash,
+    Windows,
+    Combi,
 }
 
 impl SearchMode {
+    pub const MODES: [SearchMode; 22] = [
+        SearchMode::Apps,
+        SearchMode::Files,
+        SearchMode::Run,
+        SearchMode::Calc,
+        SearchMode::Date,
+        SearchMode::Clipboard,
+        SearchMode::Emoji,
+        SearchMode::Ssh,
+        SearchMode::Projects,
+        SearchMode::Workspaces,
+        SearchMode::Bookmarks,
+        SearchMode::Web,
+        SearchMode::Processes,
+        SearchMode::Tmux,
+        SearchMode::Man,
+        SearchMode::Snippets,
+        SearchMode::Notes,
+        SearchMode::Contacts,
+        SearchMode::Pass,
+        SearchMode::Trash,
+        SearchMode::Windows,
+        SearchMode::Combi,
+    ];
+
+    /// Looks a mode up by its name or label, ignoring case, as written in the
+    /// config or on the command line
+    pub fn from_name(name: &str) -> Option<SearchMode> {
+        let name = name.trim();
+        SearchMode::MODES.iter().copied().find(|mode| {
+            mode.as_str().eq_ignore_ascii_case(name) || mode.label().eq_ignore_ascii_case(name)
+        })
+    }
+
+    /// Short name shown in the mode badge and the hints
+    pub fn label(&self) -> &str {
+        match self {
+            SearchMode::Apps => "apps",
+            SearchMode::Files => "files",
+            SearchMode::Run => "run",
+            SearchMode::Calc => "calc",
+            SearchMode::Date => "date",
+            SearchMode::Clipboard => "clip",
+            SearchMode::Emoji => "emoji",
+            SearchMode::Ssh => "ssh",
+            SearchMode::Projects => "projects",
+            SearchMode::Workspaces => "recent",
+            SearchMode::Bookmarks => "bookmarks",
+            SearchMode::Web => "web",
+            SearchMode::Processes => "procs",
+            SearchMode::Tmux => "tmux",
+            SearchMode::Man => "man",
+            SearchMode::Snippets => "snippets",
+            SearchMode::Notes => "notes",
+            SearchMode::Contacts => "contacts",
+            SearchMode::Pass => "pass",
+            SearchMode::Trash => "trash",
+            SearchMode::Windows => "windows",
+            SearchMode::Combi => "all",
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             SearchMode::Apps => "Apps",
             SearchMode::Files => "Files",
             SearchMode::Run => "Run",
+            SearchMode::Calc => "Calc",
+            SearchMode::Date => "Date",
+            SearchMode::Clipboard => "Clipboard",
+            SearchMode::Emoji => "Emoji",
+            SearchMode::Ssh => "Ssh",
+            SearchMode::Projects => "Projects",
+            SearchMode::Workspaces => "Workspaces",
+            SearchMode::Bookmarks => "Bookmarks",
+            SearchMode::Web => "Web",
+            SearchMode::Processes => "Processes",
+            SearchMode::Tmux => "Tmux",
+            SearchMode::Man => "Man",
+            SearchMode::Snippets => "Snippets",
+            SearchMode::Notes => "Notes",
+            SearchMode::Contacts => "Contacts",
+            SearchMode::Pass => "Pass",
+            SearchMode::Trash => "Trash",
+            SearchMode::Windows => "Windows",
+            SearchMode::Combi => "Combi",
         }
     }
 }
 
+/// What happens when a result is activated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResultAction {
+    /// The mode's default: launch the app, open the file, run the command
+    Open,
+    /// Put the text on the clipboard
+    Copy(String),
+    /// Copy the text and paste it into the frontmost app
+    Paste(String),
+    /// Pin or unpin a clipboard history entry
+    TogglePin(String),
+    /// Remove a clipboard history entry
+    DeleteClip(String),
+    /// Drop all unpinned clipboard history
+    ClearClipHistory,
+    /// Open a file or directory in the configured editor
+    Edit(String),
+    /// Open the configured terminal in a directory
+    TerminalAt(String),
+    /// Show a file or directory in Finder
+    Reveal(String),
+    /// Run a shell command line in the background
+    Shell(String),
+    /// Signal a process: SIGTERM, or SIGKILL when the flag is set
+    Kill(u32, bool),
+    /// Ask before running the action; the text describes it
+    Confirm(String, Box<ResultAction>),
+    /// Expand a snippet template, then paste it (flag set) or copy it
+    ExpandSnippet(String, bool),
+    /// Append an entry to the notes file
+    AddNote(String),
+    /// Mark the notes line done
+    CompleteNote(String),
+    /// Remove the notes line
+    DeleteNote(String),
+    /// Copy the password of a password-store entry
+    CopyPassword(String),
+    /// Copy the current one-time code of a password-store entry
+    CopyPassOtp(String),
+    /// Copy a named field of a password-store entry
+    CopyPassField(String, String),
+    /// Move the file to the trash
+    MoveToTrash(String),
+    /// Put back the trashed item named by its .trashinfo path
+    RestoreTrash(String),
+    /// Delete the trashed item named by its .trashinfo path for good
+    DeleteTrash(String),
+    /// Delete everything in every trash
+    EmptyTrash,
+    /// Raise and focus a window by its window manager id
+    FocusWindow(u64),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub name: String,
     pub path: String,
     pub result_type: SearchMode,
+    pub action: ResultAction,
+    /// Second line under the name; files and projects show their folder instead
+    pub hint: Option<String>,
+    /// Secondary actions offered on Option+Enter, as (label, action)
+    pub alt_actions: Vec<(String, ResultAction)>,
+    /// Header the result is grouped under, in grouped combi results
+    pub section: Option<String>,
 }
 
 impl SearchResult {
@@ -30,6 +190,30 @@ impl SearchResult {
             name,
             path,
             result_type,
+            action: ResultAction::Open,
+            hint: None,
+            alt_actions: Vec::new(),
+            section: None,
         }
     }
+
+    pub fn with_action(mut self, action: ResultAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    pub fn with_hint(mut self, hint: &str) -> Self {
+        self.hint = Some(hint.to_string());
+        self
+    }
+
+    pub fn with_section(mut self, section: &str) -> Self {
+        self.section = Some(section.to_string());
+        self
+    }
+
+    pub fn with_alt_action(mut self, label: &str, action: ResultAction) -> Self {
+        self.alt_actions.push((label.to_string(), action));
+        self
+    }
 }
@@ -0,0 +1,192 @@
+use crate::clipboard::system_clipboard;
+use crate::config::SnippetsConfig;
+use crate::date_calc::Clock;
+use crate::projects::expand_home;
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::template::{expand, fill_input, format_uuid, inputs, Placeholder};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub name: String,
+    pub template: String,
+}
+
+/// A snippet waiting for its `{input:...}` values; the search field holds the
+/// value being typed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetPrompt {
+    pub name: String,
+    pub template: String, // Inputs entered so far are already filled in
+    pub paste: bool,
+}
+
+pub fn snippets_dir(config: &SnippetsConfig) -> PathBuf {
+    if config.dir.is_empty() {
+        dirs::config_dir()
+            .unwrap_or_default()
+            .join("rofi-mac")
+            .join("snippets")
+    } else {
+        expand_home(&config.dir)
+    }
+}
+
+/// Snippets from the config followed by one per file in the snippets folder,
+/// named after the file without its extension
+pub fn load_snippets(config: &SnippetsConfig) -> Vec<Snippet> {
+    let mut snippets: Vec<Snippet> = config
+        .items
+        .iter()
+        .map(|(name, template)| Snippet {
+            name: name.clone(),
+            template: template.clone(),
+        })
+        .collect();
+    snippets.sort_by_key(|s| s.name.to_lowercase());
+
+    let mut files: Vec<Snippet> = fs::read_dir(snippets_dir(config))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            if name.starts_with('.') {
+                return None;
+            }
+            let template = fs::read_to_string(&path).ok()?;
+            // Editors add a final newline that nobody wants pasted
+            let template = template.strip_suffix('\n').unwrap_or(&template);
+            Some(Snippet {
+                name,
+                template: template.to_string(),
+            })
+        })
+        .collect();
+    files.sort_by_key(|s| s.name.to_lowercase());
+
+    snippets.extend(files);
+    snippets
+}
+
+fn random_uuid() -> String {
+    let mut bytes = [0u8; 16];
+    if let Ok(mut urandom) = fs::File::open("/dev/urandom") {
+        let _ = urandom.read_exact(&mut bytes);
+    }
+    format_uuid(bytes)
+}
+
+/// Expands the system fields of a template; inputs still missing become empty
+pub fn expand_snippet(template: &str) -> String {
+    let (date, time) = Clock::system().local_date_time();
+    // Read before the expanded text replaces it
+    let clipboard = system_clipboard()
+        .ok()
+        .and_then(|c| c.get_text())
+        .unwrap_or_default();
+
+    expand(template, &mut |placeholder| match placeholder {
+        Placeholder::Date => date.clone(),
+        Placeholder::Time => time.clone(),
+        Placeholder::DateTime => format!("{} {}", date, time),
+        Placeholder::Clipboard => clipboard.clone(),
+        Placeholder::Uuid => random_uuid(),
+        Placeholder::Input(_) => String::new(),
+    })
+}
+
+/// First line of a template, shortened for the hint
+fn preview(template: &str) -> String {
+    let line = template.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > 40 {
+        format!("{}…", line.chars().take(40).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+fn snippet_to_result(snippet: &Snippet, paste: bool) -> SearchResult {
+    let other = if paste {
+        "Copy instead of pasting"
+    } else {
+        "Paste into frontmost app"
+    };
+    SearchResult::new(
+        snippet.name.clone(),
+        snippet.template.clone(),
+        SearchMode::Snippets,
+    )
+    .with_action(ResultAction::ExpandSnippet(snippet.template.clone(), paste))
+    .with_hint(&preview(&snippet.template))
+    .with_alt_action(
+        other,
+        ResultAction::ExpandSnippet(snippet.template.clone(), !paste),
+    )
+}
+
+/// The single entry shown while asking for an input: Enter fills the next
+/// `{input:...}` field with the typed value
+pub fn prompt_result(prompt: &SnippetPrompt, value: &str) -> Option<SearchResult> {
+    let pending = inputs(&prompt.template);
+    let input = pending.first()?;
+    let filled = fill_input(&prompt.template, input, value);
+    let hint = if pending.len() > 1 {
+        format!("{} · {} more after this", prompt.name, pending.len() - 1)
+    } else {
+        prompt.name.clone()
+    };
+
+    Some(
+        SearchResult::new(
+            format!("{}: {}", input, value),
+            prompt.template.clone(),
+            SearchMode::Snippets,
+        )
+        .with_action(ResultAction::ExpandSnippet(filled, prompt.paste))
+        .with_hint(&hint),
+    )
+}
+
+pub fn search_snippets(query: &str, config: &SnippetsConfig) -> Vec<SearchResult> {
+    let snippets = load_snippets(config);
+
+    if query.is_empty() {
+        return snippets
+            .iter()
+            .take(15)
+            .map(|s| snippet_to_result(s, config.paste))
+            .collect();
+    }
+
+    let matcher = get_matcher();
+    let mut scored: Vec<_> = snippets
+        .iter()
+        .filter_map(|snippet| {
+            // Names first, then anywhere in the text
+            let name_score = matcher.fuzzy_match(&snippet.name, query).map(|s| s * 2);
+            let text_score = matcher.fuzzy_match(&snippet.template, query);
+            name_score.max(text_score).map(|score| (snippet, score))
+        })
+        .collect();
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(snippet, _)| snippet_to_result(snippet, config.paste))
+        .collect()
+}
@@ -0,0 +1,41 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Runs a read-only query with the sqlite3 CLI, returning its JSON output
+pub fn sqlite_json(db: &Path, query: &str) -> Option<String> {
+    if !db.exists() {
+        return None;
+    }
+    let output = Command::new("sqlite3")
+        .arg("-readonly")
+        .arg("-json")
+        .arg(db)
+        .arg(query)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Queries a copy of a database that another program keeps locked (browsers
+/// hold an exclusive lock on their history while running)
+pub fn sqlite_json_copy(db: &Path, query: &str) -> Option<String> {
+    let name = db.file_name()?.to_string_lossy().to_string();
+    let temp_dir = std::env::temp_dir().join(format!("rofi-mac-{}", std::process::id()));
+    fs::create_dir_all(&temp_dir).ok()?;
+
+    let copy = temp_dir.join(&name);
+    fs::copy(db, &copy).ok()?;
+    // Recent writes may still sit in the write-ahead log
+    let wal = db.with_file_name(format!("{}-wal", name));
+    if wal.exists() {
+        let _ = fs::copy(&wal, temp_dir.join(format!("{}-wal", name)));
+    }
+
+    let result = sqlite_json(&copy, query);
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
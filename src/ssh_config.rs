@@ -0,0 +1,255 @@
+// Parsing for OpenSSH client files. Nothing here touches the filesystem, so
+// callers decide how `Include` paths are resolved and read.
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SshHost {
+    pub alias: String,
+    pub hostname: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SshConfig {
+    pub hosts: Vec<SshHost>,
+    pub includes: Vec<String>, // Raw `Include` arguments, in order
+}
+
+/// Splits a config line into keyword and arguments. Keywords may be separated
+/// from their value by whitespace or `=`, and arguments may be double-quoted.
+fn split_line(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let keyword_end = line
+        .find(|c: char| c.is_whitespace() || c == '=')
+        .unwrap_or(line.len());
+    let keyword = line[..keyword_end].to_lowercase();
+    let rest = line[keyword_end..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest);
+
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in rest.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            '#' if !in_quotes && current.is_empty() => break,
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+
+    Some((keyword, args))
+}
+
+/// Whether a `Host` pattern names a single concrete host
+fn is_concrete(pattern: &str) -> bool {
+    !pattern.starts_with('!') && !pattern.contains(['*', '?'])
+}
+
+/// Parses an ssh_config file, collecting concrete `Host` aliases with the
+/// options set in their block. Like ssh, the first value for an option wins.
+pub fn parse_config(contents: &str) -> SshConfig {
+    let mut config = SshConfig::default();
+    // Indices into config.hosts for the block being read
+    let mut current: Vec<usize> = Vec::new();
+
+    for line in contents.lines() {
+        let Some((keyword, args)) = split_line(line) else {
+            continue;
+        };
+
+        match keyword.as_str() {
+            "host" => {
+                current.clear();
+                for pattern in args.iter().filter(|p| is_concrete(p)) {
+                    let index = match config.hosts.iter().position(|h| &h.alias == pattern) {
+                        Some(index) => index,
+                        None => {
+                            config.hosts.push(SshHost {
+                                alias: pattern.clone(),
+                                ..Default::default()
+                            });
+                            config.hosts.len() - 1
+                        }
+                    };
+                    current.push(index);
+                }
+            }
+            // Match blocks apply conditionally; nothing in them belongs to a Host
+            "match" => current.clear(),
+            "include" => config.includes.extend(args),
+            "hostname" | "user" | "port" => {
+                let Some(value) = args.first() else {
+                    continue;
+                };
+                for &index in &current {
+                    let host = &mut config.hosts[index];
+                    match keyword.as_str() {
+                        "hostname" if host.hostname.is_none() => {
+                            host.hostname = Some(value.clone())
+                        }
+                        "user" if host.user.is_none() => host.user = Some(value.clone()),
+                        "port" if host.port.is_none() => host.port = value.parse().ok(),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    config
+}
+
+/// Parses a known_hosts file. Hashed entries (`|1|...`) cannot be recovered and
+/// are skipped, as are wildcard patterns and revoked keys.
+pub fn parse_known_hosts(contents: &str) -> Vec<SshHost> {
+    let mut hosts: Vec<SshHost> = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let mut names = fields.next().unwrap_or_default();
+        if names.starts_with('@') {
+            if names == "@revoked" {
+                continue;
+            }
+            // @cert-authority lines still name real hosts
+            names = fields.next().unwrap_or_default();
+        }
+
+        for name in names.split(',') {
+            if name.starts_with('|') || !is_concrete(name) {
+                continue;
+            }
+
+            // Non-default ports are written as [host]:port
+            let (alias, port) = match name.strip_prefix('[').and_then(|n| n.split_once("]:")) {
+                Some((host, port)) => (host.to_string(), port.parse().ok()),
+                None => (name.to_string(), None),
+            };
+            if alias.is_empty() || hosts.iter().any(|h| h.alias == alias && h.port == port) {
+                continue;
+            }
+            hosts.push(SshHost {
+                alias,
+                port,
+                ..Default::default()
+            });
+        }
+    }
+
+    hosts
+}
+
+/// Matches a file name against an `Include` glob component (`*` and `?` only)
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // Classic backtracking over the last `*`
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = include_str!("../tests/fixtures/ssh/config");
+    const KNOWN_HOSTS: &str = include_str!("../tests/fixtures/ssh/known_hosts");
+
+    fn host(hosts: &[SshHost], alias: &str) -> SshHost {
+        hosts.iter().find(|h| h.alias == alias).cloned().unwrap()
+    }
+
+    #[test]
+    fn skips_wildcard_and_negated_hosts() {
+        let config = parse_config(CONFIG);
+        let aliases: Vec<&str> = config.hosts.iter().map(|h| h.alias.as_str()).collect();
+        assert_eq!(aliases, ["web", "db", "quoted", "with space"]);
+        assert_eq!(config.includes, ["conf.d/*.conf"]);
+    }
+
+    #[test]
+    fn first_value_wins_per_host() {
+        let hosts = parse_config(CONFIG).hosts;
+        let web = host(&hosts, "web");
+        assert_eq!(web.hostname.as_deref(), Some("10.0.0.5"));
+        assert_eq!(web.user.as_deref(), Some("deploy"));
+        assert_eq!(web.port, Some(2200));
+        // `Host *` defaults are not copied onto concrete hosts
+        assert_eq!(host(&hosts, "db").user.as_deref(), Some("deploy"));
+    }
+
+    #[test]
+    fn accepts_equals_and_quotes() {
+        let hosts = parse_config(CONFIG).hosts;
+        assert_eq!(
+            host(&hosts, "with space").hostname.as_deref(),
+            Some("quoted.example.com")
+        );
+    }
+
+    #[test]
+    fn match_blocks_do_not_leak_into_hosts() {
+        let hosts = parse_config("Host a\nMatch all\nUser matched\n").hosts;
+        assert_eq!(hosts[0].user, None);
+    }
+
+    #[test]
+    fn known_hosts_skip_hashed_wildcard_and_revoked() {
+        let hosts = parse_known_hosts(KNOWN_HOSTS);
+        let names: Vec<(&str, Option<u16>)> =
+            hosts.iter().map(|h| (h.alias.as_str(), h.port)).collect();
+        assert_eq!(
+            names,
+            [
+                ("github.com", None),
+                ("140.82.121.4", None),
+                ("git.example.com", Some(2222)),
+                ("ca.example.com", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn globs_match_file_names() {
+        assert!(glob_match("*.conf", "work.conf"));
+        assert!(glob_match("host?", "host1"));
+        assert!(glob_match("*a*b", "xaxxab"));
+        assert!(!glob_match("*.conf", "notes.txt"));
+        assert!(!glob_match("host?", "host"));
+    }
+}
@@ -0,0 +1,226 @@
+use crate::frecency::{frecency_path, now_secs, Frecency};
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::ssh_config::{glob_match, parse_config, parse_known_hosts, SshHost};
+use crate::terminal::shell_quote;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+// Guards against Include cycles
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+fn ssh_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".ssh")
+}
+
+/// Resolves an `Include` argument the way ssh does: `~` is the home directory and
+/// relative paths are relative to the ssh directory. Globs are allowed in the
+/// file name.
+fn expand_include(pattern: &str, ssh_dir: &Path) -> Vec<PathBuf> {
+    let path = if let Some(rest) = pattern.strip_prefix("~/") {
+        dirs::home_dir().unwrap_or_default().join(rest)
+    } else if Path::new(pattern).is_absolute() {
+        PathBuf::from(pattern)
+    } else {
+        ssh_dir.join(pattern)
+    };
+
+    let Some(file_pattern) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        return Vec::new();
+    };
+    if !file_pattern.contains(['*', '?']) {
+        return vec![path];
+    }
+
+    let Some(dir) = path.parent() else {
+        return Vec::new();
+    };
+    let mut matches: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| glob_match(&file_pattern, &entry.file_name().to_string_lossy()))
+        .map(|entry| entry.path())
+        .collect();
+    matches.sort();
+    matches
+}
+
+fn load_config_hosts(path: &Path, ssh_dir: &Path, depth: usize, hosts: &mut Vec<SshHost>) {
+    if depth > MAX_INCLUDE_DEPTH {
+        return;
+    }
+    let Ok(contents) = fs::read_to_string(path) else {
+        return;
+    };
+
+    let config = parse_config(&contents);
+    for host in config.hosts {
+        if !hosts.iter().any(|h| h.alias == host.alias) {
+            hosts.push(host);
+        }
+    }
+    for include in &config.includes {
+        for included in expand_include(include, ssh_dir) {
+            load_config_hosts(&included, ssh_dir, depth + 1, hosts);
+        }
+    }
+}
+
+/// Hosts from ~/.ssh/config (and its includes) followed by known_hosts
+pub fn load_hosts() -> Vec<SshHost> {
+    let ssh_dir = ssh_dir();
+    let mut hosts = Vec::new();
+    load_config_hosts(&ssh_dir.join("config"), &ssh_dir, 0, &mut hosts);
+
+    if let Ok(contents) = fs::read_to_string(ssh_dir.join("known_hosts")) {
+        for host in parse_known_hosts(&contents) {
+            // Config aliases already cover hosts they point at
+            let known = hosts.iter().any(|h| {
+                h.alias == host.alias || h.hostname.as_deref() == Some(host.alias.as_str())
+            });
+            if !known {
+                hosts.push(host);
+            }
+        }
+    }
+
+    hosts
+}
+
+/// The `ssh` invocation for a host. Config aliases carry their own options.
+fn ssh_command(host: &SshHost) -> String {
+    match host.port {
+        Some(port) if host.hostname.is_none() => {
+            format!("ssh -p {} {}", port, shell_quote(&host.alias))
+        }
+        _ => format!("ssh {}", shell_quote(&host.alias)),
+    }
+}
+
+/// Key for the ssh invocation, which is unique per host
+pub fn frecency_key(command: &str) -> String {
+    format!("ssh:{}", command)
+}
+
+fn host_to_result(host: &SshHost) -> SearchResult {
+    SearchResult::new(host.alias.clone(), ssh_command(host), SearchMode::Ssh)
+        .with_alt_action("Copy host", ResultAction::Copy(host.alias.clone()))
+        .with_alt_action("Copy ssh command", ResultAction::Copy(ssh_command(host)))
+}
+
+pub fn search_ssh(query: &str) -> Vec<SearchResult> {
+    let hosts = load_hosts();
+    let frecency = Frecency::load(&frecency_path());
+    let now = now_secs();
+    let matcher = get_matcher();
+
+    let mut scored: Vec<(&SshHost, i64)> = hosts
+        .iter()
+        .filter_map(|host| {
+            let bonus = frecency.score(&frecency_key(&ssh_command(host)), now);
+            if query.is_empty() {
+                return Some((host, bonus));
+            }
+            std::iter::once(host.alias.as_str())
+                .chain(host.hostname.as_deref())
+                .filter_map(|text| matcher.fuzzy_match(text, query))
+                .max()
+                .map(|score| (host, score + bonus))
+        })
+        .collect();
+
+    // Stable sort keeps config order for hosts that were never used
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(host, _)| host_to_result(host))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ssh")
+    }
+
+    fn aliases(hosts: &[SshHost]) -> Vec<&str> {
+        hosts.iter().map(|h| h.alias.as_str()).collect()
+    }
+
+    #[test]
+    fn include_globs_relative_to_ssh_dir() {
+        let dir = fixtures();
+        assert_eq!(
+            expand_include("conf.d/*.conf", &dir),
+            vec![
+                dir.join("conf.d/personal.conf"),
+                dir.join("conf.d/work.conf")
+            ]
+        );
+        assert_eq!(expand_include("conf.d/none-*", &dir), Vec::<PathBuf>::new());
+        // Plain names are returned even when missing, reading them just fails
+        assert_eq!(expand_include("missing", &dir), vec![dir.join("missing")]);
+        assert_eq!(
+            expand_include("/etc/ssh/ssh_config", &dir),
+            vec![PathBuf::from("/etc/ssh/ssh_config")]
+        );
+    }
+
+    #[test]
+    fn loads_hosts_through_includes() {
+        let dir = fixtures();
+        let mut hosts = Vec::new();
+        load_config_hosts(&dir.join("config"), &dir, 0, &mut hosts);
+
+        // Included hosts follow the including file's own, notes.txt is not matched
+        assert_eq!(
+            aliases(&hosts),
+            ["web", "db", "quoted", "with space", "home", "work"]
+        );
+        // The first file to define a host wins
+        assert_eq!(hosts[4].hostname.as_deref(), Some("home.example.net"));
+        assert_eq!(hosts[4].port, Some(2222));
+        assert_eq!(hosts[5].user.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn include_cycles_stop_at_depth_limit() {
+        let dir = fixtures().join("loop");
+        let mut hosts = Vec::new();
+        load_config_hosts(&dir.join("config"), &dir, 0, &mut hosts);
+        assert_eq!(aliases(&hosts), ["looped"]);
+
+        // Past the limit nothing is read at all
+        let mut hosts = Vec::new();
+        load_config_hosts(&dir.join("config"), &dir, MAX_INCLUDE_DEPTH + 1, &mut hosts);
+        assert!(hosts.is_empty());
+    }
+
+    #[test]
+    fn port_is_passed_only_without_config_alias() {
+        let known = SshHost {
+            alias: "git.example.com".into(),
+            port: Some(2222),
+            ..Default::default()
+        };
+        assert_eq!(ssh_command(&known), "ssh -p 2222 git.example.com");
+
+        let configured = SshHost {
+            hostname: Some("10.0.0.5".into()),
+            ..known
+        };
+        assert_eq!(ssh_command(&configured), "ssh git.example.com");
+    }
+}
@@ -0,0 +1,234 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placeholder {
+    Date,     // YYYY-MM-DD
+    Time,     // HH:MM
+    DateTime, // YYYY-MM-DD HH:MM
+    Clipboard,
+    Uuid,
+    Input(String), // Asked for when the snippet is used
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Text(String),
+    Field(Placeholder),
+}
+
+fn parse_placeholder(body: &str) -> Option<Placeholder> {
+    if let Some(name) = body.strip_prefix("input:") {
+        let name = name.trim();
+        return (!name.is_empty()).then(|| Placeholder::Input(name.to_string()));
+    }
+    match body {
+        "date" => Some(Placeholder::Date),
+        "time" => Some(Placeholder::Time),
+        "datetime" => Some(Placeholder::DateTime),
+        "clipboard" => Some(Placeholder::Clipboard),
+        "uuid" => Some(Placeholder::Uuid),
+        _ => None,
+    }
+}
+
+fn push_text(segments: &mut Vec<Segment>, text: &str) {
+    if let Some(Segment::Text(last)) = segments.last_mut() {
+        last.push_str(text);
+    } else if !text.is_empty() {
+        segments.push(Segment::Text(text.to_string()));
+    }
+}
+
+/// Splits a snippet template into text and `{date}`, `{time}`, `{datetime}`,
+/// `{clipboard}`, `{uuid}` and `{input:Name}` fields. `{{` and `}}` stand for
+/// literal braces; anything else in braces is kept as written.
+pub fn parse(template: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        push_text(&mut segments, &rest[..pos]);
+        let tail = &rest[pos..];
+
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            push_text(&mut segments, &tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if let Some(after) = tail.strip_prefix('}') {
+            push_text(&mut segments, "}");
+            rest = after;
+            continue;
+        }
+
+        // An opening brace: a field if it closes on something we know
+        match tail[1..].find(['{', '}']) {
+            Some(end) if tail[1 + end..].starts_with('}') => {
+                let body = &tail[1..1 + end];
+                match parse_placeholder(body) {
+                    Some(placeholder) => segments.push(Segment::Field(placeholder)),
+                    None => push_text(&mut segments, &tail[..end + 2]),
+                }
+                rest = &tail[end + 2..];
+            }
+            _ => {
+                push_text(&mut segments, "{");
+                rest = &tail[1..];
+            }
+        }
+    }
+    push_text(&mut segments, rest);
+    segments
+}
+
+/// Names of the `{input:...}` fields in order of first appearance
+pub fn inputs(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for segment in parse(template) {
+        if let Segment::Field(Placeholder::Input(name)) = segment {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+fn escape(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+fn source(placeholder: &Placeholder) -> String {
+    match placeholder {
+        Placeholder::Date => "{date}".to_string(),
+        Placeholder::Time => "{time}".to_string(),
+        Placeholder::DateTime => "{datetime}".to_string(),
+        Placeholder::Clipboard => "{clipboard}".to_string(),
+        Placeholder::Uuid => "{uuid}".to_string(),
+        Placeholder::Input(name) => format!("{{input:{}}}", name),
+    }
+}
+
+/// Replaces every `{input:name}` with `value`, leaving a template that still
+/// expands the remaining fields. Braces in the value stay literal.
+pub fn fill_input(template: &str, name: &str, value: &str) -> String {
+    parse(template)
+        .iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => escape(text),
+            Segment::Field(Placeholder::Input(input)) if input == name => escape(value),
+            Segment::Field(placeholder) => source(placeholder),
+        })
+        .collect()
+}
+
+/// Expands a template, asking `resolve` for each field occurrence (so every
+/// `{uuid}` can differ)
+pub fn expand(template: &str, resolve: &mut dyn FnMut(&Placeholder) -> String) -> String {
+    parse(template)
+        .iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => text.clone(),
+            Segment::Field(placeholder) => resolve(placeholder),
+        })
+        .collect()
+}
+
+/// Formats 16 random bytes as a version 4 UUID
+pub fn format_uuid(mut bytes: [u8; 16]) -> String {
+    bytes[6] = (bytes[6] & 0x0f) | 0x40; // Version 4
+    bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Segment {
+        Segment::Text(s.to_string())
+    }
+
+    #[test]
+    fn splits_text_and_fields() {
+        assert_eq!(
+            parse("On {date} at {time}: {input:Ticket}"),
+            [
+                text("On "),
+                Segment::Field(Placeholder::Date),
+                text(" at "),
+                Segment::Field(Placeholder::Time),
+                text(": "),
+                Segment::Field(Placeholder::Input("Ticket".into())),
+            ]
+        );
+        assert_eq!(
+            parse("{input: Name }"),
+            [Segment::Field(Placeholder::Input("Name".into()))]
+        );
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(parse("{{date}}"), [text("{date}")]);
+        assert_eq!(parse("fn main() {{ }}"), [text("fn main() { }")]);
+    }
+
+    #[test]
+    fn unknown_and_unbalanced_braces_stay_as_written() {
+        assert_eq!(parse("{foo} and {input:}"), [text("{foo} and {input:}")]);
+        assert_eq!(parse("a } b {"), [text("a } b {")]);
+        assert_eq!(
+            parse("{a{date}"),
+            [text("{a"), Segment::Field(Placeholder::Date)]
+        );
+    }
+
+    #[test]
+    fn lists_inputs_once_in_order() {
+        assert_eq!(
+            inputs("{input:To} {input:Subject} {input:To} {uuid}"),
+            ["To", "Subject"]
+        );
+    }
+
+    #[test]
+    fn fill_input_escapes_braces_in_values() {
+        let filled = fill_input("{input:Code} on {date} {input:Other}", "Code", "{uuid}");
+        assert_eq!(filled, "{{uuid}} on {date} {input:Other}");
+        assert_eq!(inputs(&filled), ["Other"]);
+
+        let mut resolve = |p: &Placeholder| match p {
+            Placeholder::Date => "2026-10-18".to_string(),
+            _ => "?".to_string(),
+        };
+        assert_eq!(expand(&filled, &mut resolve), "{uuid} on 2026-10-18 ?");
+        // Literal braces in the template survive a fill
+        assert_eq!(fill_input("{{x}} {input:A}", "A", "}"), "{{x}} }}");
+    }
+
+    #[test]
+    fn repeated_fields_resolve_separately() {
+        let mut count = 0;
+        let expanded = expand("{uuid} {uuid} {uuid}", &mut |_| {
+            count += 1;
+            count.to_string()
+        });
+        assert_eq!(expanded, "1 2 3");
+    }
+
+    #[test]
+    fn uuid_sets_version_and_variant() {
+        assert_eq!(
+            format_uuid([0xff; 16]),
+            "ffffffff-ffff-4fff-bfff-ffffffffffff"
+        );
+        assert_eq!(format_uuid([0; 16]), "00000000-0000-4000-8000-000000000000");
+    }
+}
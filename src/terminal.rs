@@ -0,0 +1,86 @@
+use std::process::Command;
+
+/// Quotes a string for POSIX `sh`
+pub fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c))
+    {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Builds the program and arguments that run `command` in the configured terminal.
+///
+/// `terminal` is either "Terminal" or "iTerm" (driven through AppleScript), a
+/// template containing `{cmd}` (replaced by the shell-quoted command), or the
+/// name of a terminal that accepts `-e`.
+pub fn terminal_command(terminal: &str, command: &str) -> (String, Vec<String>) {
+    let terminal = terminal.trim();
+
+    match terminal.to_lowercase().as_str() {
+        "" | "terminal" | "terminal.app" => {
+            let script = format!(
+                "tell application \"Terminal\"\nactivate\ndo script {}\nend tell",
+                applescript_string(command)
+            );
+            ("osascript".to_string(), vec!["-e".to_string(), script])
+        }
+        "iterm" | "iterm2" | "iterm.app" => {
+            let script = format!(
+                "tell application \"iTerm\"\nactivate\ncreate window with default profile command {}\nend tell",
+                applescript_string(command)
+            );
+            ("osascript".to_string(), vec!["-e".to_string(), script])
+        }
+        _ if terminal.contains("{cmd}") => {
+            let line = terminal.replace("{cmd}", &shell_quote(command));
+            ("sh".to_string(), vec!["-c".to_string(), line])
+        }
+        _ => (
+            terminal.to_string(),
+            vec![
+                "-e".to_string(),
+                "sh".to_string(),
+                "-c".to_string(),
+                command.to_string(),
+            ],
+        ),
+    }
+}
+
+/// Runs a shell command in a new terminal window
+pub fn run_in_terminal(terminal: &str, command: &str) {
+    let (program, args) = terminal_command(terminal, command);
+    Command::new(program).args(args).spawn().ok();
+}
+
+/// Builds the shell line that opens `path` in the configured editor. `{path}` in
+/// the editor command is replaced by the quoted path, otherwise it is appended.
+pub fn editor_command(editor: &str, path: &str) -> String {
+    if editor.contains("{path}") {
+        editor.replace("{path}", &shell_quote(path))
+    } else {
+        format!("{} {}", editor.trim(), shell_quote(path))
+    }
+}
+
+pub fn open_in_editor(editor: &str, path: &str) {
+    Command::new("sh")
+        .arg("-c")
+        .arg(editor_command(editor, path))
+        .spawn()
+        .ok();
+}
+
+/// Opens an interactive shell in `dir` in the configured terminal
+pub fn open_terminal_at(terminal: &str, dir: &str) {
+    let command = format!("cd {} && exec \"${{SHELL:-sh}}\"", shell_quote(dir));
+    run_in_terminal(terminal, &command);
+}
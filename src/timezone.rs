@@ -0,0 +1,713 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Days since 1970-01-01 for a proleptic Gregorian date
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of `days_from_civil`: (year, month, day)
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+pub fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+/// Day of week for days since the epoch, 0 = Sunday
+pub fn weekday(days: i64) -> u32 {
+    (days + 4).rem_euclid(7) as u32
+}
+
+pub fn weekday_name(days: i64) -> &'static str {
+    ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"][weekday(days) as usize]
+}
+
+/// Breaks local seconds since the epoch into (year, month, day, hour, minute, second)
+pub fn civil_from_seconds(local: i64) -> (i64, u32, u32, u32, u32, u32) {
+    let days = local.div_euclid(SECONDS_PER_DAY);
+    let secs = local.rem_euclid(SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    (
+        year,
+        month,
+        day,
+        (secs / 3600) as u32,
+        (secs % 3600 / 60) as u32,
+        (secs % 60) as u32,
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LocalTimeType {
+    offset: i32,
+    is_dst: bool,
+    abbreviation: String,
+}
+
+/// When a POSIX TZ rule switches between standard and daylight time
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RuleDate {
+    Julian(u32),                 // Jn: 1..=365, February 29 is never counted
+    ZeroBased(u32),              // n: 0..=365, counting February 29
+    MonthWeekDay(u32, u32, u32), // Mm.w.d
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct DaylightRule {
+    dst: LocalTimeType,
+    start: RuleDate,
+    start_time: i64,
+    end: RuleDate,
+    end_time: i64,
+}
+
+/// POSIX TZ string such as `CET-1CEST,M3.5.0,M10.5.0/3`, used by TZif
+/// footers for times after the last explicit transition
+#[derive(Debug, Clone, PartialEq)]
+struct PosixRule {
+    std: LocalTimeType,
+    daylight: Option<DaylightRule>,
+}
+
+/// A time zone loaded from the system tz database or a fixed UTC offset
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeZone {
+    pub name: String,
+    transitions: Vec<i64>,
+    transition_types: Vec<usize>,
+    types: Vec<LocalTimeType>,
+    rule: Option<PosixRule>,
+}
+
+impl TimeZone {
+    pub fn utc() -> Self {
+        Self::fixed("UTC", 0)
+    }
+
+    pub fn fixed(name: &str, offset: i32) -> Self {
+        TimeZone {
+            name: name.to_string(),
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            types: vec![LocalTimeType {
+                offset,
+                is_dst: false,
+                abbreviation: name.to_string(),
+            }],
+            rule: None,
+        }
+    }
+
+    /// Loads a zone such as `Europe/Berlin` from a zoneinfo directory
+    pub fn load(zoneinfo: &Path, name: &str) -> Option<Self> {
+        let data = fs::read(zoneinfo.join(name)).ok()?;
+        Self::parse_tzif(name, &data)
+    }
+
+    /// The local zone from `$TZ`, falling back to `/etc/localtime`
+    pub fn local(zoneinfo: &Path) -> Self {
+        if let Ok(tz) = std::env::var("TZ") {
+            let tz = tz.trim_start_matches(':');
+            let zone = if tz.starts_with('/') {
+                fs::read(tz)
+                    .ok()
+                    .and_then(|data| Self::parse_tzif(tz, &data))
+            } else {
+                Self::load(zoneinfo, tz).or_else(|| {
+                    parse_posix_rule(tz).map(|rule| TimeZone {
+                        name: tz.to_string(),
+                        transitions: Vec::new(),
+                        transition_types: Vec::new(),
+                        types: vec![rule.std.clone()],
+                        rule: Some(rule),
+                    })
+                })
+            };
+            if let Some(zone) = zone {
+                return zone;
+            }
+        }
+
+        // /etc/localtime is usually a symlink into the zoneinfo tree, which gives us the name
+        let name = fs::read_link("/etc/localtime")
+            .ok()
+            .and_then(|target| {
+                let target = target.to_string_lossy().to_string();
+                target
+                    .split_once("zoneinfo/")
+                    .map(|(_, name)| name.to_string())
+            })
+            .unwrap_or_else(|| "localtime".to_string());
+        fs::read("/etc/localtime")
+            .ok()
+            .and_then(|data| Self::parse_tzif(&name, &data))
+            .unwrap_or_else(Self::utc)
+    }
+
+    /// Parses TZif data (RFC 8536), preferring the 64-bit v2+ section
+    pub fn parse_tzif(name: &str, data: &[u8]) -> Option<Self> {
+        let header = TzifHeader::parse(data)?;
+        let (header, body, time_size) = if header.version >= b'2' {
+            let second = data.get(44 + header.v1_body_len()..)?;
+            (TzifHeader::parse(second)?, second.get(44..)?, 8)
+        } else {
+            (header, &data[44..], 4)
+        };
+
+        let mut pos = 0;
+        let mut take = |len: usize| -> Option<&[u8]> {
+            let slice = body.get(pos..pos + len)?;
+            pos += len;
+            Some(slice)
+        };
+
+        let transitions: Vec<i64> = take(header.timecnt * time_size)?
+            .chunks(time_size)
+            .map(|chunk| match time_size {
+                8 => i64::from_be_bytes(chunk.try_into().unwrap()),
+                _ => i32::from_be_bytes(chunk.try_into().unwrap()) as i64,
+            })
+            .collect();
+        let transition_types: Vec<usize> =
+            take(header.timecnt)?.iter().map(|i| *i as usize).collect();
+        let raw_types: Vec<(i32, bool, usize)> = take(header.typecnt * 6)?
+            .chunks(6)
+            .map(|c| {
+                (
+                    i32::from_be_bytes([c[0], c[1], c[2], c[3]]),
+                    c[4] != 0,
+                    c[5] as usize,
+                )
+            })
+            .collect();
+        let chars = take(header.charcnt)?;
+        take(header.leapcnt * (time_size + 4))?;
+        take(header.isstdcnt)?;
+        take(header.isutcnt)?;
+
+        let types: Vec<LocalTimeType> = raw_types
+            .into_iter()
+            .map(|(offset, is_dst, index)| {
+                let abbr = chars.get(index..).unwrap_or_default();
+                let end = abbr.iter().position(|b| *b == 0).unwrap_or(abbr.len());
+                LocalTimeType {
+                    offset,
+                    is_dst,
+                    abbreviation: String::from_utf8_lossy(&abbr[..end]).to_string(),
+                }
+            })
+            .collect();
+
+        if types.is_empty() || transition_types.iter().any(|i| *i >= types.len()) {
+            return None;
+        }
+
+        // The footer is a newline-enclosed POSIX TZ string (v2+ only)
+        let rule = if time_size == 8 {
+            let footer = String::from_utf8_lossy(&body[pos..]);
+            footer
+                .trim_matches('\n')
+                .lines()
+                .next()
+                .and_then(parse_posix_rule)
+        } else {
+            None
+        };
+
+        Some(TimeZone {
+            name: name.to_string(),
+            transitions,
+            transition_types,
+            types,
+            rule,
+        })
+    }
+
+    fn local_time_type(&self, utc: i64) -> LocalTimeType {
+        let index = self.transitions.partition_point(|t| *t <= utc);
+        if index == 0 {
+            // Before the first transition: the first standard time type
+            return self
+                .types
+                .iter()
+                .find(|t| !t.is_dst)
+                .unwrap_or(&self.types[0])
+                .clone();
+        }
+        if index == self.transitions.len() {
+            if let Some(rule) = &self.rule {
+                return rule.local_time_type(utc);
+            }
+        }
+        self.types[self.transition_types[index - 1]].clone()
+    }
+
+    /// UTC offset in seconds at the given instant
+    pub fn offset_at(&self, utc: i64) -> i32 {
+        self.local_time_type(utc).offset
+    }
+
+    /// Abbreviation such as "CEST" at the given instant
+    pub fn abbreviation_at(&self, utc: i64) -> String {
+        self.local_time_type(utc).abbreviation
+    }
+
+    /// Converts local wall clock seconds to UTC, resolving DST gaps forwards
+    pub fn local_to_utc(&self, local: i64) -> i64 {
+        let guess = local - self.offset_at(local) as i64;
+        let offset = self.offset_at(guess) as i64;
+        let utc = local - offset;
+        if self.offset_at(utc) as i64 == offset {
+            utc
+        } else {
+            // No offset fits, so the time falls into a gap: read it with the
+            // offset from before the change, which gives the later instant
+            utc.max(local - self.offset_at(utc) as i64)
+        }
+    }
+
+    /// Local wall clock seconds for the given instant
+    pub fn to_local(&self, utc: i64) -> i64 {
+        utc + self.offset_at(utc) as i64
+    }
+}
+
+struct TzifHeader {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl TzifHeader {
+    fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < 44 || &data[..4] != b"TZif" {
+            return None;
+        }
+        let count = |i: usize| {
+            let start = 20 + i * 4;
+            u32::from_be_bytes(data[start..start + 4].try_into().unwrap()) as usize
+        };
+        Some(TzifHeader {
+            version: data[4],
+            isutcnt: count(0),
+            isstdcnt: count(1),
+            leapcnt: count(2),
+            timecnt: count(3),
+            typecnt: count(4),
+            charcnt: count(5),
+        })
+    }
+
+    fn v1_body_len(&self) -> usize {
+        self.timecnt * 5
+            + self.typecnt * 6
+            + self.charcnt
+            + self.leapcnt * 8
+            + self.isstdcnt
+            + self.isutcnt
+    }
+}
+
+impl PosixRule {
+    fn local_time_type(&self, utc: i64) -> LocalTimeType {
+        let Some(daylight) = &self.daylight else {
+            return self.std.clone();
+        };
+
+        let std_offset = self.std.offset as i64;
+        let dst_offset = daylight.dst.offset as i64;
+        let (year, ..) = civil_from_seconds(utc + std_offset);
+
+        // Start is given in standard time, end in daylight time
+        let start = daylight.start.day(year) * SECONDS_PER_DAY + daylight.start_time - std_offset;
+        let end = daylight.end.day(year) * SECONDS_PER_DAY + daylight.end_time - dst_offset;
+
+        let in_dst = if start < end {
+            utc >= start && utc < end
+        } else {
+            // Southern hemisphere: DST spans the new year
+            !(utc >= end && utc < start)
+        };
+        if in_dst {
+            daylight.dst.clone()
+        } else {
+            self.std.clone()
+        }
+    }
+}
+
+impl RuleDate {
+    /// Days since the epoch on which the rule fires in `year`
+    fn day(&self, year: i64) -> i64 {
+        let jan1 = days_from_civil(year, 1, 1);
+        match *self {
+            RuleDate::Julian(n) => {
+                let leap_shift = if is_leap_year(year) && n >= 60 { 1 } else { 0 };
+                jan1 + n as i64 - 1 + leap_shift
+            }
+            RuleDate::ZeroBased(n) => jan1 + n as i64,
+            RuleDate::MonthWeekDay(month, week, day) => {
+                let first = days_from_civil(year, month, 1);
+                let first_match = first + ((day + 7 - weekday(first)) % 7) as i64;
+                let mut date = first_match + (week as i64 - 1) * 7;
+                // Week 5 means "last", which may only have four occurrences
+                while date >= first + days_in_month(year, month) as i64 {
+                    date -= 7;
+                }
+                date
+            }
+        }
+    }
+}
+
+fn parse_posix_rule(spec: &str) -> Option<PosixRule> {
+    let mut rest = spec;
+    let std_name = take_zone_name(&mut rest)?;
+    let std_offset = -take_offset(&mut rest)?;
+    let std = LocalTimeType {
+        offset: std_offset as i32,
+        is_dst: false,
+        abbreviation: std_name,
+    };
+
+    if rest.is_empty() {
+        return Some(PosixRule {
+            std,
+            daylight: None,
+        });
+    }
+
+    let dst_name = take_zone_name(&mut rest)?;
+    let dst_offset = if rest.starts_with(',') || rest.is_empty() {
+        std_offset + 3600
+    } else {
+        -take_offset(&mut rest)?
+    };
+
+    // Without explicit dates POSIX implies the US rules
+    let rules = rest.strip_prefix(',').unwrap_or("M3.2.0,M11.1.0");
+    let (start, end) = rules.split_once(',')?;
+    let (start, start_time) = parse_rule_date(start)?;
+    let (end, end_time) = parse_rule_date(end)?;
+
+    Some(PosixRule {
+        std,
+        daylight: Some(DaylightRule {
+            dst: LocalTimeType {
+                offset: dst_offset as i32,
+                is_dst: true,
+                abbreviation: dst_name,
+            },
+            start,
+            start_time,
+            end,
+            end_time,
+        }),
+    })
+}
+
+fn take_zone_name(rest: &mut &str) -> Option<String> {
+    let (name, remainder) = if let Some(quoted) = rest.strip_prefix('<') {
+        let end = quoted.find('>')?;
+        (&quoted[..end], &quoted[end + 1..])
+    } else {
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        rest.split_at(end)
+    };
+    if name.len() < 3 {
+        return None;
+    }
+    *rest = remainder;
+    Some(name.to_string())
+}
+
+/// Parses `[+-]hh[:mm[:ss]]` into seconds
+fn take_offset(rest: &mut &str) -> Option<i64> {
+    let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == ':' || c == '+' || c == '-'))
+        .unwrap_or(rest.len());
+    let seconds = parse_hms(&rest[..end])?;
+    *rest = &rest[end..];
+    Some(seconds)
+}
+
+fn parse_hms(text: &str) -> Option<i64> {
+    let (sign, digits) = match text.as_bytes().first()? {
+        b'-' => (-1, &text[1..]),
+        b'+' => (1, &text[1..]),
+        _ => (1, text),
+    };
+    let mut parts = digits.split(':');
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = parts.next().map_or(Some(0), |m| m.parse().ok())?;
+    let seconds: i64 = parts.next().map_or(Some(0), |s| s.parse().ok())?;
+    Some(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+fn parse_rule_date(text: &str) -> Option<(RuleDate, i64)> {
+    let (date, time) = match text.split_once('/') {
+        Some((date, time)) => (date, parse_hms(time)?),
+        None => (text, 7200),
+    };
+    let rule = if let Some(mwd) = date.strip_prefix('M') {
+        let mut parts = mwd.split('.').map(|p| p.parse::<u32>().ok());
+        let (month, week, day) = (parts.next()??, parts.next()??, parts.next()??);
+        if !(1..=12).contains(&month) || !(1..=5).contains(&week) || day > 6 {
+            return None;
+        }
+        RuleDate::MonthWeekDay(month, week, day)
+    } else if let Some(julian) = date.strip_prefix('J') {
+        RuleDate::Julian(julian.parse().ok()?)
+    } else {
+        RuleDate::ZeroBased(date.parse().ok()?)
+    };
+    Some((rule, time))
+}
+
+/// The system tz database directory, honouring `$TZDIR`
+pub fn zoneinfo_dir() -> PathBuf {
+    std::env::var("TZDIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("/usr/share/zoneinfo"))
+}
+
+/// Finds a zone by IANA name ("Europe/Berlin") or city ("berlin", "new york"),
+/// ignoring case.
+pub fn find_zone(zoneinfo: &Path, query: &str) -> Option<TimeZone> {
+    let wanted = query.trim().replace(' ', "_").to_lowercase();
+    if wanted.is_empty() {
+        return None;
+    }
+
+    let mut stack = vec![(zoneinfo.to_path_buf(), String::new())];
+    let mut city_match = None;
+    while let Some((dir, prefix)) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_name) = entry.file_name().into_string() else {
+                continue;
+            };
+            // Skip the duplicate "posix"/"right" trees and non-zone files
+            if file_name.starts_with('.')
+                || file_name.contains('.')
+                || file_name == "posix"
+                || file_name == "right"
+            {
+                continue;
+            }
+            let name = if prefix.is_empty() {
+                file_name.clone()
+            } else {
+                format!("{}/{}", prefix, file_name)
+            };
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                stack.push((entry.path(), name));
+            } else if name.to_lowercase() == wanted {
+                return TimeZone::load(zoneinfo, &name);
+            } else if city_match.is_none() && file_name.to_lowercase() == wanted {
+                city_match = Some(name);
+            }
+        }
+    }
+
+    city_match.and_then(|name| TimeZone::load(zoneinfo, &name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BERLIN: &[u8] = include_bytes!("../tests/fixtures/zoneinfo/Europe/Berlin");
+    const NEW_YORK: &[u8] = include_bytes!("../tests/fixtures/zoneinfo/America/New_York");
+    const SYDNEY: &[u8] = include_bytes!("../tests/fixtures/zoneinfo/Australia/Sydney");
+
+    fn berlin() -> TimeZone {
+        TimeZone::parse_tzif("Europe/Berlin", BERLIN).unwrap()
+    }
+
+    #[test]
+    fn epoch_and_civil_dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(2024, 2, 29), 19_782);
+        assert_eq!(civil_from_days(20_512), (2026, 2, 28));
+        assert_eq!(civil_from_days(-719_468), (0, 3, 1));
+        for days in (-800_000..800_000).step_by(997) {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        assert_eq!(weekday_name(0), "Thu");
+        assert_eq!(weekday_name(-1), "Wed");
+        assert_eq!(
+            civil_from_seconds(1_769_860_800 + 45),
+            (2026, 1, 31, 12, 0, 45)
+        );
+    }
+
+    #[test]
+    fn leap_years() {
+        assert!(is_leap_year(2024));
+        assert!(is_leap_year(2000));
+        assert!(!is_leap_year(1900));
+        assert_eq!(days_in_month(2023, 2), 28);
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2024, 4), 30);
+    }
+
+    #[test]
+    fn dst_transitions_from_tzif() {
+        let zone = berlin();
+        // 2026-03-29 01:00Z: CET -> CEST
+        assert_eq!(zone.offset_at(1_774_746_000 - 1), 3600);
+        assert_eq!(zone.abbreviation_at(1_774_746_000 - 1), "CET");
+        assert_eq!(zone.offset_at(1_774_746_000), 7200);
+        assert_eq!(zone.abbreviation_at(1_774_746_000), "CEST");
+        // 2026-10-25 01:00Z: back to CET
+        assert_eq!(zone.offset_at(1_792_890_000 - 1), 7200);
+        assert_eq!(zone.offset_at(1_792_890_000), 3600);
+        // Before the first transition: local mean time is not DST
+        assert!(zone.offset_at(i64::MIN / 2) > 0);
+    }
+
+    #[test]
+    fn local_to_utc_resolves_gaps_forwards() {
+        let zone = berlin();
+        let day = days_from_civil(2026, 3, 29) * SECONDS_PER_DAY;
+        // 01:30 CET exists, 02:30 falls into the gap and becomes 03:30 CEST
+        assert_eq!(zone.local_to_utc(day + 5400), day + 1800);
+        assert_eq!(zone.local_to_utc(day + 9000), day + 5400);
+        assert_eq!(zone.to_local(day + 5400), day + 12_600);
+
+        // The same gap west of Greenwich: 2026-03-08 02:30 in New York is 03:30 EDT
+        let new_york = TimeZone::parse_tzif("America/New_York", NEW_YORK).unwrap();
+        let day = days_from_civil(2026, 3, 8) * SECONDS_PER_DAY;
+        assert_eq!(new_york.local_to_utc(day + 9000), day + 9000 + 5 * 3600);
+        // 01:30 on 2026-11-01 happens twice; either reading is accepted
+        let day = days_from_civil(2026, 11, 1) * SECONDS_PER_DAY;
+        let utc = new_york.local_to_utc(day + 5400);
+        assert_eq!(new_york.to_local(utc), day + 5400);
+    }
+
+    #[test]
+    fn footer_rule_applies_after_last_transition() {
+        let zone = berlin();
+        // The fixture lists transitions up to 2037; 2040 comes from the footer
+        assert_eq!(zone.offset_at(2_216_250_000 - 1), 3600);
+        assert_eq!(zone.offset_at(2_216_250_000), 7200);
+        assert_eq!(zone.abbreviation_at(2_216_250_000), "CEST");
+        assert_eq!(zone.offset_at(2_234_998_800 - 1), 7200);
+        assert_eq!(zone.offset_at(2_234_998_800), 3600);
+
+        // Southern hemisphere: daylight time spans the new year
+        let sydney = TimeZone::parse_tzif("Australia/Sydney", SYDNEY).unwrap();
+        assert_eq!(sydney.abbreviation_at(2_210_198_400), "AEDT");
+        assert_eq!(sydney.offset_at(2_210_198_400), 39_600);
+        assert_eq!(sydney.abbreviation_at(2_225_923_200), "AEST");
+    }
+
+    #[test]
+    fn posix_rules() {
+        let rule = parse_posix_rule("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        assert_eq!(rule.std.offset, 3600);
+        let daylight = rule.daylight.as_ref().unwrap();
+        assert_eq!(daylight.dst.offset, 7200);
+        assert_eq!(daylight.start, RuleDate::MonthWeekDay(3, 5, 0));
+        assert_eq!(daylight.start_time, 7200);
+        assert_eq!(daylight.end_time, 10_800);
+        // Last Sunday in March 2026 is the 29th, the first in November the 1st
+        assert_eq!(daylight.start.day(2026), days_from_civil(2026, 3, 29));
+        assert_eq!(
+            RuleDate::MonthWeekDay(11, 1, 0).day(2026),
+            days_from_civil(2026, 11, 1)
+        );
+
+        // Implied US dates and quoted numeric names
+        let us = parse_posix_rule("EST5EDT").unwrap();
+        assert_eq!(us.std.offset, -18_000);
+        assert_eq!(us.daylight.unwrap().start, RuleDate::MonthWeekDay(3, 2, 0));
+        let quoted = parse_posix_rule("<+0530>-5:30").unwrap();
+        assert_eq!(quoted.std.abbreviation, "+0530");
+        assert_eq!(quoted.std.offset, 19_800);
+        assert!(quoted.daylight.is_none());
+
+        // Jn skips February 29, n counts it
+        assert_eq!(RuleDate::Julian(60).day(2024), days_from_civil(2024, 3, 1));
+        assert_eq!(
+            RuleDate::ZeroBased(59).day(2024),
+            days_from_civil(2024, 2, 29)
+        );
+
+        assert!(parse_posix_rule("X1").is_none());
+        assert!(parse_posix_rule("CET-1CEST,M13.1.0,M10.5.0").is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_tzif() {
+        for len in [0, 10, 44, 100, BERLIN.len() / 2, BERLIN.len() - 60] {
+            assert!(
+                TimeZone::parse_tzif("cut", &BERLIN[..len]).is_none(),
+                "{}",
+                len
+            );
+        }
+        let mut corrupt = BERLIN.to_vec();
+        corrupt[20..24].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(TimeZone::parse_tzif("corrupt", &corrupt).is_none());
+    }
+
+    #[test]
+    fn finds_zones_by_name_or_city() {
+        let zoneinfo = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/zoneinfo"
+        ));
+        assert_eq!(
+            find_zone(zoneinfo, "europe/berlin").unwrap().name,
+            "Europe/Berlin"
+        );
+        assert_eq!(
+            find_zone(zoneinfo, "new york").unwrap().name,
+            "America/New_York"
+        );
+        assert!(find_zone(zoneinfo, "atlantis").is_none());
+    }
+}
@@ -0,0 +1,295 @@
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::terminal::shell_quote;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+// tmux never allows `:` in session names, so it can separate the fields; the
+// window name, which may contain anything, always comes last
+pub const SESSION_FORMAT: &str = "#{session_name}:#{session_windows}:#{session_attached}";
+pub const WINDOW_FORMAT: &str =
+    "#{session_name}:#{window_index}:#{window_active}:#{window_panes}:#{window_name}";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmuxSession {
+    pub name: String,
+    pub windows: u32,
+    pub attached: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmuxWindow {
+    pub session: String,
+    pub index: u32,
+    pub name: String,
+    pub active: bool,
+    pub panes: u32,
+}
+
+impl TmuxWindow {
+    /// The `session:index` target
+    pub fn target(&self) -> String {
+        format!("{}:{}", self.session, self.index)
+    }
+}
+
+/// Parses `tmux list-sessions -F` output in [`SESSION_FORMAT`]
+pub fn parse_sessions(output: &str) -> Vec<TmuxSession> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next().filter(|n| !n.is_empty())?;
+            let windows = fields.next()?.parse().ok()?;
+            // Counts the clients attached to the session
+            let attached = fields.next()?.parse::<u32>().ok()? > 0;
+            Some(TmuxSession {
+                name: name.to_string(),
+                windows,
+                attached,
+            })
+        })
+        .collect()
+}
+
+/// Parses `tmux list-windows -a -F` output in [`WINDOW_FORMAT`]
+pub fn parse_windows(output: &str) -> Vec<TmuxWindow> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, ':');
+            let session = fields.next().filter(|s| !s.is_empty())?;
+            let index = fields.next()?.parse().ok()?;
+            let active = fields.next()? == "1";
+            let panes = fields.next()?.parse().ok()?;
+            let name = fields.next()?;
+            Some(TmuxWindow {
+                session: session.to_string(),
+                index,
+                name: name.to_string(),
+                active,
+                panes,
+            })
+        })
+        .collect()
+}
+
+/// tmux turns `:` and `.` in session names into `_`; do it up front so the
+/// name we attach to is the one that gets created
+pub fn session_name(query: &str) -> String {
+    query
+        .trim()
+        .chars()
+        .map(|c| if c == ':' || c == '.' { '_' } else { c })
+        .collect()
+}
+
+/// The tmux binary; apps started from Finder do not see the shell's PATH
+fn tmux_path() -> String {
+    [
+        "/opt/homebrew/bin/tmux",
+        "/usr/local/bin/tmux",
+        "/usr/bin/tmux",
+    ]
+    .into_iter()
+    .find(|p| Path::new(p).exists())
+    .unwrap_or("tmux")
+    .to_string()
+}
+
+fn tmux_output(args: &[&str]) -> Option<String> {
+    let output = Command::new(tmux_path()).args(args).output().ok()?;
+    // Fails with "no server running" when there are no sessions
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+pub fn load_sessions() -> Vec<TmuxSession> {
+    tmux_output(&["list-sessions", "-F", SESSION_FORMAT])
+        .map(|out| parse_sessions(&out))
+        .unwrap_or_default()
+}
+
+pub fn load_windows() -> Vec<TmuxWindow> {
+    tmux_output(&["list-windows", "-a", "-F", WINDOW_FORMAT])
+        .map(|out| parse_windows(&out))
+        .unwrap_or_default()
+}
+
+fn attach_command(target: &str) -> String {
+    format!("{} attach-session -t {}", tmux_path(), shell_quote(target))
+}
+
+/// Result for a target. When some client is attached already, Enter switches
+/// that client; otherwise `path` is attached in a new terminal.
+fn target_result(name: String, target: &str, hint: String, has_client: bool) -> SearchResult {
+    let mut result = SearchResult::new(name, attach_command(target), SearchMode::Tmux)
+        .with_hint(&hint)
+        .with_alt_action("Attach in new terminal", ResultAction::Open)
+        .with_alt_action("Copy target", ResultAction::Copy(target.to_string()));
+    if has_client {
+        result = result.with_action(ResultAction::Shell(format!(
+            "{} switch-client -t {}",
+            tmux_path(),
+            shell_quote(target)
+        )));
+    }
+    result
+}
+
+fn session_to_result(session: &TmuxSession, has_client: bool) -> SearchResult {
+    let windows = if session.windows == 1 {
+        "1 window".to_string()
+    } else {
+        format!("{} windows", session.windows)
+    };
+    let hint = if session.attached {
+        format!("{} · attached", windows)
+    } else {
+        windows
+    };
+    target_result(session.name.clone(), &session.name, hint, has_client)
+}
+
+fn window_to_result(window: &TmuxWindow, has_client: bool) -> SearchResult {
+    let mut hint = window.target();
+    if window.panes > 1 {
+        hint.push_str(&format!(" · {} panes", window.panes));
+    }
+    if window.active {
+        hint.push_str(" · active");
+    }
+    target_result(window.name.clone(), &window.target(), hint, has_client)
+}
+
+/// "New session" entry that attaches when the name exists after all
+pub fn new_session_result(query: &str) -> SearchResult {
+    let name = session_name(query);
+    SearchResult::new(
+        format!("New session \"{}\"", name),
+        format!("{} new-session -A -s {}", tmux_path(), shell_quote(&name)),
+        SearchMode::Tmux,
+    )
+}
+
+pub fn search_tmux(query: &str) -> Vec<SearchResult> {
+    let sessions = load_sessions();
+    let windows = load_windows();
+    let has_client = sessions.iter().any(|s| s.attached);
+
+    let mut results: Vec<SearchResult> = if query.is_empty() {
+        sessions
+            .iter()
+            .map(|s| session_to_result(s, has_client))
+            .chain(windows.iter().map(|w| window_to_result(w, has_client)))
+            .take(15)
+            .collect()
+    } else {
+        let matcher = get_matcher();
+        // Session names weigh double; the stable sort keeps sessions first on ties
+        let mut scored: Vec<(SearchResult, i64)> = sessions
+            .iter()
+            .filter_map(|s| {
+                let score = matcher.fuzzy_match(&s.name, query)?;
+                Some((session_to_result(s, has_client), score * 2))
+            })
+            .chain(windows.iter().filter_map(|w| {
+                let text = format!("{} {}", w.session, w.name);
+                let score = matcher.fuzzy_match(&text, query)?;
+                Some((window_to_result(w, has_client), score))
+            }))
+            .collect();
+        scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        scored.into_iter().take(15).map(|(r, _)| r).collect()
+    };
+
+    if results.is_empty() && !session_name(query).is_empty() {
+        results.push(new_session_result(query));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sessions() {
+        let output = "main:3:1\nscratch pad:1:0\nbroken:x:0\n:2:0\n";
+        assert_eq!(
+            parse_sessions(output),
+            [
+                TmuxSession {
+                    name: "main".into(),
+                    windows: 3,
+                    attached: true,
+                },
+                TmuxSession {
+                    name: "scratch pad".into(),
+                    windows: 1,
+                    attached: false,
+                },
+            ]
+        );
+        // Several clients on one session
+        assert!(parse_sessions("work:2:2")[0].attached);
+    }
+
+    #[test]
+    fn parses_windows_with_separator_in_name() {
+        let output = "main:0:1:2:vim: src/main.rs\nmain:1:0:1:\nwork:12:0:1:a:b:c\n";
+        assert_eq!(
+            parse_windows(output),
+            [
+                TmuxWindow {
+                    session: "main".into(),
+                    index: 0,
+                    name: "vim: src/main.rs".into(),
+                    active: true,
+                    panes: 2,
+                },
+                TmuxWindow {
+                    session: "main".into(),
+                    index: 1,
+                    name: String::new(),
+                    active: false,
+                    panes: 1,
+                },
+                TmuxWindow {
+                    session: "work".into(),
+                    index: 12,
+                    name: "a:b:c".into(),
+                    active: false,
+                    panes: 1,
+                },
+            ]
+        );
+        assert_eq!(parse_windows("main:0:1:2:vim")[0].target(), "main:0");
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        assert!(parse_sessions("no server running on /tmp/tmux-501/default").is_empty());
+        assert!(parse_windows("main:zero:1:1:vim\nmain:0:1").is_empty());
+    }
+
+    #[test]
+    fn session_names_drop_separators() {
+        // A session created from this name never contains the field separator
+        assert_eq!(session_name(" api:v2.1 "), "api_v2_1");
+        assert_eq!(
+            parse_sessions(&format!("{}:1:0", session_name("a:b")))[0].name,
+            "a_b"
+        );
+    }
+}
@@ -0,0 +1,236 @@
+/// One line of a todo.txt file, see https://github.com/todotxt/todo.txt
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TodoItem {
+    pub done: bool,
+    pub priority: Option<char>,
+    pub completion_date: Option<String>,
+    pub creation_date: Option<String>,
+    pub text: String,          // Description, including contexts, projects and tags
+    pub contexts: Vec<String>, // Without the `@`
+    pub projects: Vec<String>, // Without the `+`
+    pub due: Option<String>,   // From a `due:YYYY-MM-DD` tag
+}
+
+fn is_date(word: &str) -> bool {
+    let bytes = word.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+fn is_priority(word: &str) -> Option<char> {
+    let mut chars = word.chars();
+    match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some('('), Some(p), Some(')'), None) if p.is_ascii_uppercase() => Some(p),
+        _ => None,
+    }
+}
+
+/// Splits off the first word if `accept` likes it
+fn take_word<'a, T>(rest: &mut &'a str, accept: impl Fn(&'a str) -> Option<T>) -> Option<T> {
+    let (word, after) = rest.split_once(' ').unwrap_or((rest, ""));
+    let value = accept(word)?;
+    *rest = after.trim_start();
+    Some(value)
+}
+
+pub fn parse_line(line: &str) -> Option<TodoItem> {
+    let mut rest = line.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut item = TodoItem {
+        done: take_word(&mut rest, |w| (w == "x").then_some(())).is_some(),
+        ..TodoItem::default()
+    };
+    let date = |w: &str| is_date(w).then(|| w.to_string());
+
+    if item.done {
+        // A completed task has its completion date first, then the creation date
+        item.completion_date = take_word(&mut rest, date);
+        if item.completion_date.is_some() {
+            item.creation_date = take_word(&mut rest, date);
+        }
+    } else {
+        item.priority = take_word(&mut rest, is_priority);
+        item.creation_date = take_word(&mut rest, date);
+    }
+    item.text = rest.to_string();
+
+    for word in rest.split_whitespace() {
+        if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+            item.contexts.push(context.to_string());
+        } else if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+            item.projects.push(project.to_string());
+        } else if let Some(due) = word.strip_prefix("due:").filter(|d| is_date(d)) {
+            item.due = Some(due.to_string());
+        } else if let Some(priority) = word.strip_prefix("pri:") {
+            // Kept by some clients when completing a task
+            if item.priority.is_none() && priority.len() == 1 {
+                item.priority = priority.chars().next().filter(|p| p.is_ascii_uppercase());
+            }
+        }
+    }
+
+    Some(item)
+}
+
+/// Completes a task line: `x <today>` goes in front and the priority becomes a
+/// `pri:` tag, as the spec suggests. Lines already done are returned as is.
+pub fn mark_done(line: &str, today: &str) -> String {
+    let line = line.trim();
+    let Some(item) = parse_line(line) else {
+        return line.to_string();
+    };
+    if item.done {
+        return line.to_string();
+    }
+
+    let mut rest = line;
+    let priority = take_word(&mut rest, is_priority);
+    let mut done = format!("x {} {}", today, rest);
+    if let Some(priority) = priority {
+        done.push_str(&format!(" pri:{}", priority));
+    }
+    done
+}
+
+/// A new task line with today's creation date
+pub fn new_task(text: &str, today: &str) -> String {
+    let text = text.trim();
+    let mut rest = text;
+    // Keep a priority typed up front in front of the date
+    match take_word(&mut rest, is_priority) {
+        Some(priority) => format!("({}) {} {}", priority, today, rest),
+        None => format!("{} {}", today, text),
+    }
+}
+
+/// A search like `call @phone +garage`: the tags have to be present, the rest
+/// is matched fuzzily
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TodoFilter {
+    pub contexts: Vec<String>,
+    pub projects: Vec<String>,
+    pub text: String,
+}
+
+impl TodoFilter {
+    pub fn parse(query: &str) -> Self {
+        let mut filter = TodoFilter::default();
+        let mut words = Vec::new();
+        for word in query.split_whitespace() {
+            if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+                filter.contexts.push(context.to_lowercase());
+            } else if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+                filter.projects.push(project.to_lowercase());
+            } else {
+                words.push(word);
+            }
+        }
+        filter.text = words.join(" ");
+        filter
+    }
+
+    /// Whether the item carries every context and project of the filter
+    pub fn matches_tags(&self, item: &TodoItem) -> bool {
+        let has = |wanted: &String, present: &[String]| {
+            present.iter().any(|p| p.to_lowercase() == *wanted)
+        };
+        self.contexts.iter().all(|c| has(c, &item.contexts))
+            && self.projects.iter().all(|p| has(p, &item.projects))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_open_task() {
+        let item = parse_line("(A) 2026-10-01 Call Mom @phone +family due:2026-10-20").unwrap();
+        assert_eq!(
+            item,
+            TodoItem {
+                done: false,
+                priority: Some('A'),
+                completion_date: None,
+                creation_date: Some("2026-10-01".into()),
+                text: "Call Mom @phone +family due:2026-10-20".into(),
+                contexts: vec!["phone".into()],
+                projects: vec!["family".into()],
+                due: Some("2026-10-20".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn priority_must_be_uppercase_and_first() {
+        assert_eq!(parse_line("(a) lowercase").unwrap().priority, None);
+        assert_eq!(parse_line("Really (A) later").unwrap().priority, None);
+        assert_eq!(parse_line("(AB) two letters").unwrap().priority, None);
+        assert_eq!(parse_line("(B) ok").unwrap().text, "ok");
+    }
+
+    #[test]
+    fn parses_completed_task_dates() {
+        let item = parse_line("x 2026-10-18 2026-10-01 Pay rent +home pri:B").unwrap();
+        assert!(item.done);
+        assert_eq!(item.completion_date.as_deref(), Some("2026-10-18"));
+        assert_eq!(item.creation_date.as_deref(), Some("2026-10-01"));
+        assert_eq!(item.priority, Some('B'));
+        assert_eq!(item.text, "Pay rent +home pri:B");
+
+        // Only a lone lowercase x marks completion
+        assert!(!parse_line("xylophone lessons").unwrap().done);
+        assert!(!parse_line("X marks the spot").unwrap().done);
+    }
+
+    #[test]
+    fn tags_need_a_name_and_valid_dates() {
+        let item = parse_line("email a@b.com @ + due:soon @work +proj").unwrap();
+        assert_eq!(item.contexts, ["work"]);
+        assert_eq!(item.projects, ["proj"]);
+        assert_eq!(item.due, None);
+        assert_eq!(
+            parse_line("2026-1-01 not a date").unwrap().creation_date,
+            None
+        );
+        assert_eq!(parse_line("   "), None);
+    }
+
+    #[test]
+    fn marks_done_moving_priority_to_tag() {
+        assert_eq!(
+            mark_done("(A) 2026-10-01 Call Mom @phone", "2026-10-18"),
+            "x 2026-10-18 2026-10-01 Call Mom @phone pri:A"
+        );
+        assert_eq!(mark_done("Buy milk", "2026-10-18"), "x 2026-10-18 Buy milk");
+        let done = "x 2026-10-17 Already";
+        assert_eq!(mark_done(done, "2026-10-18"), done);
+    }
+
+    #[test]
+    fn new_task_keeps_priority_in_front() {
+        assert_eq!(new_task(" Buy milk ", "2026-10-18"), "2026-10-18 Buy milk");
+        assert_eq!(
+            new_task("(C) Buy milk +home", "2026-10-18"),
+            "(C) 2026-10-18 Buy milk +home"
+        );
+    }
+
+    #[test]
+    fn filter_requires_every_tag() {
+        let filter = TodoFilter::parse("call @Phone +family");
+        assert_eq!(filter.text, "call");
+        assert_eq!(filter.contexts, ["phone"]);
+
+        let item = parse_line("Call Mom @phone +Family +errands").unwrap();
+        assert!(filter.matches_tags(&item));
+        assert!(!TodoFilter::parse("@work").matches_tags(&item));
+        assert!(TodoFilter::parse("anything").matches_tags(&item));
+    }
+}
@@ -0,0 +1,753 @@
+#[cfg(not(target_os = "macos"))]
+use crate::date_calc::Clock;
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::fs;
+use std::io::{self, Write};
+#[cfg(not(target_os = "macos"))]
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+// Implements https://specifications.freedesktop.org/trash-spec/latest/
+
+const INFO_SUFFIX: &str = ".trashinfo";
+
+/// A trashed file: its data under `files/` and its `.trashinfo` under `info/`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashItem {
+    pub info_path: PathBuf,
+    pub file_path: PathBuf,
+    pub original: PathBuf,
+    pub deleted: String, // YYYY-MM-DDThh:mm:ss, local time
+}
+
+fn uid() -> u32 {
+    unsafe { libc::getuid() }
+}
+
+/// `$XDG_DATA_HOME/Trash`, defaulting to ~/.local/share/Trash
+pub fn home_trash() -> PathBuf {
+    let data_home = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".local/share"));
+    data_home.join("Trash")
+}
+
+/// `$top/.Trash/$uid` may only be used when `.Trash` is a real directory
+/// with the sticky bit set
+fn shared_trash(top: &Path, uid: u32) -> Option<PathBuf> {
+    let shared = top.join(".Trash");
+    let metadata = fs::symlink_metadata(&shared).ok()?;
+    let sticky = metadata.permissions().mode() & 0o1000 != 0;
+    (metadata.is_dir() && sticky).then(|| shared.join(uid.to_string()))
+}
+
+/// The trash directories of a volume that exist, in the order the spec
+/// prefers them
+pub fn volume_trash_dirs(top: &Path, uid: u32) -> Vec<PathBuf> {
+    let own = top.join(format!(".Trash-{}", uid));
+    shared_trash(top, uid)
+        .into_iter()
+        .chain(std::iter::once(own))
+        .filter(|dir| dir.join("files").is_dir())
+        .collect()
+}
+
+/// Mounted volumes that can hold a trash directory
+fn mount_points() -> Vec<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        fs::read_dir("/Volumes")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .collect()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let mounts = fs::read_to_string("/proc/self/mounts").unwrap_or_default();
+        mounts
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            // Spaces and tabs are written as octal escapes
+            .map(|point| PathBuf::from(point.replace("\\040", " ").replace("\\011", "\t")))
+            .filter(|point| {
+                !["/proc", "/sys", "/dev", "/run"]
+                    .iter()
+                    .any(|pseudo| point.starts_with(pseudo))
+            })
+            .collect()
+    }
+}
+
+/// The home trash followed by every volume trash that exists
+pub fn trash_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![home_trash()];
+    for top in mount_points() {
+        for dir in volume_trash_dirs(&top, uid()) {
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+    dirs
+}
+
+/// Percent-encodes a path for the `Path=` key, keeping `/`
+pub fn encode_path(path: &str) -> String {
+    let mut out = String::new();
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+pub fn decode_path(encoded: &str) -> String {
+    let bytes = encoded.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// The decoded `Path` and the `DeletionDate` of a `.trashinfo` file
+pub fn parse_trashinfo(text: &str) -> Option<(String, String)> {
+    let mut in_section = false;
+    let mut path = None;
+    let mut deleted = String::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line == "[Trash Info]";
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        match line.split_once('=') {
+            Some(("Path", value)) => path = Some(decode_path(value)),
+            Some(("DeletionDate", value)) => deleted = value.to_string(),
+            _ => {}
+        }
+    }
+    path.filter(|p| !p.is_empty()).map(|p| (p, deleted))
+}
+
+pub fn format_trashinfo(path: &str, deleted: &str) -> String {
+    format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encode_path(path),
+        deleted
+    )
+}
+
+/// The directory relative `Path` values start from: the volume holding a
+/// volume trash, or `/` for the home trash
+fn top_dir(trash_dir: &Path) -> PathBuf {
+    let name = trash_dir.file_name().unwrap_or_default().to_string_lossy();
+    let parent = trash_dir.parent().unwrap_or(Path::new("/"));
+    if name.starts_with(".Trash-") {
+        parent.to_path_buf()
+    } else if parent.file_name().is_some_and(|n| n == ".Trash") {
+        parent.parent().unwrap_or(Path::new("/")).to_path_buf()
+    } else {
+        PathBuf::from("/")
+    }
+}
+
+/// Items of one trash directory, newest first. Info files without data are
+/// skipped, as the spec asks.
+pub fn list_trash(trash_dir: &Path) -> Vec<TrashItem> {
+    let top = top_dir(trash_dir);
+    let mut items: Vec<TrashItem> = fs::read_dir(trash_dir.join("info"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let info_path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let name = file_name.strip_suffix(INFO_SUFFIX)?;
+            let file_path = trash_dir.join("files").join(name);
+            fs::symlink_metadata(&file_path).ok()?;
+            let (path, deleted) = parse_trashinfo(&fs::read_to_string(&info_path).ok()?)?;
+            Some(TrashItem {
+                info_path,
+                file_path,
+                original: top.join(path),
+                deleted,
+            })
+        })
+        .collect();
+    items.sort_by(|a, b| b.deleted.cmp(&a.deleted));
+    items
+}
+
+/// Puts the item back where it came from; an existing file there is never
+/// replaced
+pub fn restore(item: &TrashItem) -> io::Result<()> {
+    if fs::symlink_metadata(&item.original).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", item.original.display()),
+        ));
+    }
+    if let Some(parent) = item.original.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::rename(&item.file_path, &item.original) {
+        // The original folder is on another volume than the trash
+        Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+            move_by_copy(&item.file_path, &item.original)?
+        }
+        result => result?,
+    }
+    fs::remove_file(&item.info_path)
+}
+
+/// Copies a file, symlink or folder tree, keeping permissions
+fn copy_any(from: &Path, to: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(from)?;
+    if metadata.file_type().is_symlink() {
+        std::os::unix::fs::symlink(fs::read_link(from)?, to)
+    } else if metadata.is_dir() {
+        fs::create_dir(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_any(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        fs::set_permissions(to, metadata.permissions())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+/// Moves across volumes, where `rename` cannot. A failed copy is undone so
+/// the item stays whole in the trash.
+fn move_by_copy(from: &Path, to: &Path) -> io::Result<()> {
+    if let Err(e) = copy_any(from, to) {
+        let _ = remove_any(to);
+        return Err(e);
+    }
+    remove_any(from)
+}
+
+fn remove_any(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Deletes the item for good, data first so a failure leaves it listed
+pub fn delete(item: &TrashItem) -> io::Result<()> {
+    remove_any(&item.file_path)?;
+    remove_any(&item.info_path)
+}
+
+/// Deletes everything in a trash directory, including leftovers without info
+pub fn empty(trash_dir: &Path) -> io::Result<()> {
+    for sub in ["files", "info"] {
+        for entry in fs::read_dir(trash_dir.join(sub)).into_iter().flatten() {
+            remove_any(&entry?.path())?;
+        }
+    }
+    // A cache of the sizes of trashed directories, gone with them
+    remove_any(&trash_dir.join("directorysizes"))
+}
+
+fn create_trash_dir(trash_dir: &Path) -> io::Result<()> {
+    for sub in ["files", "info"] {
+        let dir = trash_dir.join(sub);
+        fs::create_dir_all(&dir)?;
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))?;
+    }
+    fs::set_permissions(trash_dir, fs::Permissions::from_mode(0o700))
+}
+
+/// Moves `path` into `trash_dir`, recording it relative to `top` when given
+/// (volume trashes) and absolute otherwise. The info file is created first and
+/// exclusively, so two trashers never pick the same name.
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+pub fn move_to_trash_in(
+    path: &Path,
+    trash_dir: &Path,
+    top: Option<&Path>,
+    deleted: &str,
+) -> io::Result<TrashItem> {
+    create_trash_dir(trash_dir)?;
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "nothing to trash"))?
+        .to_string_lossy()
+        .to_string();
+    let recorded = match top.and_then(|top| path.strip_prefix(top).ok()) {
+        Some(relative) => relative.to_string_lossy().to_string(),
+        None => path.to_string_lossy().to_string(),
+    };
+
+    // `name`, then `name.2`, `name.3`, ... until both slots are free
+    let mut attempt = 1;
+    let (trashed_name, info_path, mut info_file) = loop {
+        let candidate = match attempt {
+            1 => name.clone(),
+            n => format!("{}.{}", name, n),
+        };
+        attempt += 1;
+        if fs::symlink_metadata(trash_dir.join("files").join(&candidate)).is_ok() {
+            continue;
+        }
+        let info_path = trash_dir
+            .join("info")
+            .join(format!("{}{}", candidate, INFO_SUFFIX));
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path);
+        match file {
+            Ok(file) => break (candidate, info_path, file),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    };
+
+    let file_path = trash_dir.join("files").join(&trashed_name);
+    let moved = info_file
+        .write_all(format_trashinfo(&recorded, deleted).as_bytes())
+        .and_then(|_| fs::rename(path, &file_path));
+    if let Err(e) = moved {
+        let _ = fs::remove_file(&info_path);
+        return Err(e);
+    }
+
+    Ok(TrashItem {
+        info_path,
+        file_path,
+        original: path.to_path_buf(),
+        deleted: deleted.to_string(),
+    })
+}
+
+/// Picks the trash on the same device as `path`: the home trash, or the
+/// volume's own, creating `$top/.Trash-$uid` when neither exists yet
+#[cfg(not(target_os = "macos"))]
+pub fn trash_dir_for(path: &Path) -> io::Result<(PathBuf, Option<PathBuf>)> {
+    let device = fs::symlink_metadata(path)?.dev();
+    let home_trash = home_trash();
+    let home_device = home_trash
+        .ancestors()
+        .find_map(|dir| fs::metadata(dir).ok())
+        .map(|metadata| metadata.dev());
+    if home_device == Some(device) {
+        return Ok((home_trash, None));
+    }
+
+    // The volume's top directory is the highest ancestor on the same device
+    let mut top = path.parent().unwrap_or(Path::new("/")).to_path_buf();
+    while let Some(parent) = top.parent() {
+        match fs::metadata(parent) {
+            Ok(metadata) if metadata.dev() == device => top = parent.to_path_buf(),
+            _ => break,
+        }
+    }
+    let trash_dir =
+        shared_trash(&top, uid()).unwrap_or_else(|| top.join(format!(".Trash-{}", uid())));
+    Ok((trash_dir, Some(top)))
+}
+
+/// Files `path` in the freedesktop trash and returns where it went
+#[cfg(not(target_os = "macos"))]
+pub fn move_to_trash(path: &Path) -> io::Result<PathBuf> {
+    let (trash_dir, top) = trash_dir_for(path)?;
+    move_to_trash_in(
+        path,
+        &trash_dir,
+        top.as_deref(),
+        &Clock::system().local_timestamp(),
+    )
+    .map(|item| item.file_path)
+}
+
+/// Hands `path` to the Finder's trash, which keeps its own record of where it
+/// came from for Put Back, and returns where it went
+#[cfg(target_os = "macos")]
+pub fn move_to_trash(path: &Path) -> io::Result<PathBuf> {
+    use cocoa::base::{id, nil, BOOL, NO};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe fn to_string(string: id) -> Option<String> {
+        if string == nil {
+            return None;
+        }
+        let cstr: *const std::os::raw::c_char = msg_send![string, UTF8String];
+        (!cstr.is_null()).then(|| std::ffi::CStr::from_ptr(cstr).to_string_lossy().to_string())
+    }
+
+    unsafe {
+        let path_string = NSString::alloc(nil).init_str(&path.to_string_lossy());
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: path_string];
+        let manager: id = msg_send![class!(NSFileManager), defaultManager];
+        let mut trashed: id = nil;
+        let mut error: id = nil;
+        let trashed_ptr: *mut id = &mut trashed;
+        let error_ptr: *mut id = &mut error;
+        let moved: BOOL = msg_send![manager, trashItemAtURL: url
+                                             resultingItemURL: trashed_ptr
+                                             error: error_ptr];
+        if moved == NO {
+            let description: id = if error == nil {
+                nil
+            } else {
+                msg_send![error, localizedDescription]
+            };
+            let message = to_string(description)
+                .unwrap_or_else(|| format!("could not trash {}", path.display()));
+            return Err(io::Error::other(message));
+        }
+        let trashed_path: id = if trashed == nil {
+            nil
+        } else {
+            msg_send![trashed, path]
+        };
+        Ok(to_string(trashed_path)
+            .map(PathBuf::from)
+            .unwrap_or_default())
+    }
+}
+
+/// Runs a trash action from a result; items are named by their info file
+pub fn apply_action(action: &ResultAction) -> io::Result<()> {
+    let item_for = |info_path: &str| {
+        let info_path = Path::new(info_path);
+        let trash_dir = info_path.parent().and_then(Path::parent);
+        trash_dir
+            .and_then(|dir| {
+                list_trash(dir)
+                    .into_iter()
+                    .find(|item| item.info_path == info_path)
+            })
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no longer in the trash"))
+    };
+
+    match action {
+        ResultAction::MoveToTrash(path) => move_to_trash(Path::new(path)).map(|_| ()),
+        ResultAction::RestoreTrash(info_path) => restore(&item_for(info_path)?),
+        ResultAction::DeleteTrash(info_path) => delete(&item_for(info_path)?),
+        ResultAction::EmptyTrash => trash_dirs().iter().try_for_each(|dir| empty(dir)),
+        _ => Ok(()),
+    }
+}
+
+fn item_to_result(item: &TrashItem) -> SearchResult {
+    let name = item
+        .original
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let from = item
+        .original
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    // Dates are written as 2024-05-01T13:45:10; the minutes are plenty
+    let deleted = item.deleted.replace('T', " ");
+    let deleted = deleted.get(..16).unwrap_or(&deleted);
+    let info_path = item.info_path.to_string_lossy().to_string();
+
+    SearchResult::new(
+        name.clone(),
+        item.file_path.to_string_lossy().to_string(),
+        SearchMode::Trash,
+    )
+    .with_action(ResultAction::RestoreTrash(info_path.clone()))
+    .with_hint(&format!("{} · {}", from, deleted))
+    .with_alt_action("Restore", ResultAction::RestoreTrash(info_path.clone()))
+    .with_alt_action(
+        "Delete permanently",
+        ResultAction::Confirm(
+            format!("Delete \"{}\" permanently", name),
+            Box::new(ResultAction::DeleteTrash(info_path)),
+        ),
+    )
+    .with_alt_action("Empty trash", empty_trash_action())
+}
+
+fn empty_trash_action() -> ResultAction {
+    ResultAction::Confirm(
+        "Delete everything in the trash".to_string(),
+        Box::new(ResultAction::EmptyTrash),
+    )
+}
+
+pub fn search_trash(query: &str) -> Vec<SearchResult> {
+    let items: Vec<TrashItem> = trash_dirs().iter().flat_map(|d| list_trash(d)).collect();
+
+    if query.is_empty() {
+        let mut results: Vec<SearchResult> = items.iter().take(15).map(item_to_result).collect();
+        if !items.is_empty() {
+            results.push(
+                SearchResult::new("Empty Trash".to_string(), String::new(), SearchMode::Trash)
+                    .with_action(empty_trash_action())
+                    .with_hint(&format!("{} items", items.len())),
+            );
+        }
+        return results;
+    }
+
+    let matcher = get_matcher();
+    let mut scored: Vec<(&TrashItem, i64)> = items
+        .iter()
+        .filter_map(|item| {
+            let path = item.original.to_string_lossy();
+            let name = item
+                .original
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            // The name counts more than the folder it was in
+            let name_score = matcher.fuzzy_match(&name, query).map(|s| s * 2);
+            let path_score = matcher.fuzzy_match(&path, query);
+            name_score.max(path_score).map(|score| (item, score))
+        })
+        .collect();
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(item, _)| item_to_result(item))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory per test, removed when the test is done
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("rufi-trash-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const DELETED: &str = "2026-10-18T09:30:00";
+
+    #[test]
+    fn trashinfo_round_trips_with_percent_encoding() {
+        let path = "/home/ada/My Notes/50% done #1 é.txt";
+        let info = format_trashinfo(path, DELETED);
+        assert_eq!(
+            info,
+            "[Trash Info]\nPath=/home/ada/My%20Notes/50%25%20done%20%231%20%C3%A9.txt\nDeletionDate=2026-10-18T09:30:00\n"
+        );
+        assert_eq!(
+            parse_trashinfo(&info),
+            Some((path.to_string(), DELETED.to_string()))
+        );
+    }
+
+    #[test]
+    fn trashinfo_needs_its_section_and_path() {
+        assert_eq!(parse_trashinfo("Path=/a\nDeletionDate=x\n"), None);
+        assert_eq!(parse_trashinfo("[Trash Info]\nDeletionDate=x\n"), None);
+        assert_eq!(
+            parse_trashinfo("[Other]\nPath=/b\n[Trash Info]\nPath=/a\n"),
+            Some(("/a".to_string(), String::new()))
+        );
+    }
+
+    #[test]
+    fn name_collisions_get_numbered() {
+        let temp = TempDir::new("collide");
+        let trash = temp.0.join("Trash");
+        let mut trashed = Vec::new();
+        for _ in 0..3 {
+            let file = temp.0.join("notes.txt");
+            fs::write(&file, "x").unwrap();
+            trashed.push(move_to_trash_in(&file, &trash, None, DELETED).unwrap());
+        }
+
+        let names: Vec<String> = trashed
+            .iter()
+            .map(|item| {
+                item.file_path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(names, ["notes.txt", "notes.txt.2", "notes.txt.3"]);
+        assert!(trash.join("info/notes.txt.3.trashinfo").is_file());
+        assert!(!temp.0.join("notes.txt").exists());
+        assert_eq!(list_trash(&trash).len(), 3);
+    }
+
+    #[test]
+    fn volume_trash_records_relative_paths() {
+        let temp = TempDir::new("volume");
+        fs::create_dir(temp.0.join("docs")).unwrap();
+        let file = temp.0.join("docs/report.pdf");
+        fs::write(&file, "x").unwrap();
+        let trash = temp.0.join(".Trash-1000");
+
+        let item = move_to_trash_in(&file, &trash, Some(&temp.0), DELETED).unwrap();
+        let info = fs::read_to_string(&item.info_path).unwrap();
+        assert!(info.contains("\nPath=docs/report.pdf\n"));
+        // Listing resolves it against the volume again
+        assert_eq!(list_trash(&trash)[0].original, file);
+    }
+
+    #[test]
+    fn restore_never_replaces_an_existing_file() {
+        let temp = TempDir::new("restore");
+        let trash = temp.0.join("Trash");
+        let file = temp.0.join("plan.md");
+        fs::write(&file, "old").unwrap();
+        let item = move_to_trash_in(&file, &trash, None, DELETED).unwrap();
+
+        fs::write(&file, "new").unwrap();
+        let error = restore(&item).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+        assert!(item.file_path.exists() && item.info_path.exists());
+
+        fs::remove_file(&file).unwrap();
+        restore(&item).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "old");
+        assert!(!item.info_path.exists());
+    }
+
+    #[test]
+    fn actions_report_failures() {
+        let temp = TempDir::new("actions");
+        let trash = temp.0.join("Trash");
+        let file = temp.0.join("plan.md");
+        fs::write(&file, "old").unwrap();
+        let item = move_to_trash_in(&file, &trash, None, DELETED).unwrap();
+        let info_path = item.info_path.to_string_lossy().to_string();
+
+        fs::write(&file, "new").unwrap();
+        let error = apply_action(&ResultAction::RestoreTrash(info_path.clone())).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+
+        fs::remove_file(&file).unwrap();
+        apply_action(&ResultAction::RestoreTrash(info_path.clone())).unwrap();
+        // Restored already, so it is gone from the trash
+        let error = apply_action(&ResultAction::DeleteTrash(info_path)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn moves_by_copy_keep_the_tree() {
+        let temp = TempDir::new("copy");
+        let from = temp.0.join("from");
+        fs::create_dir_all(from.join("bin")).unwrap();
+        fs::write(from.join("bin/run"), "#!/bin/sh\n").unwrap();
+        fs::set_permissions(from.join("bin/run"), fs::Permissions::from_mode(0o755)).unwrap();
+        std::os::unix::fs::symlink("bin/run", from.join("start")).unwrap();
+
+        let to = temp.0.join("to");
+        move_by_copy(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(
+            fs::read_to_string(to.join("bin/run")).unwrap(),
+            "#!/bin/sh\n"
+        );
+        let mode = fs::metadata(to.join("bin/run"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+        assert_eq!(
+            fs::read_link(to.join("start")).unwrap(),
+            Path::new("bin/run")
+        );
+
+        // A failed copy leaves the source alone and nothing half-copied
+        fs::create_dir(&from).unwrap();
+        let error = move_by_copy(&from, &temp.0.join("missing/to")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(from.exists());
+    }
+
+    #[test]
+    fn empty_removes_everything_and_directorysizes() {
+        let temp = TempDir::new("empty");
+        let trash = temp.0.join("Trash");
+        let dir = temp.0.join("build");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("nested/out.o"), "x").unwrap();
+        move_to_trash_in(&dir, &trash, None, DELETED).unwrap();
+        // A leftover without info, and the size cache
+        fs::write(trash.join("files/orphan"), "x").unwrap();
+        fs::write(trash.join("directorysizes"), "4096 0 build\n").unwrap();
+
+        empty(&trash).unwrap();
+        assert_eq!(fs::read_dir(trash.join("files")).unwrap().count(), 0);
+        assert_eq!(fs::read_dir(trash.join("info")).unwrap().count(), 0);
+        assert!(!trash.join("directorysizes").exists());
+    }
+
+    #[test]
+    fn items_without_data_are_not_listed() {
+        let temp = TempDir::new("stale");
+        let trash = temp.0.join("Trash");
+        create_trash_dir(&trash).unwrap();
+        fs::write(
+            trash.join("info/gone.trashinfo"),
+            format_trashinfo("/tmp/gone", DELETED),
+        )
+        .unwrap();
+        assert!(list_trash(&trash).is_empty());
+    }
+}
@@ -1,11 +1,26 @@
 use crate::{
-    app_search::{fuzzy_search, Application},
+    app_search::Application,
+    clipboard::system_clipboard,
+    clipboard_history::apply_action as apply_clipboard_action,
     config::Config,
-    file_search::{search_files, search_files_random},
-    search_mode::{SearchMode, SearchResult},
-    system_commands::search_commands,
+    frecency::record_use,
+    grid::GridLayout,
+    keymap::{custom_command, Action, KeyChord, Keymap},
+    modes::{self, cycle_mode, detect_mode, prefix_hints},
+    notes::apply_action as apply_note_action,
+    pass::{copy_field as copy_pass_field, copy_otp, copy_password},
+    processes::send_signal,
+    providers,
+    search_mode::{ResultAction, SearchMode, SearchResult},
+    snippets::{expand_snippet, prompt_result, SnippetPrompt},
+    ssh_hosts::frecency_key as ssh_frecency_key,
+    template::inputs,
+    terminal::{open_in_editor, open_terminal_at, run_in_terminal},
+    trash::apply_action as apply_trash_action,
+    window_switcher::focus_window,
+    workspaces::frecency_key as workspace_frecency_key,
 };
-use cocoa::appkit::{NSApp, NSTextField};
+use cocoa::appkit::{NSApp, NSEvent, NSTextField};
 use cocoa::base::{id, nil, NO, YES};
 use cocoa::foundation::{NSPoint, NSRect, NSSize, NSString};
 use core_graphics::geometry::CGSize;
@@ -13,6 +28,7 @@ use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
 use objc::{class, msg_send, sel, sel_impl};
 use std::collections::HashMap;
+use std::io;
 use std::sync::{Arc, Mutex, Once};
 
 static DELEGATE_CLASS_INIT: Once = Once::new();
@@ -24,6 +40,12 @@ const CELL_WIDTH: f64 = 116.0;
 const CELL_HEIGHT: f64 = 116.0;
 const ICON_SIZE: f64 = 64.0;
 const CELL_SPACING: f64 = 12.0;
+const HEADER_HEIGHT: f64 = 24.0;
+
+// Finds a cell's name label when the selection moves, and its quick-select
+// number when the grid scrolls
+const NAME_LABEL_TAG: isize = 1;
+const QUICK_NUMBER_TAG: isize = 2;
 
 // Global config storage for hover callbacks
 static CONFIG_DATA: Mutex<Option<Config>> = Mutex::new(None);
@@ -34,22 +56,27 @@ struct SendId(id);
 unsafe impl Send for SendId {}
 
 // Global storage for delegate data
+#[derive(Clone)]
 struct DelegateData {
     results_view: SendId,
     apps: Arc<Mutex<Vec<Application>>>,
     filtered: Arc<Mutex<Vec<SearchResult>>>, // Currently filtered/displayed results
     selected_index: Arc<Mutex<usize>>,       // Currently selected item index
     search_mode: Arc<Mutex<SearchMode>>,     // Current search mode
-    _search_field: SendId,                   // Reference to search field for refreshing
+    locked_mode: Arc<Mutex<Option<SearchMode>>>, // Mode picked by cycling or --mode; prefixes are not read while set
+    search_field: SendId,                    // Reference to search field for refreshing
+    action_menu: Arc<Mutex<bool>>,           // Whether the grid shows a result's secondary actions
+    snippet_prompt: Arc<Mutex<Option<SnippetPrompt>>>, // Snippet asking for its inputs
     _pill_buttons: Vec<SendId>,              // References to the 3 pill buttons
     config: Config,                          // Configuration for colors and fonts
     prompt_label: SendId,                    // Reference to prompt label for mode switching
     mode_badge: SendId,                      // Reference to mode badge label
+    keymap: Keymap,                          // Key chords and the actions they run
 }
 
 static DELEGATE_DATA: Mutex<Option<HashMap<usize, DelegateData>>> = Mutex::new(None);
 
-// Create a text field delegate that handles text changes and key commands
+// Create a text field delegate that refreshes the results as the query changes
 fn create_text_field_delegate_class() -> *const Class {
     unsafe {
         DELEGATE_CLASS_INIT.call_once(|| {
@@ -65,345 +92,32 @@ fn create_text_field_delegate_class() -> *const Class {
                     let delegate_ptr = delegate as usize;
 
                     // Get delegate data from global storage
-                    let mut data_map = DELEGATE_DATA.lock().unwrap();
-                    let data = match data_map.as_mut().and_then(|m| m.get(&delegate_ptr)) {
+                    let data_map = DELEGATE_DATA.lock().unwrap();
+                    let data = match data_map.as_ref().and_then(|m| m.get(&delegate_ptr)) {
                         Some(d) => d,
                         None => return,
                     };
 
-                    let text: id = msg_send![text_field, stringValue];
-                    let query_cstr: *const i8 = msg_send![text, UTF8String];
-                    let raw_query = std::ffi::CStr::from_ptr(query_cstr).to_string_lossy();
-
-                    // Detect mode from prefix and strip it
-                    let (mode, query) = if raw_query.starts_with('/') {
-                        (SearchMode::Files, raw_query[1..].to_string())
-                    } else if raw_query.starts_with(':') {
-                        (SearchMode::Run, raw_query[1..].to_string())
-                    } else {
-                        (SearchMode::Apps, raw_query.to_string())
-                    };
-
-                    // Update mode and UI indicators
-                    *data.search_mode.lock().unwrap() = mode;
-                    update_mode_ui(mode, data.prompt_label.0, data.mode_badge.0);
-
-                    // Filter based on mode
-                    let filtered: Vec<SearchResult> = match mode {
-                        SearchMode::Apps => {
-                            if query.is_empty() {
-                                // Show first 15 apps when empty (already sorted alphabetically)
-                                let apps = data.apps.lock().unwrap();
-                                apps.iter()
-                                    .take(15)
-                                    .map(|app| {
-                                        SearchResult::new(
-                                            app.name.clone(),
-                                            app.path.clone(),
-                                            SearchMode::Apps,
-                                        )
-                                    })
-                                    .collect()
-                            } else {
-                                fuzzy_search(&data.apps.lock().unwrap(), &query)
-                                    .into_iter()
-                                    .take(8)
-                                    .map(|app| {
-                                        SearchResult::new(app.name, app.path, SearchMode::Apps)
-                                    })
-                                    .collect()
-                            }
-                        }
-                        SearchMode::Files => {
-                            if query.is_empty() {
-                                // Show 4 random files when empty
-                                search_files_random(4)
-                            } else {
-                                search_files(&query)
-                            }
-                        }
-                        SearchMode::Run => search_commands(&query),
-                    };
-
-                    // Store filtered results and reset selection to first item
-                    *data.filtered.lock().unwrap() = filtered.clone();
-                    *data.selected_index.lock().unwrap() = 0;
-
-                    // Rebuild the results view
-                    let results_view = data.results_view.0;
-                    let config = data.config.clone();
-                    rebuild_results_grid(results_view, &filtered, 0, &config);
+                    refresh_results(data);
                 }
             }
 
-            // Handle command keys (Escape, Enter)
-            extern "C" fn control_text_view_do_command_by_selector(
-                _this: &Object,
-                _: Sel,
-                control: id,
-                _text_view: id,
-                command_selector: Sel,
-            ) -> u8 {
+            // Renumber the cells in view as the grid scrolls
+            extern "C" fn grid_did_scroll(this: &Object, _: Sel, _notification: id) {
                 unsafe {
-                    extern "C" {
-                        fn sel_getName(sel: Sel) -> *const i8;
-                    }
-
-                    let sel_name = sel_getName(command_selector);
-                    let sel_str = std::ffi::CStr::from_ptr(sel_name).to_string_lossy();
-
-                    // Escape key triggers "cancelOperation:"
-                    if sel_str == "cancelOperation:" {
-                        let app = NSApp();
-                        let _: () = msg_send![app, terminate: nil];
-                        return YES as u8;
-                    }
-
-                    // Enter/Return triggers "insertNewline:"
-                    if sel_str == "insertNewline:" {
-                        // Get delegate data
-                        let delegate: id = msg_send![control, delegate];
-                        let delegate_ptr = delegate as usize;
-
-                        let data_map = DELEGATE_DATA.lock().unwrap();
-                        if let Some(data) = data_map.as_ref().and_then(|m| m.get(&delegate_ptr)) {
-                            let filtered = data.filtered.lock().unwrap();
-                            let selected_idx = *data.selected_index.lock().unwrap();
-                            if let Some(result) = filtered.get(selected_idx) {
-                                match result.result_type {
-                                    SearchMode::Apps | SearchMode::Files => {
-                                        // Launch application or open file using NSWorkspace
-                                        let workspace_class = class!(NSWorkspace);
-                                        let workspace: id =
-                                            msg_send![workspace_class, sharedWorkspace];
-                                        let path_string =
-                                            NSString::alloc(nil).init_str(&result.path);
-
-                                        // Use launchApplication for apps, openFile for other files
-                                        if result.result_type == SearchMode::Apps {
-                                            let _: bool = msg_send![workspace, launchApplication: path_string];
-                                        } else {
-                                            let url_class = class!(NSURL);
-                                            let url: id = msg_send![url_class, fileURLWithPath: path_string];
-                                            let _: bool = msg_send![workspace, openURL: url];
-                                        }
-                                    }
-                                    SearchMode::Run => {
-                                        // Execute system command
-                                        std::process::Command::new("sh")
-                                            .arg("-c")
-                                            .arg(&result.path) // path contains the command
-                                            .spawn()
-                                            .ok();
-                                    }
-                                }
-
-                                // Exit after launching
-                                let app = NSApp();
-                                let _: () = msg_send![app, terminate: nil];
-                            }
-                        }
-
-                        return YES as u8;
-                    }
-
-                    // Arrow Down triggers "moveDown:" - move to next row (5 items)
-                    if sel_str == "moveDown:" {
-                        let delegate: id = msg_send![control, delegate];
-                        let delegate_ptr = delegate as usize;
-
-                        let mut data_map = DELEGATE_DATA.lock().unwrap();
-                        if let Some(data) = data_map.as_mut().and_then(|m| m.get_mut(&delegate_ptr))
-                        {
-                            let grid_cols: usize = GRID_COLUMNS as usize;
-                            let filtered_count = data.filtered.lock().unwrap().len();
-                            let mut selected_idx = data.selected_index.lock().unwrap();
-                            let new_idx = *selected_idx + grid_cols;
-                            // Wrap to top row (same column) if at bottom
-                            if new_idx < filtered_count {
-                                *selected_idx = new_idx;
-                            } else {
-                                // Wrap: go to same column in first row
-                                *selected_idx = *selected_idx % grid_cols;
-                            }
-                            drop(selected_idx);
-
-                            let results_view = data.results_view.0;
-                            let filtered = data.filtered.lock().unwrap().clone();
-                            let selected_index = *data.selected_index.lock().unwrap();
-                            let config = data.config.clone();
-                            drop(data_map);
-
-                            rebuild_results_grid(results_view, &filtered, selected_index, &config);
-                        }
-                        return YES as u8;
-                    }
-
-                    // Arrow Up triggers "moveUp:" - move to previous row (5 items)
-                    if sel_str == "moveUp:" {
-                        let delegate: id = msg_send![control, delegate];
-                        let delegate_ptr = delegate as usize;
-
-                        let mut data_map = DELEGATE_DATA.lock().unwrap();
-                        if let Some(data) = data_map.as_mut().and_then(|m| m.get_mut(&delegate_ptr))
-                        {
-                            let grid_cols: usize = GRID_COLUMNS as usize;
-                            let filtered_count = data.filtered.lock().unwrap().len();
-                            let mut selected_idx = data.selected_index.lock().unwrap();
-                            if *selected_idx >= grid_cols {
-                                *selected_idx -= grid_cols;
-                            } else {
-                                // Wrap: go to same column in last row
-                                let last_row_start = (filtered_count / grid_cols) * grid_cols;
-                                let target = last_row_start + (*selected_idx % grid_cols);
-                                *selected_idx = target.min(filtered_count.saturating_sub(1));
-                            }
-                            drop(selected_idx);
-
-                            let results_view = data.results_view.0;
-                            let filtered = data.filtered.lock().unwrap().clone();
-                            let selected_index = *data.selected_index.lock().unwrap();
-                            let config = data.config.clone();
-                            drop(data_map);
-
-                            rebuild_results_grid(results_view, &filtered, selected_index, &config);
-                        }
-                        return YES as u8;
-                    }
-
-                    // Arrow Right triggers "moveRight:" - move to next item
-                    if sel_str == "moveRight:" {
-                        let delegate: id = msg_send![control, delegate];
-                        let delegate_ptr = delegate as usize;
-
-                        let mut data_map = DELEGATE_DATA.lock().unwrap();
-                        if let Some(data) = data_map.as_mut().and_then(|m| m.get_mut(&delegate_ptr))
-                        {
-                            let filtered_count = data.filtered.lock().unwrap().len();
-                            let mut selected_idx = data.selected_index.lock().unwrap();
-                            if *selected_idx < filtered_count.saturating_sub(1) {
-                                *selected_idx += 1;
-                            } else {
-                                // Wrap to first item
-                                *selected_idx = 0;
-                            }
-                            let new_selected = *selected_idx;
-                            drop(selected_idx);
-
-                            // Update cell backgrounds for visual selection
-                            let results_view = data.results_view.0;
-                            let selection_bg =
-                                Config::hex_to_nscolor(&data.config.colors.selection_background);
-                            let selection_text =
-                                Config::hex_to_nscolor(&data.config.colors.selection_text);
-                            let normal_text = Config::hex_to_nscolor(&data.config.colors.text);
-                            let clear_color: id = msg_send![class!(NSColor), clearColor];
-
-                            let subviews: id = msg_send![results_view, subviews];
-                            let count: usize = msg_send![subviews, count];
-                            for i in 0..count {
-                                let cell_view: id = msg_send![subviews, objectAtIndex: i];
-                                let layer: id = msg_send![cell_view, layer];
-                                if layer != nil {
-                                    let row_idx: isize =
-                                        *(&*cell_view as &Object).get_ivar::<isize>("rowIndex");
-                                    if row_idx == new_selected as isize {
-                                        let cg_color: id = msg_send![selection_bg, CGColor];
-                                        let _: () = msg_send![layer, setBackgroundColor: cg_color];
-                                    } else {
-                                        let cg_color: id = msg_send![clear_color, CGColor];
-                                        let _: () = msg_send![layer, setBackgroundColor: cg_color];
-                                    }
-                                    // Update label text color
-                                    let cell_subviews: id = msg_send![cell_view, subviews];
-                                    let cell_subview_count: usize = msg_send![cell_subviews, count];
-                                    for j in 0..cell_subview_count {
-                                        let subview: id = msg_send![cell_subviews, objectAtIndex: j];
-                                        let class_name: id = msg_send![subview, className];
-                                        let cstr: *const i8 = msg_send![class_name, UTF8String];
-                                        let name = std::ffi::CStr::from_ptr(cstr).to_string_lossy();
-                                        if name == "NSTextField" {
-                                            let text_color = if row_idx == new_selected as isize {
-                                                selection_text
-                                            } else {
-                                                normal_text
-                                            };
-                                            let _: () = msg_send![subview, setTextColor: text_color];
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        return YES as u8;
-                    }
-
-                    // Arrow Left triggers "moveLeft:" - move to previous item
-                    if sel_str == "moveLeft:" {
-                        let delegate: id = msg_send![control, delegate];
-                        let delegate_ptr = delegate as usize;
-
-                        let mut data_map = DELEGATE_DATA.lock().unwrap();
-                        if let Some(data) = data_map.as_mut().and_then(|m| m.get_mut(&delegate_ptr))
-                        {
-                            let filtered_count = data.filtered.lock().unwrap().len();
-                            let mut selected_idx = data.selected_index.lock().unwrap();
-                            if *selected_idx > 0 {
-                                *selected_idx -= 1;
-                            } else {
-                                // Wrap to last item
-                                *selected_idx = filtered_count.saturating_sub(1);
-                            }
-                            let new_selected = *selected_idx;
-                            drop(selected_idx);
-
-                            // Update cell backgrounds for visual selection
-                            let results_view = data.results_view.0;
-                            let selection_bg =
-                                Config::hex_to_nscolor(&data.config.colors.selection_background);
-                            let selection_text =
-                                Config::hex_to_nscolor(&data.config.colors.selection_text);
-                            let normal_text = Config::hex_to_nscolor(&data.config.colors.text);
-                            let clear_color: id = msg_send![class!(NSColor), clearColor];
-
-                            let subviews: id = msg_send![results_view, subviews];
-                            let count: usize = msg_send![subviews, count];
-                            for i in 0..count {
-                                let cell_view: id = msg_send![subviews, objectAtIndex: i];
-                                let layer: id = msg_send![cell_view, layer];
-                                if layer != nil {
-                                    let row_idx: isize =
-                                        *(&*cell_view as &Object).get_ivar::<isize>("rowIndex");
-                                    if row_idx == new_selected as isize {
-                                        let cg_color: id = msg_send![selection_bg, CGColor];
-                                        let _: () = msg_send![layer, setBackgroundColor: cg_color];
-                                    } else {
-                                        let cg_color: id = msg_send![clear_color, CGColor];
-                                        let _: () = msg_send![layer, setBackgroundColor: cg_color];
-                                    }
-                                    // Update label text color
-                                    let cell_subviews: id = msg_send![cell_view, subviews];
-                                    let cell_subview_count: usize = msg_send![cell_subviews, count];
-                                    for j in 0..cell_subview_count {
-                                        let subview: id = msg_send![cell_subviews, objectAtIndex: j];
-                                        let class_name: id = msg_send![subview, className];
-                                        let cstr: *const i8 = msg_send![class_name, UTF8String];
-                                        let name = std::ffi::CStr::from_ptr(cstr).to_string_lossy();
-                                        if name == "NSTextField" {
-                                            let text_color = if row_idx == new_selected as isize {
-                                                selection_text
-                                            } else {
-                                                normal_text
-                                            };
-                                            let _: () = msg_send![subview, setTextColor: text_color];
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        return YES as u8;
-                    }
+                    // Scrolling during a rebuild already holds the data, and
+                    // the rebuild renumbers the cells itself
+                    let Ok(data_map) = DELEGATE_DATA.try_lock() else {
+                        return;
+                    };
+                    let delegate_ptr = this as *const Object as usize;
+                    let data = match data_map.as_ref().and_then(|m| m.get(&delegate_ptr)) {
+                        Some(d) => d,
+                        None => return,
+                    };
 
-                    NO as u8
+                    let layout = GridLayout::new(&data.filtered.lock().unwrap(), GRID_COLUMNS as usize);
+                    number_visible_cells(data.results_view.0, &layout);
                 }
             }
 
@@ -412,11 +126,9 @@ fn create_text_field_delegate_class() -> *const Class {
                     sel!(controlTextDidChange:),
                     control_text_did_change as extern "C" fn(&Object, Sel, id),
                 );
-
                 decl.add_method(
-                    sel!(control:textView:doCommandBySelector:),
-                    control_text_view_do_command_by_selector
-                        as extern "C" fn(&Object, Sel, id, id, Sel) -> u8,
+                    sel!(gridDidScroll:),
+                    grid_did_scroll as extern "C" fn(&Object, Sel, id),
                 );
             }
 
@@ -441,6 +153,10 @@ fn create_row_view_class() -> *const Class {
             extern "C" fn mouse_entered(this: &mut Object, _: Sel, _event: id) {
                 unsafe {
                     let row_index: isize = *this.get_ivar("rowIndex");
+                    // Section headers are not results
+                    if row_index < 0 {
+                        return;
+                    }
 
                     // Get colors from global config
                     let config_guard = CONFIG_DATA.lock().unwrap();
@@ -599,30 +315,9 @@ extern "C" fn mouse_down(this: &mut Object, _: Sel, _event: id) {
 
         let data_map = DELEGATE_DATA.lock().unwrap();
         if let Some(data) = data_map.as_ref().and_then(|m| m.get(&delegate_ptr)) {
-            let filtered = data.filtered.lock().unwrap();
-            if let Some(result) = filtered.get(row_index as usize) {
-                match result.result_type {
-                    SearchMode::Apps | SearchMode::Files => {
-                        let workspace_class = class!(NSWorkspace);
-                        let workspace: id = msg_send![workspace_class, sharedWorkspace];
-
-                        let path_string = NSString::alloc(nil).init_str(&result.path);
-                        let url: id = msg_send![class!(NSURL), fileURLWithPath: path_string];
-
-                        let _: bool = msg_send![workspace, openURL: url];
-                    }
-                    SearchMode::Run => {
-                        std::process::Command::new("sh")
-                            .arg("-c")
-                            .arg(&result.path)
-                            .spawn()
-                            .ok();
-                    }
-                }
-
-                // Exit after launching
-                let app = NSApp();
-                let _: () = msg_send![app, terminate: nil];
+            let clicked = data.filtered.lock().unwrap().get(row_index as usize).cloned();
+            if let Some(result) = clicked {
+                run_result(data, &result);
             }
         }
     }
@@ -680,13 +375,85 @@ extern "C" fn mouse_down(this: &mut Object, _: Sel, _event: id) {
     }
 }
 
+unsafe fn search_text(data: &DelegateData) -> String {
+    let text: id = msg_send![data.search_field.0, stringValue];
+    let query_cstr: *const i8 = msg_send![text, UTF8String];
+    std::ffi::CStr::from_ptr(query_cstr).to_string_lossy().to_string()
+}
+
+/// Runs the search field's current query and redraws the results
+unsafe fn refresh_results(data: &DelegateData) {
+    let raw_query = search_text(data);
+
+    // While a snippet asks for an input, the whole field is the value
+    let prompt = data.snippet_prompt.lock().unwrap().clone();
+    if let Some(prompt) = prompt {
+        let filtered: Vec<SearchResult> = prompt_result(&prompt, &raw_query).into_iter().collect();
+        *data.search_mode.lock().unwrap() = SearchMode::Snippets;
+        *data.action_menu.lock().unwrap() = false;
+        update_mode_ui(SearchMode::Snippets, &data.config, data.prompt_label.0, data.mode_badge.0);
+        *data.filtered.lock().unwrap() = filtered.clone();
+        *data.selected_index.lock().unwrap() = 0;
+        rebuild_results_grid(data.results_view.0, &filtered, 0, &data.config);
+        return;
+    }
+
+    // A locked mode takes the query as typed, otherwise the prefix decides
+    let locked = *data.locked_mode.lock().unwrap();
+    let (mode, query) = match locked {
+        Some(mode) => (mode, raw_query.clone()),
+        None => detect_mode(&raw_query, &data.config),
+    };
+
+    // Update mode and UI indicators; a fresh query always leaves the action menu
+    *data.search_mode.lock().unwrap() = mode;
+    *data.action_menu.lock().unwrap() = false;
+    update_mode_ui(mode, &data.config, data.prompt_label.0, data.mode_badge.0);
+
+    // Filter based on mode
+    let filtered = providers::search(mode, &query, &data.config, &data.apps.lock().unwrap());
+
+    // Store filtered results and reset selection to first item
+    *data.filtered.lock().unwrap() = filtered.clone();
+    *data.selected_index.lock().unwrap() = 0;
+
+    // Rebuild the results view
+    let results_view = data.results_view.0;
+    let config = data.config.clone();
+    rebuild_results_grid(results_view, &filtered, 0, &config);
+}
+
 /// Updates the prompt label and mode badge based on current search mode
-unsafe fn update_mode_ui(mode: SearchMode, prompt_label: id, mode_badge: id) {
-    let (prompt_char, badge_text, color_hex) = match mode {
-        SearchMode::Apps => (">", "[apps]", "#d65d0e"),   // Orange
-        SearchMode::Files => ("/", "[files]", "#458588"), // Blue
-        SearchMode::Run => (":", "[run]", "#98971a"),     // Green
+unsafe fn update_mode_ui(mode: SearchMode, config: &Config, prompt_label: id, mode_badge: id) {
+    let color_hex = match mode {
+        SearchMode::Apps => "#d65d0e",          // Orange
+        SearchMode::Files => "#458588",         // Blue
+        SearchMode::Run => "#98971a",           // Green
+        SearchMode::Calc => "#b16286",          // Purple
+        SearchMode::Date => "#689d6a",          // Aqua
+        SearchMode::Clipboard => "#d79921",     // Yellow
+        SearchMode::Emoji => "#fe8019",         // Bright orange
+        SearchMode::Ssh => "#83a598",           // Bright blue
+        SearchMode::Projects => "#b8bb26",      // Bright green
+        SearchMode::Workspaces => "#d3869b",    // Bright purple
+        SearchMode::Bookmarks => "#fabd2f",     // Bright yellow
+        SearchMode::Web => "#8ec07c",           // Bright aqua
+        SearchMode::Processes => "#cc241d",     // Red
+        SearchMode::Tmux => "#a89984",          // Gray
+        SearchMode::Man => "#928374",           // Dark gray
+        SearchMode::Snippets => "#b57614",      // Dark yellow
+        SearchMode::Notes => "#79740e",         // Dark green
+        SearchMode::Contacts => "#427b58",      // Dark aqua
+        SearchMode::Pass => "#af3a03",          // Dark orange
+        SearchMode::Trash => "#9d0006",         // Dark red
+        SearchMode::Windows => "#076678",       // Dark blue
+        SearchMode::Combi => "#ebdbb2",         // Light
+    };
+    let prompt_char = match modes::prefix(mode, config) {
+        "" => ">",
+        prefix => prefix,
     };
+    let badge_text = format!("[{}]", mode.label());
 
     // Update prompt symbol and color
     let prompt_str = NSString::alloc(nil).init_str(prompt_char);
@@ -695,12 +462,658 @@ unsafe fn update_mode_ui(mode: SearchMode, prompt_label: id, mode_badge: id) {
     let _: () = msg_send![prompt_label, setTextColor: prompt_color];
 
     // Update mode badge
-    let badge_str = NSString::alloc(nil).init_str(badge_text);
+    let badge_str = NSString::alloc(nil).init_str(&badge_text);
     let _: () = msg_send![mode_badge, setStringValue: badge_str];
     let badge_color: id = msg_send![prompt_color, colorWithAlphaComponent: 0.6f64];
     let _: () = msg_send![mode_badge, setTextColor: badge_color];
 }
 
+/// Activates a result, then closes the launcher or refreshes the grid. Actions
+/// that need confirmation show a one-entry menu first; Escape backs out.
+unsafe fn run_result(data: &DelegateData, result: &SearchResult) {
+    if let ResultAction::Confirm(prompt, action) = &result.action {
+        let mut confirm = SearchResult::new(
+            format!("{}?", prompt),
+            result.path.clone(),
+            result.result_type,
+        )
+        .with_action((**action).clone());
+        confirm.hint = Some("enter · confirm   esc · cancel".to_string());
+        show_menu(data, vec![confirm]);
+        return;
+    }
+
+    // Snippets with `{input:...}` fields ask for one value at a time
+    if let ResultAction::ExpandSnippet(template, paste) = &result.action {
+        if !inputs(template).is_empty() {
+            let mut prompt = data.snippet_prompt.lock().unwrap();
+            let name = prompt
+                .as_ref()
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| result.name.clone());
+            *prompt = Some(SnippetPrompt {
+                name,
+                template: template.clone(),
+                paste: *paste,
+            });
+            drop(prompt);
+            set_search_text(data, "");
+            refresh_results(data);
+            return;
+        }
+    }
+
+    // Modes ranked by frecency remember what was picked, but not the entries
+    // of an action menu, which are alternatives rather than a pick
+    let in_menu = *data.action_menu.lock().unwrap();
+    if let Some(key) = frecency_key(result).filter(|_| !in_menu) {
+        record_use(&key);
+    }
+
+    match activate_result(result) {
+        Ok(true) => {
+            // Exit after launching
+            let app = NSApp();
+            let _: () = msg_send![app, terminate: nil];
+        }
+        Ok(false) => refresh_results(data),
+        Err(error) => {
+            // Nothing happened; say so instead of closing
+            let mut notice = result.clone();
+            notice.name = failure_notice(&result.action, &error);
+            notice.hint = Some("enter · retry   esc · back".to_string());
+            notice.alt_actions.clear();
+            show_menu(data, vec![notice]);
+        }
+    }
+}
+
+/// What went wrong, shown in place of the result whose action failed
+fn failure_notice(action: &ResultAction, error: &io::Error) -> String {
+    let what = match action {
+        ResultAction::Kill(pid, _) => format!("Could not signal {}", pid),
+        ResultAction::MoveToTrash(_) => "Could not move to the trash".to_string(),
+        ResultAction::RestoreTrash(_) => "Could not restore".to_string(),
+        ResultAction::DeleteTrash(_) | ResultAction::EmptyTrash => "Could not delete".to_string(),
+        _ => "Could not copy".to_string(),
+    };
+    format!("{}: {}", what, error)
+}
+
+/// Replaces the search field's text without going through the delegate
+unsafe fn set_search_text(data: &DelegateData, text: &str) {
+    let text = NSString::alloc(nil).init_str(text);
+    let _: () = msg_send![data.search_field.0, setStringValue: text];
+}
+
+/// Switches to `mode` with `query` in the search field. Prefixes stop being
+/// read until the query alone would pick the mode again.
+unsafe fn lock_mode(data: &DelegateData, mode: SearchMode, query: &str) {
+    let unlocked = detect_mode(query, &data.config).0 == mode;
+    *data.locked_mode.lock().unwrap() = (!unlocked).then_some(mode);
+    set_search_text(data, query);
+    refresh_results(data);
+}
+
+/// Shows `mode` with `query` behind the mode's prefix, or locks the mode when
+/// one is locked already or it has no prefix
+unsafe fn show_mode(data: &DelegateData, mode: SearchMode, query: &str) {
+    let prefix = modes::prefix(mode, &data.config);
+    let locked = data.locked_mode.lock().unwrap().is_some();
+    if locked || prefix.is_empty() {
+        lock_mode(data, mode, query);
+    } else {
+        set_search_text(data, &format!("{}{}", prefix, query));
+        refresh_results(data);
+    }
+}
+
+/// Runs the action bound to a key press in the launcher window. Keys that are
+/// not bound, or are being composed by an input method, are left to the
+/// search field.
+pub(crate) unsafe fn handle_key_down(window: id, event: id) -> bool {
+    let characters = event.charactersIgnoringModifiers();
+    let cstr: *const i8 = if characters != nil {
+        msg_send![characters, UTF8String]
+    } else {
+        std::ptr::null()
+    };
+    let characters = if cstr.is_null() {
+        String::new()
+    } else {
+        std::ffi::CStr::from_ptr(cstr).to_string_lossy().to_string()
+    };
+    let flags = event.modifierFlags().bits();
+    let Some(chord) = KeyChord::from_event(event.keyCode(), &characters, flags) else {
+        return false;
+    };
+
+    let responder: id = msg_send![window, firstResponder];
+    let composing: bool = msg_send![responder, respondsToSelector: sel!(hasMarkedText)];
+    if composing {
+        let marked: bool = msg_send![responder, hasMarkedText];
+        if marked {
+            return false;
+        }
+    }
+
+    // Actions can edit the search field, whose change notification locks the
+    // data again, so they run on a copy once the lock is released
+    let data = {
+        let data_map = DELEGATE_DATA.lock().unwrap();
+        data_map.as_ref().and_then(|m| {
+            m.values()
+                .find(|data| {
+                    let field_window: id = msg_send![data.search_field.0, window];
+                    field_window == window
+                })
+                .cloned()
+        })
+    };
+    let Some(data) = data else {
+        return false;
+    };
+    let Some(action) = data.keymap.action(&chord) else {
+        return false;
+    };
+    perform_action(&data, action);
+    true
+}
+
+/// Carries out a bound action
+unsafe fn perform_action(data: &DelegateData, action: Action) {
+    let layout = GridLayout::new(&data.filtered.lock().unwrap(), GRID_COLUMNS as usize);
+    let selected_idx = *data.selected_index.lock().unwrap();
+    if let Some(index) = layout.navigate(action, selected_idx, visible_rows(data)) {
+        select_index(data, index);
+        return;
+    }
+
+    let selected = data.filtered.lock().unwrap().get(selected_idx).cloned();
+    match action {
+        Action::Accept => {
+            if let Some(result) = selected {
+                run_result(data, &result);
+            }
+        }
+        Action::AcceptAlt => {
+            if let Some(result) = selected.filter(|r| !r.alt_actions.is_empty()) {
+                show_menu(data, action_menu_results(&result));
+            }
+        }
+        Action::DeleteWord => {
+            let editor: id = msg_send![data.search_field.0, currentEditor];
+            if editor != nil {
+                let _: () = msg_send![editor, deleteWordBackward: nil];
+            }
+        }
+        Action::ModeNext | Action::ModePrev => {
+            // A snippet's inputs are abandoned, its query is not worth keeping
+            let prompting = data.snippet_prompt.lock().unwrap().take().is_some();
+            let text = if prompting { String::new() } else { search_text(data) };
+            let query = if data.locked_mode.lock().unwrap().is_some() {
+                text
+            } else {
+                detect_mode(&text, &data.config).1
+            };
+            let current = *data.search_mode.lock().unwrap();
+            let forward = action == Action::ModeNext;
+            lock_mode(data, cycle_mode(current, &data.config, forward), &query);
+        }
+        Action::Cancel => {
+            // Leave the action menu, or a snippet's inputs, before closing
+            let in_menu = *data.action_menu.lock().unwrap();
+            if in_menu {
+                refresh_results(data);
+                return;
+            }
+            let prompting = data.snippet_prompt.lock().unwrap().take().is_some();
+            if prompting {
+                show_mode(data, SearchMode::Snippets, "");
+                return;
+            }
+            let app = NSApp();
+            let _: () = msg_send![app, terminate: nil];
+        }
+        Action::Custom(n) => {
+            let template = data.config.keys.custom.get(n - 1);
+            if let (Some(template), Some(result)) = (template, selected) {
+                std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(custom_command(template, &result))
+                    .spawn()
+                    .ok();
+                let app = NSApp();
+                let _: () = msg_send![app, terminate: nil];
+            }
+        }
+        // Moving the selection is handled by the grid above
+        Action::SelectNext
+        | Action::SelectPrev
+        | Action::SelectDown
+        | Action::SelectUp
+        | Action::PageDown
+        | Action::PageUp
+        | Action::SelectFirst
+        | Action::SelectLast => {}
+        Action::AcceptVisible(n) => {
+            let visible = visible_results(data.results_view.0, &layout);
+            let result = visible
+                .get(n - 1)
+                .and_then(|index| data.filtered.lock().unwrap().get(*index).cloned());
+            if let Some(result) = result {
+                run_result(data, &result);
+            }
+        }
+    }
+}
+
+/// The scrolled-to part of the grid, as its distance from the grid's top
+/// and its height
+unsafe fn scroll_area(results_view: id) -> Option<(f64, f64)> {
+    let scroll_view: id = msg_send![results_view, enclosingScrollView];
+    if scroll_view == nil {
+        return None;
+    }
+    let clip_view: id = msg_send![scroll_view, contentView];
+    let bounds: NSRect = msg_send![clip_view, bounds];
+    let doc_frame: NSRect = msg_send![results_view, frame];
+    // The grid view is not flipped, its origin is at the bottom
+    let top = doc_frame.size.height - (bounds.origin.y + bounds.size.height);
+    Some((top.max(0.0), bounds.size.height))
+}
+
+/// Whole rows of cells that fit in the scroll view
+unsafe fn visible_rows(data: &DelegateData) -> usize {
+    match scroll_area(data.results_view.0) {
+        Some((_, height)) => ((height / (CELL_HEIGHT + CELL_SPACING)).floor() as usize).max(1),
+        None => 1,
+    }
+}
+
+/// The results wholly in view, top to bottom
+unsafe fn visible_results(results_view: id, layout: &GridLayout) -> Vec<usize> {
+    match scroll_area(results_view) {
+        Some((top, height)) => layout.visible(top, height, CELL_HEIGHT + CELL_SPACING, HEADER_HEIGHT),
+        None => Vec::new(),
+    }
+}
+
+/// Numbers the first nine results in view for Option/Cmd+1…9
+unsafe fn number_visible_cells(results_view: id, layout: &GridLayout) {
+    let visible = visible_results(results_view, layout);
+    let row_class = create_row_view_class();
+    let subviews: id = msg_send![results_view, subviews];
+    let count: usize = msg_send![subviews, count];
+    for i in 0..count {
+        let view: id = msg_send![subviews, objectAtIndex: i];
+        let is_row: bool = msg_send![view, isKindOfClass: row_class];
+        if !is_row {
+            continue;
+        }
+        let label: id = msg_send![view, viewWithTag: QUICK_NUMBER_TAG];
+        if label == nil {
+            continue;
+        }
+        let row_index: isize = *(&*view as &Object).get_ivar::<isize>("rowIndex");
+        let number = visible
+            .iter()
+            .position(|index| *index as isize == row_index)
+            .filter(|position| *position < 9)
+            .map(|position| (position + 1).to_string())
+            .unwrap_or_default();
+        let number_str = NSString::alloc(nil).init_str(&number);
+        let _: () = msg_send![label, setStringValue: number_str];
+    }
+}
+
+/// Moves the selection to `index` without rebuilding the grid, and scrolls
+/// it into view
+unsafe fn select_index(data: &DelegateData, index: usize) {
+    *data.selected_index.lock().unwrap() = index;
+
+    let results_view = data.results_view.0;
+    let row_class = create_row_view_class();
+    let subviews: id = msg_send![results_view, subviews];
+    let count: usize = msg_send![subviews, count];
+    for i in 0..count {
+        let view: id = msg_send![subviews, objectAtIndex: i];
+        let is_row: bool = msg_send![view, isKindOfClass: row_class];
+        if !is_row {
+            continue;
+        }
+        let row_index: isize = *(&*view as &Object).get_ivar::<isize>("rowIndex");
+        // Section headers are not results
+        if row_index < 0 {
+            continue;
+        }
+        let selected = row_index == index as isize;
+        style_cell(view, selected);
+        if selected {
+            let frame: NSRect = msg_send![view, frame];
+            let _: bool = msg_send![results_view, scrollRectToVisible: frame];
+        }
+    }
+
+    let layout = GridLayout::new(&data.filtered.lock().unwrap(), GRID_COLUMNS as usize);
+    number_visible_cells(results_view, &layout);
+}
+
+/// Replaces the grid with menu entries until the query changes or Escape
+unsafe fn show_menu(data: &DelegateData, menu: Vec<SearchResult>) {
+    *data.filtered.lock().unwrap() = menu.clone();
+    *data.selected_index.lock().unwrap() = 0;
+    *data.action_menu.lock().unwrap() = true;
+    rebuild_results_grid(data.results_view.0, &menu, 0, &data.config);
+}
+
+/// Performs a result's action, falling back to the mode's default (launch, open, run).
+/// Returns whether the launcher should close afterwards, or why the action failed.
+unsafe fn activate_result(result: &SearchResult) -> io::Result<bool> {
+    match &result.action {
+        ResultAction::Open => {}
+        ResultAction::Copy(text) => {
+            system_clipboard()?.set_text(text)?;
+            return Ok(true);
+        }
+        ResultAction::Paste(text) => {
+            paste_text(text)?;
+            return Ok(true);
+        }
+        ResultAction::ExpandSnippet(template, paste) => {
+            let text = expand_snippet(template);
+            if *paste {
+                paste_text(&text)?;
+            } else {
+                system_clipboard()?.set_text(&text)?;
+            }
+            return Ok(true);
+        }
+        ResultAction::Edit(path) => {
+            open_in_editor(&current_config().editor, path);
+            return Ok(true);
+        }
+        ResultAction::TerminalAt(dir) => {
+            open_terminal_at(&current_config().terminal, dir);
+            return Ok(true);
+        }
+        ResultAction::Reveal(path) => {
+            if std::path::Path::new(path).is_dir() {
+                open_path(path);
+            } else {
+                // Select the file in its enclosing folder
+                let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+                let path_string = NSString::alloc(nil).init_str(path);
+                let root = NSString::alloc(nil).init_str("");
+                let _: bool = msg_send![workspace, selectFile: path_string inFileViewerRootedAtPath: root];
+            }
+            return Ok(true);
+        }
+        ResultAction::Shell(command) => {
+            std::process::Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .spawn()
+                .ok();
+            return Ok(true);
+        }
+        ResultAction::TogglePin(_)
+        | ResultAction::DeleteClip(_)
+        | ResultAction::ClearClipHistory => {
+            // History edits keep the launcher open so the change is visible
+            apply_clipboard_action(&result.action);
+            return Ok(false);
+        }
+        ResultAction::AddNote(_) => {
+            // Capture and get out of the way
+            apply_note_action(&result.action, &current_config().notes);
+            return Ok(true);
+        }
+        ResultAction::CompleteNote(_) | ResultAction::DeleteNote(_) => {
+            apply_note_action(&result.action, &current_config().notes);
+            return Ok(false);
+        }
+        ResultAction::CopyPassword(entry) => {
+            copy_password(entry, &current_config().pass)?;
+            return Ok(true);
+        }
+        ResultAction::CopyPassOtp(entry) => {
+            copy_otp(entry, &current_config().pass)?;
+            return Ok(true);
+        }
+        ResultAction::CopyPassField(entry, field) => {
+            copy_pass_field(entry, field, &current_config().pass)?;
+            return Ok(true);
+        }
+        ResultAction::MoveToTrash(_)
+        | ResultAction::RestoreTrash(_)
+        | ResultAction::DeleteTrash(_)
+        | ResultAction::EmptyTrash => {
+            // Stay open so the refreshed list shows the change
+            apply_trash_action(&result.action)?;
+            return Ok(false);
+        }
+        ResultAction::FocusWindow(id) => {
+            focus_window(*id);
+            return Ok(true);
+        }
+        ResultAction::Kill(pid, force) => {
+            // Stay open so the refreshed list shows whether it is gone
+            send_signal(*pid, *force)?;
+            return Ok(false);
+        }
+        ResultAction::Confirm(..) => {
+            // Confirmation is shown by run_result before getting here
+            return Ok(false);
+        }
+    }
+
+    match result.result_type {
+        SearchMode::Apps | SearchMode::Files => {
+            // Launch application or open file using NSWorkspace
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let path_string = NSString::alloc(nil).init_str(&result.path);
+
+            // Use launchApplication for apps, openURL for other files
+            if result.result_type == SearchMode::Apps {
+                let _: bool = msg_send![workspace, launchApplication: path_string];
+            } else {
+                open_path(&result.path);
+            }
+        }
+        SearchMode::Run => {
+            // Execute system command
+            std::process::Command::new("sh")
+                .arg("-c")
+                .arg(&result.path) // path contains the command
+                .spawn()
+                .ok();
+        }
+        SearchMode::Projects => {
+            open_in_editor(&current_config().editor, &result.path);
+        }
+        SearchMode::Ssh | SearchMode::Tmux | SearchMode::Man => {
+            run_in_terminal(&current_config().terminal, &result.path);
+        }
+        SearchMode::Workspaces | SearchMode::Notes => {
+            open_path(&result.path);
+        }
+        SearchMode::Bookmarks | SearchMode::Web | SearchMode::Contacts => {
+            open_url(&result.path);
+        }
+        SearchMode::Processes
+        | SearchMode::Snippets
+        | SearchMode::Pass
+        | SearchMode::Trash
+        | SearchMode::Windows
+        | SearchMode::Combi => {}
+        SearchMode::Calc | SearchMode::Date | SearchMode::Clipboard | SearchMode::Emoji => {
+            system_clipboard()?.set_text(&result.path)?;
+        }
+    }
+    Ok(true)
+}
+
+/// Copies text and pastes it into the app that was frontmost before us
+fn paste_text(text: &str) -> io::Result<()> {
+    system_clipboard()?.set_text(text)?;
+    // Send Cmd+V once our window is gone and the previous app has focus again
+    std::process::Command::new("sh")
+        .arg("-c")
+        .arg("sleep 0.3; osascript -e 'tell application \"System Events\" to keystroke \"v\" using command down'")
+        .spawn()
+        .ok();
+    Ok(())
+}
+
+/// Usage key for modes that rank results by frecency
+fn frecency_key(result: &SearchResult) -> Option<String> {
+    match result.result_type {
+        SearchMode::Ssh => Some(ssh_frecency_key(&result.path)),
+        SearchMode::Workspaces => Some(workspace_frecency_key(
+            result.hint.as_deref().unwrap_or_default(),
+            &result.path,
+        )),
+        // Other modes have no ranking; secrets in Pass stay out of every store
+        _ => None,
+    }
+}
+
+/// The loaded config, for actions that need settings beyond colors
+fn current_config() -> Config {
+    let config_guard = CONFIG_DATA.lock().unwrap();
+    config_guard.clone().unwrap_or_else(Config::theme_gruvbox)
+}
+
+/// Opens a file with its default app, or a directory in Finder
+unsafe fn open_path(path: &str) {
+    let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+    let path_string = NSString::alloc(nil).init_str(path);
+    let url: id = msg_send![class!(NSURL), fileURLWithPath: path_string];
+    let _: bool = msg_send![workspace, openURL: url];
+}
+
+/// Opens a URL in the default browser
+unsafe fn open_url(url: &str) {
+    let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+    let url_string = NSString::alloc(nil).init_str(url);
+    let url: id = msg_send![class!(NSURL), URLWithString: url_string];
+    if url != nil {
+        let _: bool = msg_send![workspace, openURL: url];
+    }
+}
+
+/// Turns a result's secondary actions into grid entries
+fn action_menu_results(result: &SearchResult) -> Vec<SearchResult> {
+    result
+        .alt_actions
+        .iter()
+        .map(|(label, action)| {
+            let mut item =
+                SearchResult::new(label.clone(), result.path.clone(), result.result_type)
+                    .with_action(action.clone());
+            item.hint = result.hint.clone();
+            item
+        })
+        .collect()
+}
+
+/// Adds the muted second line under a cell's name
+unsafe fn add_hint_label(cell_view: id, frame: NSRect, text: &str) {
+    let hint_label: id = msg_send![class!(NSTextField), alloc];
+    let hint_label: id = msg_send![hint_label, initWithFrame: frame];
+    let _: () = msg_send![hint_label, setEditable: 0u32];
+    let _: () = msg_send![hint_label, setSelectable: 0u32];
+    let _: () = msg_send![hint_label, setBordered: 0u32];
+    let _: () = msg_send![hint_label, setDrawsBackground: 0u32];
+    let _: () = msg_send![hint_label, setAlignment: 1i64];
+    let hint_color = Config::hex_to_nscolor("#665c54"); // Muted
+    let _: () = msg_send![hint_label, setTextColor: hint_color];
+    let hint_font: id = msg_send![class!(NSFont), systemFontOfSize:12.0f64 weight:0.0f64];
+    let _: () = msg_send![hint_label, setFont: hint_font];
+    let hint_str = NSString::alloc(nil).init_str(text);
+    let _: () = msg_send![hint_label, setStringValue: hint_str];
+    let _: () = msg_send![hint_label, setLineBreakMode: 4i64];
+    let _: () = msg_send![cell_view, addSubview: hint_label];
+}
+
+/// A section title spanning the grid. It is a row view so the selection code
+/// walking the grid's subviews can read its index, which matches no result.
+unsafe fn add_section_header(
+    results_view: id,
+    row_class: *const Class,
+    y_pos: f64,
+    width: f64,
+    title: &str,
+    config: &Config,
+) {
+    let header_frame = NSRect::new(NSPoint::new(0.0, y_pos), NSSize::new(width, HEADER_HEIGHT));
+    let header_view: id = msg_send![row_class, alloc];
+    let header_view: id = msg_send![header_view, initWithFrame: header_frame];
+    (*header_view).set_ivar("rowIndex", -1isize);
+
+    let label_frame = NSRect::new(NSPoint::new(4.0, 2.0), NSSize::new(width - 8.0, 18.0));
+    let label: id = msg_send![class!(NSTextField), alloc];
+    let label: id = msg_send![label, initWithFrame: label_frame];
+    let _: () = msg_send![label, setEditable: 0u32];
+    let _: () = msg_send![label, setSelectable: 0u32];
+    let _: () = msg_send![label, setBordered: 0u32];
+    let _: () = msg_send![label, setDrawsBackground: 0u32];
+    let text_color = Config::hex_to_nscolor(&config.colors.text);
+    let _: () = msg_send![label, setTextColor: text_color];
+    let font: id = msg_send![class!(NSFont), boldSystemFontOfSize: 12.0f64];
+    let _: () = msg_send![label, setFont: font];
+    let title_str = NSString::alloc(nil).init_str(&title.to_uppercase());
+    let _: () = msg_send![label, setStringValue: title_str];
+    let _: () = msg_send![header_view, addSubview: label];
+
+    let _: () = msg_send![results_view, addSubview: header_view];
+}
+
+/// Ultraclean: minimal styling, emphasis on selection
+unsafe fn style_cell(cell_view: id, selected: bool) {
+    let cell_layer: id = msg_send![cell_view, layer];
+    if selected {
+        // Selected: elevated card with glow
+        let selected_bg = Config::hex_to_nscolor("#32302f");
+        let cg_selected_bg: id = msg_send![selected_bg, CGColor];
+        let _: () = msg_send![cell_layer, setBackgroundColor: cg_selected_bg];
+
+        // Accent border
+        let accent = Config::hex_to_nscolor("#fabd2f"); // Gruvbox yellow
+        let cg_accent: id = msg_send![accent, CGColor];
+        let _: () = msg_send![cell_layer, setBorderColor: cg_accent];
+        let _: () = msg_send![cell_layer, setBorderWidth: 2.0f64];
+
+        // Glow shadow
+        let glow_color = Config::hex_to_nscolor("#fabd2f"); // Gruvbox yellow
+        let cg_glow: id = msg_send![glow_color, CGColor];
+        let _: () = msg_send![cell_layer, setShadowColor: cg_glow];
+        let _: () = msg_send![cell_layer, setShadowOpacity: 0.4f32];
+        let _: () = msg_send![cell_layer, setShadowRadius: 12.0f64];
+        let shadow_offset = CGSize { width: 0.0, height: 0.0 };
+        let _: () = msg_send![cell_layer, setShadowOffset: shadow_offset];
+    } else {
+        // Unselected: clean, no border, subtle bg
+        let cell_bg = Config::hex_to_nscolor("#252423");
+        let cg_cell_bg: id = msg_send![cell_bg, CGColor];
+        let _: () = msg_send![cell_layer, setBackgroundColor: cg_cell_bg];
+        let _: () = msg_send![cell_layer, setBorderWidth: 0.0f64];
+        let _: () = msg_send![cell_layer, setShadowOpacity: 0.0f32];
+    }
+
+    // Text color: bright when selected, muted otherwise
+    let label: id = msg_send![cell_view, viewWithTag: NAME_LABEL_TAG];
+    if label != nil {
+        let text_color = if selected {
+            Config::hex_to_nscolor("#fbf1c7") // Gruvbox light
+        } else {
+            Config::hex_to_nscolor("#a89984") // Gruvbox gray (brighter)
+        };
+        let _: () = msg_send![label, setTextColor: text_color];
+    }
+}
+
 /// Rebuilds the results grid view with the given filtered results
 /// This consolidates the duplicated grid rendering code from multiple locations
 unsafe fn rebuild_results_grid(
@@ -759,10 +1172,12 @@ unsafe fn rebuild_results_grid(
 
     let frame: NSRect = msg_send![results_view, frame];
 
-    // Resize results_view to fit all items in grid
-    let num_items = filtered.len();
-    let num_rows = ((num_items as f64) / GRID_COLUMNS).ceil();
-    let new_height = (num_rows * (CELL_HEIGHT + CELL_SPACING)).max(frame.size.height);
+    // Resize results_view to fit all items in grid, plus any section headers
+    let layout = GridLayout::new(filtered, GRID_COLUMNS as usize);
+    let num_rows = layout.rows() as f64;
+    let headers_height = layout.headers.len() as f64 * HEADER_HEIGHT;
+    let new_height =
+        (num_rows * (CELL_HEIGHT + CELL_SPACING) + headers_height).max(frame.size.height);
     let new_frame = NSRect::new(
         NSPoint::new(0.0, 0.0),
         NSSize::new(frame.size.width, new_height),
@@ -772,13 +1187,18 @@ unsafe fn rebuild_results_grid(
     let container_height = new_height;
     let row_class = create_row_view_class();
 
+    let row_height = CELL_HEIGHT + CELL_SPACING;
+    for (row, title) in &layout.headers {
+        let y_pos = container_height - layout.row_top(*row, row_height, HEADER_HEIGHT);
+        add_section_header(results_view, row_class, y_pos, frame.size.width, title, config);
+    }
+
     for (index, result) in filtered.iter().enumerate() {
         // Calculate grid position
-        let col = (index as f64) % GRID_COLUMNS;
-        let row = ((index as f64) / GRID_COLUMNS).floor();
+        let (row, col) = layout.cells[index];
 
-        let x_pos = col * (CELL_WIDTH + CELL_SPACING);
-        let y_pos = container_height - ((row + 1.0) * (CELL_HEIGHT + CELL_SPACING));
+        let x_pos = col as f64 * (CELL_WIDTH + CELL_SPACING);
+        let y_pos = container_height - layout.row_top(row, row_height, HEADER_HEIGHT) - row_height;
 
         // Create cell
         let cell_frame = NSRect::new(
@@ -795,36 +1215,6 @@ unsafe fn rebuild_results_grid(
         let _: () = msg_send![cell_layer, setCornerRadius: 14.0f64];
         let _: () = msg_send![cell_layer, setMasksToBounds: NO];
 
-        // Ultraclean: minimal styling, emphasis on selection
-        if index == selected_index {
-            // Selected: elevated card with glow
-            let selected_bg = Config::hex_to_nscolor("#32302f");
-            let cg_selected_bg: id = msg_send![selected_bg, CGColor];
-            let _: () = msg_send![cell_layer, setBackgroundColor: cg_selected_bg];
-
-            // Accent border
-            let accent = Config::hex_to_nscolor("#fabd2f"); // Gruvbox yellow
-            let cg_accent: id = msg_send![accent, CGColor];
-            let _: () = msg_send![cell_layer, setBorderColor: cg_accent];
-            let _: () = msg_send![cell_layer, setBorderWidth: 2.0f64];
-
-            // Glow shadow
-            let glow_color = Config::hex_to_nscolor("#fabd2f"); // Gruvbox yellow
-            let cg_glow: id = msg_send![glow_color, CGColor];
-            let _: () = msg_send![cell_layer, setShadowColor: cg_glow];
-            let _: () = msg_send![cell_layer, setShadowOpacity: 0.4f32];
-            let _: () = msg_send![cell_layer, setShadowRadius: 12.0f64];
-            let shadow_offset = CGSize { width: 0.0, height: 0.0 };
-            let _: () = msg_send![cell_layer, setShadowOffset: shadow_offset];
-        } else {
-            // Unselected: clean, no border, subtle bg
-            let cell_bg = Config::hex_to_nscolor("#252423");
-            let cg_cell_bg: id = msg_send![cell_bg, CGColor];
-            let _: () = msg_send![cell_layer, setBackgroundColor: cg_cell_bg];
-            let _: () = msg_send![cell_layer, setBorderWidth: 0.0f64];
-            let _: () = msg_send![cell_layer, setShadowOpacity: 0.0f32];
-        }
-
         // Icon centered
         let icon_x = (CELL_WIDTH - ICON_SIZE) / 2.0;
         let icon_y = CELL_HEIGHT - ICON_SIZE - 16.0;
@@ -834,7 +1224,12 @@ unsafe fn rebuild_results_grid(
         );
 
         let icon: id = match result.result_type {
-            SearchMode::Apps | SearchMode::Files => {
+            SearchMode::Apps
+            | SearchMode::Files
+            | SearchMode::Projects
+            | SearchMode::Workspaces
+            | SearchMode::Trash
+            | SearchMode::Combi => {
                 let path_str = NSString::alloc(nil).init_str(&result.path);
                 msg_send![workspace, iconForFile: path_str]
             }
@@ -843,18 +1238,95 @@ unsafe fn rebuild_results_grid(
                 let terminal_path = NSString::alloc(nil).init_str("/System/Applications/Utilities/Terminal.app");
                 msg_send![workspace, iconForFile: terminal_path]
             }
+            SearchMode::Calc => {
+                let calculator_path = NSString::alloc(nil).init_str("/System/Applications/Calculator.app");
+                msg_send![workspace, iconForFile: calculator_path]
+            }
+            SearchMode::Date => {
+                let calendar_path = NSString::alloc(nil).init_str("/System/Applications/Calendar.app");
+                msg_send![workspace, iconForFile: calendar_path]
+            }
+            SearchMode::Windows => {
+                let mission_control_path = NSString::alloc(nil).init_str("/System/Applications/Mission Control.app");
+                msg_send![workspace, iconForFile: mission_control_path]
+            }
+            SearchMode::Pass => {
+                let keychain_path = NSString::alloc(nil).init_str("/System/Applications/Utilities/Keychain Access.app");
+                msg_send![workspace, iconForFile: keychain_path]
+            }
+            SearchMode::Contacts => {
+                let contacts_path = NSString::alloc(nil).init_str("/System/Applications/Contacts.app");
+                msg_send![workspace, iconForFile: contacts_path]
+            }
+            SearchMode::Notes => {
+                let reminders_path = NSString::alloc(nil).init_str("/System/Applications/Reminders.app");
+                msg_send![workspace, iconForFile: reminders_path]
+            }
+            SearchMode::Clipboard | SearchMode::Snippets => {
+                let textedit_path = NSString::alloc(nil).init_str("/System/Applications/TextEdit.app");
+                msg_send![workspace, iconForFile: textedit_path]
+            }
+            SearchMode::Ssh | SearchMode::Tmux | SearchMode::Man => {
+                let terminal_path = NSString::alloc(nil).init_str("/System/Applications/Utilities/Terminal.app");
+                msg_send![workspace, iconForFile: terminal_path]
+            }
+            SearchMode::Bookmarks | SearchMode::Web => {
+                // Icon of the browser that would open the link
+                let url_str = NSString::alloc(nil).init_str(&result.path);
+                let url: id = msg_send![class!(NSURL), URLWithString: url_str];
+                let app_url: id = if url != nil {
+                    msg_send![workspace, URLForApplicationToOpenURL: url]
+                } else {
+                    nil
+                };
+                if app_url != nil {
+                    let app_path: id = msg_send![app_url, path];
+                    msg_send![workspace, iconForFile: app_path]
+                } else {
+                    let safari_path = NSString::alloc(nil).init_str("/Applications/Safari.app");
+                    msg_send![workspace, iconForFile: safari_path]
+                }
+            }
+            SearchMode::Processes => {
+                // App bundle icon for apps, Activity Monitor for everything else
+                let bundle = result
+                    .path
+                    .find(".app/")
+                    .map(|end| &result.path[..end + 4])
+                    .unwrap_or("/System/Applications/Utilities/Activity Monitor.app");
+                let bundle_path = NSString::alloc(nil).init_str(bundle);
+                msg_send![workspace, iconForFile: bundle_path]
+            }
+            SearchMode::Emoji => nil, // The character itself is drawn instead
         };
 
-        let icon_ns_size = NSSize::new(ICON_SIZE, ICON_SIZE);
-        let _: () = msg_send![icon, setSize: icon_ns_size];
-        let icon_view: id = msg_send![class!(NSImageView), alloc];
-        let icon_view: id = msg_send![icon_view, initWithFrame: icon_frame];
-        let _: () = msg_send![icon_view, setImage: icon];
-        let _: () = msg_send![icon_view, setImageScaling: 3i64];
-        let _: () = msg_send![cell_view, addSubview: icon_view];
+        if icon != nil {
+            let icon_ns_size = NSSize::new(ICON_SIZE, ICON_SIZE);
+            let _: () = msg_send![icon, setSize: icon_ns_size];
+            let icon_view: id = msg_send![class!(NSImageView), alloc];
+            let icon_view: id = msg_send![icon_view, initWithFrame: icon_frame];
+            let _: () = msg_send![icon_view, setImage: icon];
+            let _: () = msg_send![icon_view, setImageScaling: 3i64];
+            let _: () = msg_send![cell_view, addSubview: icon_view];
+        } else {
+            let glyph_view: id = msg_send![class!(NSTextField), alloc];
+            let glyph_view: id = msg_send![glyph_view, initWithFrame: icon_frame];
+            let _: () = msg_send![glyph_view, setEditable: 0u32];
+            let _: () = msg_send![glyph_view, setSelectable: 0u32];
+            let _: () = msg_send![glyph_view, setBordered: 0u32];
+            let _: () = msg_send![glyph_view, setDrawsBackground: 0u32];
+            let _: () = msg_send![glyph_view, setAlignment: 1i64];
+            let _: () = msg_send![glyph_view, setTextColor: normal_text];
+            let glyph_font: id = msg_send![class!(NSFont), systemFontOfSize: 48.0f64];
+            let _: () = msg_send![glyph_view, setFont: glyph_font];
+            let glyph_str = NSString::alloc(nil).init_str(&result.path);
+            let _: () = msg_send![glyph_view, setStringValue: glyph_str];
+            let _: () = msg_send![cell_view, addSubview: glyph_view];
+        }
 
         // Label position depends on whether we show path hint
-        let has_path_hint = result.result_type == SearchMode::Files;
+        let has_path_hint = result.hint.is_some()
+            || matches!(result.result_type, SearchMode::Files | SearchMode::Projects);
         let label_y = if has_path_hint { 16.0 } else { 6.0 };
 
         // Label - clean, readable
@@ -867,13 +1339,7 @@ unsafe fn rebuild_results_grid(
         let _: () = msg_send![label, setDrawsBackground: 0u32];
         let _: () = msg_send![label, setAlignment: 1i64];
 
-        // Text color: bright when selected, muted otherwise
-        let text_color = if index == selected_index {
-            Config::hex_to_nscolor("#fbf1c7") // Gruvbox light
-        } else {
-            Config::hex_to_nscolor("#a89984") // Gruvbox gray (brighter)
-        };
-        let _: () = msg_send![label, setTextColor: text_color];
+        let _: () = msg_send![label, setTag: NAME_LABEL_TAG];
 
         // Readable font size
         let font_cls = class!(NSFont);
@@ -885,8 +1351,11 @@ unsafe fn rebuild_results_grid(
 
         let _: () = msg_send![cell_view, addSubview: label];
 
-        // Path hint for files (below filename)
-        if has_path_hint {
+        // Hint below the name; files and projects show their folder
+        if let Some(truncated) = result.hint.clone().filter(|_| has_path_hint) {
+            let hint_frame = NSRect::new(NSPoint::new(4.0, 2.0), NSSize::new(CELL_WIDTH - 8.0, 14.0));
+            add_hint_label(cell_view, hint_frame, &truncated);
+        } else if has_path_hint {
             // Truncate path: ~/Documents/foo.txt -> ~/Doc...
             let path = &result.path;
             let home = std::env::var("HOME").unwrap_or_default();
@@ -907,23 +1376,28 @@ unsafe fn rebuild_results_grid(
             };
 
             let hint_frame = NSRect::new(NSPoint::new(4.0, 2.0), NSSize::new(CELL_WIDTH - 8.0, 14.0));
-            let hint_label: id = msg_send![class!(NSTextField), alloc];
-            let hint_label: id = msg_send![hint_label, initWithFrame: hint_frame];
-            let _: () = msg_send![hint_label, setEditable: 0u32];
-            let _: () = msg_send![hint_label, setSelectable: 0u32];
-            let _: () = msg_send![hint_label, setBordered: 0u32];
-            let _: () = msg_send![hint_label, setDrawsBackground: 0u32];
-            let _: () = msg_send![hint_label, setAlignment: 1i64];
-            let hint_color = Config::hex_to_nscolor("#665c54"); // Muted
-            let _: () = msg_send![hint_label, setTextColor: hint_color];
-            let hint_font: id = msg_send![font_cls, systemFontOfSize:12.0f64 weight:0.0f64];
-            let _: () = msg_send![hint_label, setFont: hint_font];
-            let hint_str = NSString::alloc(nil).init_str(&truncated);
-            let _: () = msg_send![hint_label, setStringValue: hint_str];
-            let _: () = msg_send![hint_label, setLineBreakMode: 4i64];
-            let _: () = msg_send![cell_view, addSubview: hint_label];
+            add_hint_label(cell_view, hint_frame, &truncated);
         }
 
+        // Quick-select number in the corner, filled in once the grid scrolls
+        let number_frame = NSRect::new(
+            NSPoint::new(8.0, CELL_HEIGHT - 22.0),
+            NSSize::new(16.0, 14.0),
+        );
+        let number_label: id = msg_send![class!(NSTextField), alloc];
+        let number_label: id = msg_send![number_label, initWithFrame: number_frame];
+        let _: () = msg_send![number_label, setEditable: 0u32];
+        let _: () = msg_send![number_label, setSelectable: 0u32];
+        let _: () = msg_send![number_label, setBordered: 0u32];
+        let _: () = msg_send![number_label, setDrawsBackground: 0u32];
+        let number_color = Config::hex_to_nscolor("#665c54"); // Muted
+        let _: () = msg_send![number_label, setTextColor: number_color];
+        let number_font: id = msg_send![class!(NSFont), monospacedDigitSystemFontOfSize:10.0f64 weight:0.0f64];
+        let _: () = msg_send![number_label, setFont: number_font];
+        let _: () = msg_send![number_label, setTag: QUICK_NUMBER_TAG];
+        let _: () = msg_send![cell_view, addSubview: number_label];
+
+        style_cell(cell_view, index == selected_index);
         let _: () = msg_send![results_view, addSubview: cell_view];
     }
 
@@ -939,6 +1413,7 @@ unsafe fn rebuild_results_grid(
         );
         let _: () = msg_send![results_view, scrollPoint: scroll_point];
     }
+    number_visible_cells(results_view, &layout);
 }
 
 pub struct RofiUI {
@@ -1165,7 +1640,11 @@ impl RofiUI {
             let _: () = msg_send![hints_label, setTextColor: hints_color];
             let hints_font: id = msg_send![class!(NSFont), systemFontOfSize: 11.0f64];
             let _: () = msg_send![hints_label, setFont: hints_font];
-            let hints_str = NSString::alloc(nil).init_str("enter · open   esc · close   / files   : run");
+            let hints = format!(
+                "enter · open   ⌥enter · actions   ⇧←→ · mode   esc · close   {}",
+                prefix_hints(&config)
+            );
+            let hints_str = NSString::alloc(nil).init_str(&hints);
             let _: () = msg_send![hints_label, setStringValue: hints_str];
             let _: () = msg_send![content_view, addSubview: hints_label];
 
@@ -1201,18 +1680,36 @@ impl RofiUI {
                     filtered: initial_filtered.clone(),
                     selected_index: Arc::new(Mutex::new(0)),
                     search_mode: search_mode.clone(),
-                    _search_field: SendId(search_field),
+                    locked_mode: Arc::new(Mutex::new(modes::start_mode(&config))),
+                    search_field: SendId(search_field),
+                    action_menu: Arc::new(Mutex::new(false)),
+                    snippet_prompt: Arc::new(Mutex::new(None)),
                     _pill_buttons: pill_buttons.clone(),
                     config: config.clone(),
                     prompt_label: SendId(prompt_label),
                     mode_badge: SendId(mode_badge),
+                    keymap: Keymap::new(&config.keys),
                 },
             );
+            // Starting anywhere but apps mode replaces the app list shown above
+            let starts_elsewhere = modes::start_mode(&config).is_some()
+                || modes::default_mode(&config) != SearchMode::Apps;
+            if let Some(data) = data_map.as_ref().and_then(|m| m.get(&delegate_ptr)) {
+                if starts_elsewhere {
+                    refresh_results(data);
+                }
+            }
             drop(data_map); // Release the lock
 
             // Set delegate on search field
             let _: () = msg_send![search_field, setDelegate: delegate];
 
+            // Keep the quick-select numbers on the cells in view while scrolling
+            let _: () = msg_send![clip_view, setPostsBoundsChangedNotifications: YES];
+            let center: id = msg_send![class!(NSNotificationCenter), defaultCenter];
+            let bounds_changed = NSString::alloc(nil).init_str("NSViewBoundsDidChangeNotification");
+            let _: () = msg_send![center, addObserver: delegate selector: sel!(gridDidScroll:) name: bounds_changed object: clip_view];
+
             // Force window to be key and make search field first responder
             let _: () = msg_send![window, makeKeyAndOrderFront: nil];
             let _: bool = msg_send![window, makeFirstResponder: search_field];
@@ -0,0 +1,453 @@
+use crate::calculator::{evaluate, format_number};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Mass,
+    Temperature,
+    Volume,
+    Data,
+    Time,
+    Speed,
+    Area,
+}
+
+/// Which metric prefixes a unit accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prefixes {
+    None,
+    Metric,
+    // Data sizes only scale upwards, but accept both SI (kB) and binary (KiB) prefixes
+    Data,
+}
+
+struct Unit {
+    names: &'static [&'static str], // First name is used for display
+    dimension: Dimension,
+    factor: f64, // Multiplier to the dimension's base unit
+    offset: f64, // Added after scaling (only temperatures)
+    prefixes: Prefixes,
+    power: i32, // Exponent applied to prefixes, e.g. 2 for km²
+}
+
+const fn unit(names: &'static [&'static str], dimension: Dimension, factor: f64) -> Unit {
+    Unit {
+        names,
+        dimension,
+        factor,
+        offset: 0.0,
+        prefixes: Prefixes::None,
+        power: 1,
+    }
+}
+
+const fn metric(
+    names: &'static [&'static str],
+    dimension: Dimension,
+    factor: f64,
+    power: i32,
+) -> Unit {
+    Unit {
+        names,
+        dimension,
+        factor,
+        offset: 0.0,
+        prefixes: Prefixes::Metric,
+        power,
+    }
+}
+
+const fn data(names: &'static [&'static str], factor: f64) -> Unit {
+    Unit {
+        names,
+        dimension: Dimension::Data,
+        factor,
+        offset: 0.0,
+        prefixes: Prefixes::Data,
+        power: 1,
+    }
+}
+
+const fn temperature(names: &'static [&'static str], factor: f64, offset: f64) -> Unit {
+    Unit {
+        names,
+        dimension: Dimension::Temperature,
+        factor,
+        offset,
+        prefixes: Prefixes::None,
+        power: 1,
+    }
+}
+
+// Base units: metre, gram, kelvin, litre, byte, second, metre/second, square metre
+static UNITS: &[Unit] = &[
+    // Length
+    metric(
+        &["m", "meter", "meters", "metre", "metres"],
+        Dimension::Length,
+        1.0,
+        1,
+    ),
+    unit(&["in", "inch", "inches", "\""], Dimension::Length, 0.0254),
+    unit(&["ft", "foot", "feet", "'"], Dimension::Length, 0.3048),
+    unit(&["yd", "yard", "yards"], Dimension::Length, 0.9144),
+    unit(&["mi", "mile", "miles"], Dimension::Length, 1609.344),
+    unit(
+        &["nmi", "nauticalmile", "nauticalmiles"],
+        Dimension::Length,
+        1852.0,
+    ),
+    unit(&["au"], Dimension::Length, 149_597_870_700.0),
+    unit(
+        &["ly", "lightyear", "lightyears"],
+        Dimension::Length,
+        9_460_730_472_580_800.0,
+    ),
+    // Mass
+    metric(
+        &["g", "gram", "grams", "gramme", "grammes"],
+        Dimension::Mass,
+        1.0,
+        1,
+    ),
+    unit(&["t", "tonne", "tonnes"], Dimension::Mass, 1_000_000.0),
+    unit(&["oz", "ounce", "ounces"], Dimension::Mass, 28.349_523_125),
+    unit(
+        &["lb", "lbs", "pound", "pounds"],
+        Dimension::Mass,
+        453.592_37,
+    ),
+    unit(&["st", "stone", "stones"], Dimension::Mass, 6_350.293_18),
+    // Temperature
+    temperature(&["K", "kelvin", "k"], 1.0, 0.0),
+    temperature(&["°C", "C", "c", "celsius"], 1.0, 273.15),
+    temperature(
+        &["°F", "F", "f", "fahrenheit"],
+        5.0 / 9.0,
+        459.67 * 5.0 / 9.0,
+    ),
+    temperature(&["°R", "R", "rankine"], 5.0 / 9.0, 0.0),
+    // Volume
+    metric(
+        &["L", "l", "liter", "liters", "litre", "litres"],
+        Dimension::Volume,
+        1.0,
+        1,
+    ),
+    metric(&["m³", "m3"], Dimension::Volume, 1000.0, 3),
+    unit(&["cc"], Dimension::Volume, 0.001),
+    unit(
+        &["gal", "gallon", "gallons"],
+        Dimension::Volume,
+        3.785_411_784,
+    ),
+    unit(&["qt", "quart", "quarts"], Dimension::Volume, 0.946_352_946),
+    unit(&["pt", "pint", "pints"], Dimension::Volume, 0.473_176_473),
+    unit(&["cup", "cups"], Dimension::Volume, 0.236_588_236_5),
+    unit(&["floz", "fl_oz"], Dimension::Volume, 0.029_573_529_562_5),
+    unit(
+        &["tbsp", "tablespoon", "tablespoons"],
+        Dimension::Volume,
+        0.014_786_764_781_25,
+    ),
+    unit(
+        &["tsp", "teaspoon", "teaspoons"],
+        Dimension::Volume,
+        0.004_928_921_593_75,
+    ),
+    // Data sizes
+    data(&["B", "byte", "bytes"], 1.0),
+    data(&["bit", "bits", "b"], 0.125),
+    // Time
+    metric(
+        &["s", "sec", "secs", "second", "seconds"],
+        Dimension::Time,
+        1.0,
+        1,
+    ),
+    unit(&["min", "mins", "minute", "minutes"], Dimension::Time, 60.0),
+    unit(
+        &["h", "hr", "hrs", "hour", "hours"],
+        Dimension::Time,
+        3600.0,
+    ),
+    unit(&["d", "day", "days"], Dimension::Time, 86_400.0),
+    unit(&["wk", "week", "weeks"], Dimension::Time, 604_800.0),
+    unit(&["mo", "month", "months"], Dimension::Time, 2_629_746.0),
+    unit(&["yr", "year", "years"], Dimension::Time, 31_556_952.0),
+    // Speed
+    unit(&["m/s", "mps"], Dimension::Speed, 1.0),
+    unit(&["km/h", "kmh", "kph"], Dimension::Speed, 1000.0 / 3600.0),
+    unit(&["mph", "mi/h"], Dimension::Speed, 1609.344 / 3600.0),
+    unit(&["ft/s", "fps"], Dimension::Speed, 0.3048),
+    unit(
+        &["kn", "kt", "knot", "knots"],
+        Dimension::Speed,
+        1852.0 / 3600.0,
+    ),
+    // Area
+    metric(&["m²", "m2", "sqm"], Dimension::Area, 1.0, 2),
+    unit(&["ha", "hectare", "hectares"], Dimension::Area, 10_000.0),
+    unit(&["acre", "acres", "ac"], Dimension::Area, 4_046.856_422_4),
+    unit(&["ft²", "ft2", "sqft"], Dimension::Area, 0.092_903_04),
+    unit(&["in²", "in2", "sqin"], Dimension::Area, 0.000_645_16),
+    unit(&["yd²", "yd2", "sqyd"], Dimension::Area, 0.836_127_36),
+    unit(&["mi²", "mi2", "sqmi"], Dimension::Area, 2_589_988.110_336),
+];
+
+const METRIC_PREFIXES: &[(&str, f64)] = &[
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("da", 1e1),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("µ", 1e-6),
+    ("μ", 1e-6),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+];
+
+const DATA_PREFIXES: &[(&str, f64)] = &[
+    ("Ki", 1024.0),
+    ("Mi", 1_048_576.0),
+    ("Gi", 1_073_741_824.0),
+    ("Ti", 1_099_511_627_776.0),
+    ("Pi", 1_125_899_906_842_624.0),
+    ("k", 1e3),
+    ("K", 1e3),
+    ("M", 1e6),
+    ("G", 1e9),
+    ("T", 1e12),
+    ("P", 1e15),
+];
+
+/// A resolved unit: a table entry scaled by an optional prefix
+struct ResolvedUnit {
+    unit: &'static Unit,
+    scale: f64,
+    symbol: String,
+}
+
+impl ResolvedUnit {
+    fn base_value(&self, value: f64) -> f64 {
+        value * self.scale * self.unit.factor + self.unit.offset
+    }
+
+    fn unit_value(&self, value: f64) -> f64 {
+        (value - self.unit.offset) / (self.scale * self.unit.factor)
+    }
+}
+
+fn find_exact(name: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|u| u.names.contains(&name))
+}
+
+fn find_prefixed(name: &str) -> Option<ResolvedUnit> {
+    for unit in UNITS {
+        let prefixes = match unit.prefixes {
+            Prefixes::None => continue,
+            Prefixes::Metric => METRIC_PREFIXES,
+            Prefixes::Data => DATA_PREFIXES,
+        };
+        for (prefix, scale) in prefixes {
+            let Some(rest) = name.strip_prefix(prefix) else {
+                continue;
+            };
+            // Only the short symbol takes a prefix: "km" but not "kmeters"
+            if rest == unit.names[0]
+                || unit
+                    .names
+                    .iter()
+                    .any(|n| *n == rest && n.chars().count() <= 3)
+            {
+                return Some(ResolvedUnit {
+                    unit,
+                    scale: scale.powi(unit.power),
+                    symbol: name.to_string(),
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Maps sloppy data size spellings such as "mb" or "gib" to "MB" and "GiB".
+fn normalize_data_unit(name: &str) -> Option<String> {
+    let lower = name.to_lowercase();
+    let (prefix, binary) = match lower.strip_suffix("ib") {
+        Some(prefix) => (prefix, true),
+        None => (lower.strip_suffix('b')?, false),
+    };
+    if prefix.len() != 1 || !"kmgtp".contains(prefix) {
+        return None;
+    }
+    let infix = if binary { "i" } else { "" };
+    Some(format!("{}{}B", prefix.to_uppercase(), infix))
+}
+
+fn resolve(name: &str) -> Option<ResolvedUnit> {
+    let name = name.trim();
+    let name = name
+        .strip_prefix('°')
+        .map(|n| format!("°{}", n.to_uppercase()))
+        .unwrap_or_else(|| name.to_string());
+
+    if let Some(unit) = find_exact(&name) {
+        return Some(ResolvedUnit {
+            unit,
+            scale: 1.0,
+            symbol: unit.names[0].to_string(),
+        });
+    }
+    // Lowercase data sizes are bytes throughout: "kb" like "mb" and "gb",
+    // while "Kb" and "Mb" keep meaning bits
+    if name.chars().all(|c| !c.is_uppercase()) {
+        if let Some(resolved) = normalize_data_unit(&name).and_then(|n| find_prefixed(&n)) {
+            return Some(resolved);
+        }
+    }
+    if let Some(resolved) = find_prefixed(&name) {
+        return Some(resolved);
+    }
+
+    // Fall back to case-insensitive names ("Miles", "KM") and sloppy data sizes ("mb")
+    let lower = name.to_lowercase();
+    if let Some(unit) = UNITS
+        .iter()
+        .find(|u| u.names.iter().any(|n| n.to_lowercase() == lower))
+    {
+        return Some(ResolvedUnit {
+            unit,
+            scale: 1.0,
+            symbol: unit.names[0].to_string(),
+        });
+    }
+    if let Some(resolved) = find_prefixed(&lower) {
+        return Some(resolved);
+    }
+    normalize_data_unit(&name).and_then(|n| find_prefixed(&n))
+}
+
+/// Splits "5 km", "5km" or "2*3 ft" into the amount expression and the unit name.
+fn split_amount(input: &str) -> Option<(&str, &str)> {
+    let input = input.trim();
+    if let Some(pos) = input.rfind(char::is_whitespace) {
+        let (amount, unit) = input.split_at(pos);
+        return Some((amount.trim(), unit.trim()));
+    }
+
+    let unit_start = input.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '_'))?;
+    if unit_start == 0 {
+        return None;
+    }
+    Some(input.split_at(unit_start))
+}
+
+/// A successful unit conversion
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub value: String,
+    pub unit: String,
+}
+
+/// Converts queries like `5 km in mi`, `72 F to C` or `3.5 GiB in MB`.
+pub fn convert(query: &str) -> Option<Conversion> {
+    let lower = query.to_ascii_lowercase();
+    let (pos, keyword) = [" in ", " to ", " as "]
+        .iter()
+        .filter_map(|k| lower.rfind(k).map(|pos| (pos, *k)))
+        .max_by_key(|(pos, _)| *pos)?;
+
+    let (amount, from) = split_amount(&query[..pos])?;
+    let to = resolve(&query[pos + keyword.len()..])?;
+    let from = resolve(from)?;
+
+    if from.unit.dimension != to.unit.dimension {
+        return None;
+    }
+
+    let amount = evaluate(amount)?;
+    let value = to.unit_value(from.base_value(amount));
+    if !value.is_finite() {
+        return None;
+    }
+
+    Some(Conversion {
+        value: format_number(value),
+        unit: to.symbol,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversion(query: &str) -> Option<(String, String)> {
+        convert(query).map(|c| (c.value, c.unit))
+    }
+
+    fn assert_converts(query: &str, value: &str, unit: &str) {
+        assert_eq!(
+            conversion(query),
+            Some((value.to_string(), unit.to_string())),
+            "{}",
+            query
+        );
+    }
+
+    #[test]
+    fn lowercase_data_units_are_bytes() {
+        assert_converts("1 kb in b", "8000", "bit");
+        assert_converts("1 mb in kb", "1000", "KB");
+        assert_converts("1 gb in mb", "1000", "MB");
+        assert_converts("1 gib in mib", "1024", "MiB");
+        assert_converts("1 kb in B", "1000", "B");
+    }
+
+    #[test]
+    fn uppercase_data_units() {
+        assert_converts("3.5 GiB in MB", "3758.096384", "MB");
+        assert_converts("1 KiB in B", "1024", "B");
+        assert_converts("1 MB in Mb", "8", "Mb");
+        assert_converts("8 Kb in B", "1000", "B");
+        assert_converts("16 bits in bytes", "2", "B");
+    }
+
+    #[test]
+    fn metric_prefixes_and_imperial_units() {
+        assert_converts("5 km in mi", "3.10685596119", "mi");
+        assert_converts("12 in in cm", "30.48", "cm");
+        assert_converts("1 mi to ft", "5280", "ft");
+        assert_converts("2 lb in g", "907.18474", "g");
+        assert_converts("1 km² in ha", "100", "ha");
+        assert_converts("90 km/h in mph", "55.9234073014", "mph");
+    }
+
+    #[test]
+    fn temperatures() {
+        assert_converts("100 C in F", "212", "°F");
+        assert_converts("32 °f to °c", "0", "°C");
+        assert_converts("0 K in C", "-273.15", "°C");
+    }
+
+    #[test]
+    fn amounts_and_spellings() {
+        assert_converts("5km in m", "5000", "m");
+        assert_converts("2*3 ft in in", "72", "in");
+        assert_converts("1 Miles in KM", "1.609344", "km");
+        assert_converts("90 minutes to hours", "1.5", "h");
+    }
+
+    #[test]
+    fn rejects_mismatched_or_unknown_units() {
+        assert_eq!(conversion("5 km in kg"), None);
+        assert_eq!(conversion("5 parsecs in km"), None);
+        assert_eq!(conversion("open file in finder"), None);
+        assert_eq!(conversion("5 km"), None);
+    }
+}
@@ -0,0 +1,329 @@
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Contact {
+    pub name: String,
+    pub nickname: String,
+    pub org: String,
+    pub emails: Vec<String>,
+    pub phones: Vec<String>,
+}
+
+/// A content line split into its upper-cased name (group dropped), parameters
+/// and raw value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Property {
+    pub name: String,
+    pub params: Vec<String>,
+    pub value: String,
+}
+
+impl Property {
+    fn has_param(&self, wanted: &str) -> bool {
+        self.params.iter().any(|p| {
+            let p = p.to_ascii_uppercase();
+            p == wanted || p.ends_with(&format!("={}", wanted))
+        })
+    }
+}
+
+/// Joins folded lines: a line starting with a space or tab continues the one
+/// before it. vCard 2.1 quoted-printable values end a line with `=` instead.
+pub fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut soft_break = false;
+
+    for line in text.trim_start_matches('\u{feff}').lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match lines.last_mut() {
+            Some(last) if soft_break => last.push_str(line),
+            Some(last) if line.starts_with([' ', '\t']) => last.push_str(&line[1..]),
+            _ => lines.push(line.to_string()),
+        }
+        let last = lines.last().map(String::as_str).unwrap_or_default();
+        soft_break = last.ends_with('=')
+            && last
+                .split(':')
+                .next()
+                .is_some_and(|head| head.to_ascii_uppercase().contains("QUOTED-PRINTABLE"));
+        if soft_break {
+            if let Some(last) = lines.last_mut() {
+                last.pop();
+            }
+        }
+    }
+
+    lines
+}
+
+/// Splits `item1.EMAIL;TYPE=work:value`, allowing `:` and `;` inside quoted
+/// parameter values
+pub fn parse_property(line: &str) -> Option<Property> {
+    let mut quoted = false;
+    let mut split = None;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => {
+                split = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let (head, value) = line.split_at(split?);
+
+    let mut quoted = false;
+    let mut parts = head.split(|c| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == ';' && !quoted
+    });
+    let name = parts.next()?.trim();
+    let name = name.rsplit('.').next().unwrap_or(name).to_ascii_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+    Some(Property {
+        name,
+        params: parts.map(|p| p.trim().to_string()).collect(),
+        value: value[1..].to_string(),
+    })
+}
+
+/// Undoes `\n`, `\,`, `\;` and `\\`
+pub fn unescape(value: &str) -> String {
+    let mut out = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn decode_quoted_printable(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'=', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Components of a structured value such as N or ORG, unescaped
+fn components(value: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ';' => parts.push(unescape(&std::mem::take(&mut current))),
+            _ => current.push(c),
+        }
+    }
+    parts.push(unescape(&current));
+    parts.into_iter().map(|p| p.trim().to_string()).collect()
+}
+
+/// Display name from N (`Family;Given;Additional;Prefix;Suffix`)
+fn name_from_n(value: &str) -> String {
+    let parts = components(value);
+    let get = |i: usize| parts.get(i).map(String::as_str).unwrap_or_default();
+    [get(3), get(1), get(2), get(0), get(4)]
+        .iter()
+        .filter(|p| !p.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn finish(mut contact: Contact, n: Option<String>) -> Option<Contact> {
+    if contact.name.is_empty() {
+        contact.name = n
+            .filter(|n| !n.is_empty())
+            .or_else(|| Some(contact.org.clone()).filter(|o| !o.is_empty()))
+            .or_else(|| contact.emails.first().cloned())
+            .or_else(|| contact.phones.first().cloned())?;
+    }
+    Some(contact)
+}
+
+/// Every card in a .vcf file. Unknown properties are ignored and a card
+/// missing its END line still counts, since exports are often sloppy.
+pub fn parse_vcards(text: &str) -> Vec<Contact> {
+    let mut contacts = Vec::new();
+    let mut current: Option<(Contact, Option<String>)> = None;
+
+    for line in unfold(text) {
+        let Some(property) = parse_property(&line) else {
+            continue;
+        };
+        let value = if property.has_param("QUOTED-PRINTABLE") {
+            decode_quoted_printable(&property.value)
+        } else {
+            property.value.clone()
+        };
+
+        match property.name.as_str() {
+            "BEGIN" if value.trim().eq_ignore_ascii_case("VCARD") => {
+                if let Some((contact, n)) = current.take() {
+                    contacts.extend(finish(contact, n));
+                }
+                current = Some((Contact::default(), None));
+            }
+            "END" if value.trim().eq_ignore_ascii_case("VCARD") => {
+                if let Some((contact, n)) = current.take() {
+                    contacts.extend(finish(contact, n));
+                }
+            }
+            _ => {
+                let Some((contact, n)) = current.as_mut() else {
+                    continue;
+                };
+                match property.name.as_str() {
+                    "FN" => contact.name = unescape(value.trim()),
+                    "N" => *n = Some(name_from_n(&value)),
+                    "NICKNAME" => contact.nickname = unescape(value.trim()),
+                    "ORG" => {
+                        contact.org = components(&value)
+                            .into_iter()
+                            .filter(|p| !p.is_empty())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    }
+                    "EMAIL" => {
+                        let email = unescape(value.trim());
+                        let email = email.strip_prefix("mailto:").unwrap_or(&email);
+                        if !email.is_empty() && !contact.emails.iter().any(|e| e == email) {
+                            contact.emails.push(email.to_string());
+                        }
+                    }
+                    "TEL" => {
+                        let phone = unescape(value.trim());
+                        let phone = phone.strip_prefix("tel:").unwrap_or(&phone);
+                        if !phone.is_empty() && !contact.phones.iter().any(|p| p == phone) {
+                            contact.phones.push(phone.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if let Some((contact, n)) = current {
+        contacts.extend(finish(contact, n));
+    }
+    contacts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V3: &str = include_str!("../tests/fixtures/vcard/contacts-3.0.vcf");
+    const V4: &str = include_str!("../tests/fixtures/vcard/contacts-4.0.vcf");
+    const V21: &str = include_str!("../tests/fixtures/vcard/contacts-2.1.vcf");
+
+    #[test]
+    fn parses_apple_3_0_export() {
+        // Starts with a BOM and uses CRLF line ends
+        assert!(V3.starts_with('\u{feff}') && V3.contains("\r\n"));
+        let contacts = parse_vcards(V3);
+        assert_eq!(
+            contacts,
+            [
+                Contact {
+                    name: "Ada Lovelace".into(),
+                    nickname: "Ada".into(),
+                    org: "Analytical Engines, Ltd., Research".into(),
+                    // Grouped item1.EMAIL and a folded address; the repeat is dropped
+                    emails: vec![
+                        "ada@example.com".into(),
+                        "ada.lovelace@engines.example.com".into(),
+                    ],
+                    phones: vec!["+44 20 7946 0001".into(), "+44 20 7946 0002".into()],
+                },
+                // No FN or N: named after the organisation
+                Contact {
+                    name: "Acme Corp".into(),
+                    org: "Acme Corp".into(),
+                    phones: vec!["555-0100".into()],
+                    ..Contact::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_4_0_uris_and_missing_end() {
+        let contacts = parse_vcards(V4);
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(contacts[0].name, "Grace Hopper");
+        assert_eq!(
+            contacts[0].emails,
+            ["grace@navy.example.mil", "grace@example.org"]
+        );
+        assert_eq!(contacts[0].phones, ["+1-202-555-0173", "+1-202-555-0199"]);
+        // The last card has no END:VCARD and is named from N
+        assert_eq!(contacts[1].name, "Alan Turing");
+        assert_eq!(contacts[1].emails, ["alan@example.net"]);
+    }
+
+    #[test]
+    fn decodes_quoted_printable_2_1() {
+        let contacts = parse_vcards(V21);
+        assert_eq!(contacts[0].name, "Ayşe Öztürk");
+        assert_eq!(contacts[0].phones, ["+90 555 000 00 00"]);
+        assert_eq!(contacts[0].emails, ["ayse@example.com.tr"]);
+        // Bare QUOTED-PRINTABLE parameter, name built from N
+        assert_eq!(contacts[1].name, "Jürgen Müller");
+    }
+
+    #[test]
+    fn unfolds_continuation_lines() {
+        assert_eq!(
+            unfold("NOTE:one\r\n  two\r\n\t three\r\nFN:x"),
+            ["NOTE:one two three", "FN:x"]
+        );
+        // A trailing `=` only continues quoted-printable values
+        assert_eq!(unfold("NOTE:a=\nFN:b"), ["NOTE:a=", "FN:b"]);
+    }
+
+    #[test]
+    fn splits_groups_params_and_quoted_colons() {
+        let property = parse_property("item1.email;TYPE=\"a:b;c\":x@y.z").unwrap();
+        assert_eq!(property.name, "EMAIL");
+        assert_eq!(property.params, ["TYPE=\"a:b;c\""]);
+        assert_eq!(property.value, "x@y.z");
+        assert_eq!(parse_property("no colon here"), None);
+        assert_eq!(unescape(r"a\,b\;c\\d\ne"), "a,b;c\\d\ne");
+    }
+}
@@ -0,0 +1,222 @@
+use crate::bookmarks::domain;
+use crate::config::WebConfig;
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+
+// Top-level domains recognized without a scheme; anything else needs `https://`
+// so that file names like `notes.txt` are not taken for hosts
+const COMMON_TLDS: &[&str] = &[
+    "com", "org", "net", "edu", "gov", "io", "dev", "co", "info", "xyz", "gg", "tv", "fm", "to",
+    "ly", "page", "site", "tech", "blog", "cloud", "uk", "de", "fr", "nl", "es", "se", "no", "ch",
+    "at", "eu", "us", "ca", "au", "jp", "br",
+];
+
+// Domains that double as file extensions (`main.rs`, `Safari.app`). They only
+// count with a `www.` prefix or a path, as in `docs.rs/serde`.
+const EXTENSION_TLDS: &[&str] = &["rs", "sh", "in", "it", "me", "ai", "so", "app", "py", "pl"];
+
+/// Percent-encodes everything but RFC 3986 unreserved characters
+pub fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Fills `{query}` in a URL template, or appends the query if there is none
+pub fn expand_template(template: &str, query: &str) -> String {
+    let encoded = percent_encode(query.trim());
+    if template.contains("{query}") {
+        template.replace("{query}", &encoded)
+    } else {
+        format!("{}{}", template, encoded)
+    }
+}
+
+fn is_hostname(host: &str, has_path: bool) -> bool {
+    let labels: Vec<&str> = host.split('.').collect();
+    if labels.len() < 2 {
+        return false;
+    }
+
+    // Dotted IPv4 address
+    if labels.len() == 4 && labels.iter().all(|l| l.parse::<u8>().is_ok()) {
+        return true;
+    }
+
+    let valid_labels = labels.iter().all(|label| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    let tld = labels.last().unwrap_or(&"").to_lowercase();
+    let known = COMMON_TLDS.contains(&tld.as_str())
+        || (EXTENSION_TLDS.contains(&tld.as_str())
+            && (has_path || host.to_lowercase().starts_with("www.")));
+    valid_labels && known
+}
+
+/// Returns a full URL when the query looks like one: anything with a scheme,
+/// `localhost`, an IPv4 address or a domain, each with optional port and path
+pub fn normalize_url(query: &str) -> Option<String> {
+    let query = query.trim();
+    if query.is_empty() || query.contains(char::is_whitespace) {
+        return None;
+    }
+
+    if let Some((scheme, rest)) = query.split_once("://") {
+        let valid_scheme = scheme
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+        return (valid_scheme && !rest.is_empty()).then(|| query.to_string());
+    }
+
+    let authority = query.split(['/', '?', '#']).next().unwrap_or_default();
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    };
+    if port.is_some_and(|p| p.is_empty() || p.parse::<u16>().is_err()) {
+        return None;
+    }
+
+    if host.eq_ignore_ascii_case("localhost") {
+        return Some(format!("http://{}", query));
+    }
+    // `docs.rs/serde` has a path, `main.rs` and `main.rs:12` do not
+    let has_path = query[authority.len()..]
+        .strip_prefix('/')
+        .is_some_and(|path| !path.is_empty());
+    if !is_hostname(host, has_path) {
+        return None;
+    }
+    // Local addresses rarely have certificates
+    let ipv4 = host.split('.').all(|l| l.parse::<u8>().is_ok());
+    let scheme = if ipv4 { "http" } else { "https" };
+    Some(format!("{}://{}", scheme, query))
+}
+
+/// Splits `gh serde` into the keyword's template and the rest
+pub fn match_keyword<'a>(query: &'a str, config: &'a WebConfig) -> Option<(&'a str, &'a str)> {
+    let (keyword, rest) = query.trim_start().split_once(' ')?;
+    let keyword = keyword.to_lowercase();
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    config
+        .keywords
+        .get(&keyword)
+        .map(|template| (template.as_str(), rest))
+}
+
+fn url_result(name: String, url: String) -> SearchResult {
+    SearchResult::new(name, url.clone(), SearchMode::Web)
+        .with_alt_action("Copy URL", ResultAction::Copy(url))
+}
+
+/// "Search the web" with the configured default engine
+pub fn web_fallback(query: &str, config: &WebConfig) -> SearchResult {
+    let url = expand_template(&config.search_url, query);
+    url_result(format!("Search the web for \"{}\"", query.trim()), url)
+        .with_hint(&domain(&config.search_url))
+}
+
+/// URL and keyword results for a query, best first
+pub fn search_web(query: &str, config: &WebConfig) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+
+    if let Some(url) = normalize_url(query) {
+        results.push(url_result(format!("Open {}", url), url.clone()).with_hint(&domain(&url)));
+    }
+
+    if let Some((template, rest)) = match_keyword(query, config) {
+        let site = domain(template);
+        results.push(
+            url_result(
+                format!("Search {} for \"{}\"", site, rest),
+                expand_template(template, rest),
+            )
+            .with_hint(&site),
+        );
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_domains_and_addresses() {
+        assert_eq!(
+            normalize_url("github.com/rust-lang").as_deref(),
+            Some("https://github.com/rust-lang")
+        );
+        assert_eq!(
+            normalize_url("localhost:8080/api").as_deref(),
+            Some("http://localhost:8080/api")
+        );
+        assert_eq!(
+            normalize_url("192.168.1.1").as_deref(),
+            Some("http://192.168.1.1")
+        );
+        assert_eq!(
+            normalize_url("ftp://example.org").as_deref(),
+            Some("ftp://example.org")
+        );
+    }
+
+    #[test]
+    fn file_names_are_not_urls() {
+        for query in [
+            "main.rs",
+            "main.rs:12",
+            "install.sh",
+            "Safari.app",
+            "setup.py",
+            "notes.txt",
+            "docs.rs/",
+            "hello world.com",
+        ] {
+            assert_eq!(normalize_url(query), None, "{}", query);
+        }
+    }
+
+    #[test]
+    fn extension_domains_need_a_path_or_www() {
+        assert_eq!(
+            normalize_url("docs.rs/serde").as_deref(),
+            Some("https://docs.rs/serde")
+        );
+        assert_eq!(
+            normalize_url("www.example.it").as_deref(),
+            Some("https://www.example.it")
+        );
+        assert_eq!(normalize_url("example.it"), None);
+    }
+
+    #[test]
+    fn keywords_expand_with_percent_encoding() {
+        let config = WebConfig::default();
+        let (template, rest) = match_keyword("gh  serde json ", &config).unwrap();
+        assert_eq!(
+            expand_template(template, rest),
+            "https://github.com/search?q=serde%20json"
+        );
+        assert_eq!(match_keyword("gh ", &config), None);
+        assert_eq!(match_keyword("nope serde", &config), None);
+        assert_eq!(percent_encode("a&b=ü"), "a%26b%3D%C3%BC");
+    }
+}
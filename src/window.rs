@@ -1,5 +1,5 @@
 use crate::config::Config;
-use cocoa::appkit::{NSBackingStoreType, NSWindow, NSWindowStyleMask};
+use cocoa::appkit::{NSBackingStoreType, NSEvent, NSEventType, NSWindow, NSWindowStyleMask};
 use cocoa::base::{id, nil};
 use cocoa::foundation::{NSPoint, NSRect, NSSize};
 use objc::declare::ClassDecl;
@@ -22,6 +22,18 @@ fn create_borderless_window_class() -> *const Class {
             extern "C" fn can_become_main(_: &Object, _: Sel) -> u8 {
                 YES as u8
             }
+            // Bound keys are handled before the search field sees them
+            extern "C" fn send_event(this: &Object, _: Sel, event: id) {
+                unsafe {
+                    let window = this as *const Object as id;
+                    if event.eventType() == NSEventType::NSKeyDown
+                        && crate::ui::handle_key_down(window, event)
+                    {
+                        return;
+                    }
+                    let _: () = msg_send![super(this, class!(NSWindow)), sendEvent: event];
+                }
+            }
 
             unsafe {
                 decl.add_method(
@@ -32,6 +44,10 @@ fn create_borderless_window_class() -> *const Class {
                     sel!(canBecomeMainWindow),
                     can_become_main as extern "C" fn(&Object, Sel) -> u8,
                 );
+                decl.add_method(
+                    sel!(sendEvent:),
+                    send_event as extern "C" fn(&Object, Sel, id),
+                );
             }
 
             decl.register();
@@ -0,0 +1,142 @@
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+/// ARGB pixels, row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowIcon {
+    pub width: u32,
+    pub height: u32,
+    pub argb: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowInfo {
+    pub id: u64,
+    pub title: String,
+    pub class: String,        // Application class, e.g. "firefox"
+    pub desktop: Option<u32>, // None when shown on every desktop
+    pub icon: Option<WindowIcon>,
+}
+
+/// Lists and focuses the top-level windows of the session. X11 is covered by
+/// EWMH; other platforms can add a backend here.
+pub trait WindowManager {
+    /// Client windows, topmost first
+    fn list_windows(&self) -> Vec<WindowInfo>;
+    fn active_window(&self) -> Option<u64>;
+    /// Raises and focuses the window, switching desktops if needed
+    fn activate(&self, id: u64) -> bool;
+
+    fn desktop_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// The backend for this session, if there is one
+pub fn window_manager() -> Option<Box<dyn WindowManager>> {
+    #[cfg(target_os = "linux")]
+    {
+        crate::ewmh::EwmhWindowManager::connect(None)
+            .map(|wm| Box::new(wm) as Box<dyn WindowManager>)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Whether this session has a window backend; checked once, since it means
+/// connecting to the display
+pub fn has_window_manager() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| window_manager().is_some())
+}
+
+/// Moves the focused window to the end, so the first entry is the window
+/// used before it, as with Alt+Tab
+pub fn order_windows(mut windows: Vec<WindowInfo>, active: Option<u64>) -> Vec<WindowInfo> {
+    if let Some(index) = windows.iter().position(|w| Some(w.id) == active) {
+        let current = windows.remove(index);
+        windows.push(current);
+    }
+    windows
+}
+
+fn window_hint(window: &WindowInfo, desktop_names: &[String]) -> String {
+    let desktop = match window.desktop {
+        None => "all desktops".to_string(),
+        Some(n) => desktop_names
+            .get(n as usize)
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .unwrap_or_else(|| format!("desktop {}", n + 1)),
+    };
+    if window.class.is_empty() {
+        desktop
+    } else {
+        format!("{} · {}", window.class, desktop)
+    }
+}
+
+fn window_to_result(window: &WindowInfo, desktop_names: &[String]) -> SearchResult {
+    let name = if window.title.is_empty() {
+        window.class.clone()
+    } else {
+        window.title.clone()
+    };
+    SearchResult::new(name, format!("0x{:x}", window.id), SearchMode::Windows)
+        .with_action(ResultAction::FocusWindow(window.id))
+        .with_hint(&window_hint(window, desktop_names))
+}
+
+pub fn window_results(query: &str, wm: &dyn WindowManager) -> Vec<SearchResult> {
+    let windows = order_windows(wm.list_windows(), wm.active_window());
+    let desktop_names = wm.desktop_names();
+
+    if query.is_empty() {
+        return windows
+            .iter()
+            .take(15)
+            .map(|w| window_to_result(w, &desktop_names))
+            .collect();
+    }
+
+    let matcher = get_matcher();
+    let mut scored: Vec<(&WindowInfo, i64)> = windows
+        .iter()
+        .filter_map(|window| {
+            let title_score = matcher.fuzzy_match(&window.title, query);
+            let class_score = matcher.fuzzy_match(&window.class, query);
+            title_score.max(class_score).map(|score| (window, score))
+        })
+        .collect();
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(window, _)| window_to_result(window, &desktop_names))
+        .collect()
+}
+
+pub fn search_windows(query: &str) -> Vec<SearchResult> {
+    match window_manager() {
+        Some(wm) => window_results(query, wm.as_ref()),
+        None => Vec::new(),
+    }
+}
+
+pub fn focus_window(id: u64) {
+    if let Some(wm) = window_manager() {
+        wm.activate(id);
+    }
+}
@@ -0,0 +1,590 @@
+use crate::frecency::{frecency_path, now_secs, Frecency};
+use crate::search_mode::{ResultAction, SearchMode, SearchResult};
+use crate::sqlite::sqlite_json;
+use crate::terminal::shell_quote;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+
+fn get_matcher() -> &'static SkimMatcherV2 {
+    MATCHER.get_or_init(SkimMatcherV2::default)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Editor {
+    VsCode(String),    // App name: "Visual Studio Code", "VSCodium", "Cursor"...
+    JetBrains(String), // App name: "IntelliJ IDEA", "PyCharm"...
+    Zed,
+}
+
+impl Editor {
+    pub fn app_name(&self) -> &str {
+        match self {
+            Editor::VsCode(app) | Editor::JetBrains(app) => app,
+            Editor::Zed => "Zed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    pub paths: Vec<String>, // Zed workspaces can span several folders
+    pub editor: Editor,
+}
+
+impl Workspace {
+    pub fn name(&self) -> String {
+        self.paths
+            .iter()
+            .map(|p| {
+                Path::new(p)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| p.clone())
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Shell line reopening the workspace in its editor
+    pub fn open_command(&self) -> String {
+        let paths: Vec<String> = self.paths.iter().map(|p| shell_quote(p)).collect();
+        format!(
+            "open -a {} {}",
+            shell_quote(self.editor.app_name()),
+            paths.join(" ")
+        )
+    }
+}
+
+/// Decodes a `file://` URI into a local path. Other schemes (remote
+/// workspaces, untitled buffers) have no local path.
+pub fn file_uri_to_path(uri: &str) -> Option<String> {
+    let encoded = uri.strip_prefix("file://")?;
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Parses VS Code's recently opened list. Accepts both the whole legacy
+/// `storage.json` and the `history.recentlyOpenedPathsList` value from
+/// `state.vscdb`. Folders and `.code-workspace` files are kept; files are not.
+pub fn parse_vscode_recent(json: &str) -> Vec<String> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+    let list = value.get("openedPathsList").unwrap_or(&value);
+
+    let mut paths = Vec::new();
+    if let Some(entries) = list.get("entries").and_then(|e| e.as_array()) {
+        for entry in entries {
+            let uri = entry
+                .get("folderUri")
+                .or_else(|| entry.get("workspace").and_then(|w| w.get("configPath")))
+                .and_then(|u| u.as_str());
+            if let Some(path) = uri.and_then(file_uri_to_path) {
+                paths.push(path);
+            }
+        }
+    }
+    // Releases before 1.32 kept plain folder paths in workspaces3
+    if let Some(workspaces) = list.get("workspaces3").and_then(|w| w.as_array()) {
+        for item in workspaces {
+            let path = match item.as_str() {
+                Some(uri) if uri.starts_with("file://") => file_uri_to_path(uri),
+                Some(path) => Some(path.to_string()),
+                None => item
+                    .get("configURIPath")
+                    .and_then(|u| u.as_str())
+                    .and_then(file_uri_to_path),
+            };
+            if let Some(path) = path.filter(|p| !paths.contains(p)) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Value of `name="..."` inside a tag
+fn xml_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!(" {}=\"", name);
+    let start = tag.find(&needle)? + needle.len();
+    let end = tag[start..].find('"')? + start;
+    Some(&tag[start..end])
+}
+
+/// Parses a JetBrains `recentProjects.xml`, most recently opened first.
+/// `$USER_HOME$` is replaced with `home`.
+pub fn parse_jetbrains_recent(xml: &str, home: &str) -> Vec<String> {
+    let mut projects: Vec<(String, u64)> = Vec::new();
+
+    // Current format: <entry key="path"> with a RecentProjectMetaInfo holding timestamps
+    let mut rest = xml;
+    while let Some(start) = rest.find("<entry key=\"") {
+        let tag_end = rest[start..]
+            .find('>')
+            .map(|e| start + e)
+            .unwrap_or(rest.len());
+        let tag = &rest[start..tag_end];
+        let body_end = rest[tag_end..]
+            .find("</entry>")
+            .map(|e| tag_end + e)
+            .unwrap_or(rest.len());
+        let body = &rest[tag_end..body_end];
+
+        if let Some(key) = xml_attribute(tag, "key") {
+            // activationTimestamp is the last time the window was focused
+            let timestamp = body
+                .find("name=\"activationTimestamp\"")
+                .and_then(|i| xml_attribute(&body[i - 1..], "value"))
+                .or_else(|| xml_attribute(body, "projectOpenTimestamp"))
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(0);
+            projects.push((xml_unescape(key), timestamp));
+        }
+        rest = &rest[body_end..];
+    }
+
+    // Older format: <option name="recentPaths"><list><option value="path"/>
+    if let Some(start) = xml.find("name=\"recentPaths\"") {
+        let end = xml[start..]
+            .find("</list>")
+            .map(|e| start + e)
+            .unwrap_or(xml.len());
+        let mut list = &xml[start..end];
+        while let Some(i) = list.find("<option value=\"") {
+            let value_start = i + "<option value=\"".len();
+            let Some(len) = list[value_start..].find('"') else {
+                break;
+            };
+            let path = xml_unescape(&list[value_start..value_start + len]);
+            if !projects.iter().any(|(p, _)| *p == path) {
+                projects.push((path, 0));
+            }
+            list = &list[value_start + len..];
+        }
+    }
+
+    // Stable, so entries without timestamps keep file order
+    projects.sort_by_key(|(_, timestamp)| std::cmp::Reverse(*timestamp));
+    projects
+        .into_iter()
+        .map(|(path, _)| path.replace("$USER_HOME$", home))
+        .collect()
+}
+
+/// Decodes Zed's older `local_paths` blob (bincode `Vec<PathBuf>`), given as hex
+fn decode_zed_local_paths(hex: &str) -> Vec<String> {
+    let bytes: Vec<u8> = (0..hex.len() / 2)
+        .filter_map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok())
+        .collect();
+
+    let read_u64 = |at: usize| -> Option<usize> {
+        let chunk: [u8; 8] = bytes.get(at..at + 8)?.try_into().ok()?;
+        Some(u64::from_le_bytes(chunk) as usize)
+    };
+
+    let mut paths = Vec::new();
+    let Some(count) = read_u64(0) else {
+        return paths;
+    };
+    let mut at = 8;
+    for _ in 0..count {
+        let Some(len) = read_u64(at) else {
+            break;
+        };
+        let Some(path) = bytes.get(at + 8..at + 8 + len) else {
+            break;
+        };
+        paths.push(String::from_utf8_lossy(path).to_string());
+        at += 8 + len;
+    }
+    paths
+}
+
+/// Parses `sqlite3 -json` output of Zed's workspaces table, newest first. Rows
+/// have either `paths` (newline separated) or `local_paths` (hex blob).
+pub fn parse_zed_workspaces(json: &str) -> Vec<Vec<String>> {
+    let Ok(rows) = serde_json::from_str::<Vec<serde_json::Value>>(json) else {
+        return Vec::new();
+    };
+
+    let mut workspaces: Vec<(String, Vec<String>)> = rows
+        .iter()
+        .filter_map(|row| {
+            let paths: Vec<String> = match row.get("paths").and_then(|p| p.as_str()) {
+                Some(paths) => paths
+                    .lines()
+                    .filter(|p| !p.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                None => decode_zed_local_paths(row.get("local_paths")?.as_str()?),
+            };
+            let timestamp = row
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .unwrap_or_default()
+                .to_string();
+            (!paths.is_empty()).then_some((timestamp, paths))
+        })
+        .collect();
+
+    // Timestamps are "YYYY-MM-DD HH:MM:SS", so text order is time order
+    workspaces.sort_by(|a, b| b.0.cmp(&a.0));
+    workspaces.into_iter().map(|(_, paths)| paths).collect()
+}
+
+fn vscode_workspaces(config_dir: &Path) -> Vec<Vec<Workspace>> {
+    let flavors = [
+        ("Code", "Visual Studio Code"),
+        ("Code - Insiders", "Visual Studio Code - Insiders"),
+        ("VSCodium", "VSCodium"),
+        ("Cursor", "Cursor"),
+    ];
+
+    flavors
+        .iter()
+        .map(|(dir, app)| {
+            let storage = config_dir.join(dir).join("User").join("globalStorage");
+            let mut paths = sqlite_json(
+                &storage.join("state.vscdb"),
+                "SELECT value FROM ItemTable WHERE key = 'history.recentlyOpenedPathsList'",
+            )
+            .and_then(|json| serde_json::from_str::<Vec<serde_json::Value>>(&json).ok())
+            .and_then(|rows| {
+                rows.first()?
+                    .get("value")?
+                    .as_str()
+                    .map(parse_vscode_recent)
+            })
+            .unwrap_or_default();
+            if paths.is_empty() {
+                paths = fs::read_to_string(storage.join("storage.json"))
+                    .map(|json| parse_vscode_recent(&json))
+                    .unwrap_or_default();
+            }
+            paths
+                .into_iter()
+                .map(|path| Workspace {
+                    paths: vec![path],
+                    editor: Editor::VsCode(app.to_string()),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Maps a JetBrains config directory ("PyCharm2024.1") to its app name
+fn jetbrains_app(config_name: &str) -> Option<&'static str> {
+    let products = [
+        ("IntelliJIdea", "IntelliJ IDEA"),
+        ("IdeaIC", "IntelliJ IDEA CE"),
+        ("PyCharmCE", "PyCharm CE"),
+        ("PyCharm", "PyCharm"),
+        ("WebStorm", "WebStorm"),
+        ("GoLand", "GoLand"),
+        ("CLion", "CLion"),
+        ("RustRover", "RustRover"),
+        ("PhpStorm", "PhpStorm"),
+        ("Rider", "Rider"),
+        ("RubyMine", "RubyMine"),
+        ("DataGrip", "DataGrip"),
+        ("AndroidStudio", "Android Studio"),
+    ];
+    products
+        .iter()
+        .find(|(prefix, _)| {
+            config_name
+                .strip_prefix(prefix)
+                .is_some_and(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+        })
+        .map(|(_, app)| *app)
+}
+
+fn jetbrains_workspaces(config_dir: &Path, home: &str) -> Vec<Vec<Workspace>> {
+    let mut config_dirs: Vec<PathBuf> = ["JetBrains", "Google"]
+        .iter()
+        .filter_map(|vendor| fs::read_dir(config_dir.join(vendor)).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .collect();
+    // Newest version last, so it wins when deduplicating
+    config_dirs.sort();
+
+    let mut by_app: Vec<(String, Vec<Workspace>)> = Vec::new();
+    for dir in config_dirs {
+        let name = dir.file_name().unwrap_or_default().to_string_lossy();
+        let Some(app) = jetbrains_app(&name) else {
+            continue;
+        };
+        let Ok(xml) = fs::read_to_string(dir.join("options").join("recentProjects.xml")) else {
+            continue;
+        };
+        let workspaces = parse_jetbrains_recent(&xml, home)
+            .into_iter()
+            .map(|path| Workspace {
+                paths: vec![path],
+                editor: Editor::JetBrains(app.to_string()),
+            })
+            .collect();
+        by_app.retain(|(existing, _)| existing != app);
+        by_app.push((app.to_string(), workspaces));
+    }
+    by_app
+        .into_iter()
+        .map(|(_, workspaces)| workspaces)
+        .collect()
+}
+
+fn zed_workspaces() -> Vec<Workspace> {
+    // macOS keeps it under Application Support, Linux under the data dir
+    let candidates = [
+        dirs::config_dir().map(|d| d.join("Zed")),
+        dirs::data_dir().map(|d| d.join("zed")),
+    ];
+    let Some(db) = candidates
+        .into_iter()
+        .flatten()
+        .map(|d| d.join("db").join("0-stable").join("db.sqlite"))
+        .find(|db| db.exists())
+    else {
+        return Vec::new();
+    };
+
+    let json = sqlite_json(&db, "SELECT paths, timestamp FROM workspaces")
+        .or_else(|| {
+            sqlite_json(
+                &db,
+                "SELECT hex(local_paths) AS local_paths, timestamp FROM workspaces",
+            )
+        })
+        .unwrap_or_default();
+    parse_zed_workspaces(&json)
+        .into_iter()
+        .map(|paths| Workspace {
+            paths,
+            editor: Editor::Zed,
+        })
+        .collect()
+}
+
+/// Recent workspaces of all editors, interleaved so each editor's most
+/// recent entries come first
+pub fn load_workspaces() -> Vec<Workspace> {
+    let config_dir = dirs::config_dir().unwrap_or_default();
+    let home = dirs::home_dir()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let mut lists = vscode_workspaces(&config_dir);
+    lists.extend(jetbrains_workspaces(&config_dir, &home));
+    lists.push(zed_workspaces());
+
+    let longest = lists.iter().map(Vec::len).max().unwrap_or(0);
+    let mut workspaces = Vec::new();
+    for i in 0..longest {
+        for list in &lists {
+            if let Some(workspace) = list.get(i) {
+                // Skip folders that no longer exist
+                if workspace.paths.iter().all(|p| Path::new(p).exists()) {
+                    workspaces.push(workspace.clone());
+                }
+            }
+        }
+    }
+    workspaces
+}
+
+/// Key for an editor and the workspace's first folder
+pub fn frecency_key(app_name: &str, path: &str) -> String {
+    format!("workspace:{}:{}", app_name, path)
+}
+
+fn workspace_to_result(workspace: &Workspace) -> SearchResult {
+    let first = workspace.paths.first().cloned().unwrap_or_default();
+    SearchResult::new(workspace.name(), first.clone(), SearchMode::Workspaces)
+        .with_hint(workspace.editor.app_name())
+        .with_action(ResultAction::Shell(workspace.open_command()))
+        .with_alt_action(
+            "Open terminal here",
+            ResultAction::TerminalAt(first.clone()),
+        )
+        .with_alt_action("Open in Finder", ResultAction::Reveal(first.clone()))
+        .with_alt_action("Copy path", ResultAction::Copy(first))
+}
+
+pub fn search_workspaces(query: &str) -> Vec<SearchResult> {
+    let workspaces = load_workspaces();
+    let frecency = Frecency::load(&frecency_path());
+    let now = now_secs();
+    let matcher = get_matcher();
+
+    let mut scored: Vec<(&Workspace, i64)> = workspaces
+        .iter()
+        .filter_map(|workspace| {
+            let first = workspace
+                .paths
+                .first()
+                .map(String::as_str)
+                .unwrap_or_default();
+            let bonus = frecency.score(&frecency_key(workspace.editor.app_name(), first), now);
+            if query.is_empty() {
+                return Some((workspace, bonus));
+            }
+            let name_score = matcher.fuzzy_match(&workspace.name(), query).map(|s| s * 2);
+            let path_score = workspace
+                .paths
+                .iter()
+                .filter_map(|p| matcher.fuzzy_match(p, query))
+                .max();
+            name_score
+                .max(path_score)
+                .map(|score| (workspace, score + bonus))
+        })
+        .collect();
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(15)
+        .map(|(workspace, _)| workspace_to_result(workspace))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/workspaces");
+        fs::read_to_string(dir.join(name)).unwrap()
+    }
+
+    #[test]
+    fn decodes_file_uris() {
+        assert_eq!(
+            file_uri_to_path("file:///a/My%20Dir/caf%C3%A9").as_deref(),
+            Some("/a/My Dir/café")
+        );
+        // A stray percent is kept as is
+        assert_eq!(file_uri_to_path("file:///100%").as_deref(), Some("/100%"));
+        assert_eq!(file_uri_to_path("vscode-remote://box/a"), None);
+    }
+
+    #[test]
+    fn vscode_storage_json_keeps_local_folders_and_workspaces() {
+        assert_eq!(
+            parse_vscode_recent(&fixture("vscode-storage.json")),
+            [
+                "/Users/alice/src/rufi",
+                "/Users/alice/My Projects/site",
+                "/Users/alice/work/monorepo.code-workspace",
+                "/Users/alice/old-project",
+                "/Users/alice/legacy.code-workspace",
+            ]
+        );
+    }
+
+    #[test]
+    fn vscode_state_db_value() {
+        assert_eq!(
+            parse_vscode_recent(&fixture("vscode-state-value.json")),
+            ["/home/alice/src/kernel", "/home/alice/café"]
+        );
+        assert!(parse_vscode_recent("not json").is_empty());
+    }
+
+    #[test]
+    fn jetbrains_recent_projects_newest_first() {
+        assert_eq!(
+            parse_jetbrains_recent(&fixture("jetbrains-recentProjects.xml"), "/home/alice"),
+            [
+                "/home/alice/IdeaProjects/newest",
+                "/opt/src/R&D",
+                "/home/alice/IdeaProjects/older",
+            ]
+        );
+    }
+
+    #[test]
+    fn jetbrains_legacy_recent_paths_keep_file_order() {
+        assert_eq!(
+            parse_jetbrains_recent(&fixture("jetbrains-recentProjects-legacy.xml"), "/home/bob"),
+            [
+                "/home/bob/PycharmProjects/first",
+                "/home/bob/PycharmProjects/second",
+            ]
+        );
+    }
+
+    #[test]
+    fn jetbrains_config_dirs_map_to_apps() {
+        assert_eq!(jetbrains_app("PyCharm2024.1"), Some("PyCharm"));
+        assert_eq!(jetbrains_app("PyCharmCE2023.3"), Some("PyCharm CE"));
+        assert_eq!(jetbrains_app("IntelliJIdea2025.2"), Some("IntelliJ IDEA"));
+        assert_eq!(jetbrains_app("PyCharm-backup"), None);
+        assert_eq!(jetbrains_app("consentOptions"), None);
+    }
+
+    #[test]
+    fn zed_rows_with_paths_newest_first() {
+        assert_eq!(
+            parse_zed_workspaces(&fixture("zed-workspaces.json")),
+            [
+                vec!["/home/alice/src/front", "/home/alice/src/back"],
+                vec!["/home/alice/src/rufi"],
+            ]
+        );
+    }
+
+    #[test]
+    fn zed_rows_with_local_paths_blob() {
+        // The last row's blob is truncated and is dropped
+        assert_eq!(
+            parse_zed_workspaces(&fixture("zed-workspaces-local-paths.json")),
+            [
+                vec!["/Users/alice/a", "/Users/alice/b"],
+                vec!["/Users/alice/zed-old"],
+            ]
+        );
+    }
+
+    #[test]
+    fn multi_folder_workspace_opens_every_folder() {
+        let workspace = Workspace {
+            paths: vec!["/src/front".into(), "/src/my back".into()],
+            editor: Editor::Zed,
+        };
+        assert_eq!(workspace.name(), "front, my back");
+        assert_eq!(
+            workspace.open_command(),
+            "open -a Zed /src/front '/src/my back'"
+        );
+    }
+}